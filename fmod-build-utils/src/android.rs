@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! The files an Android app needs at runtime, beyond what gets linked into it.
+
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{LibDir, LinkedLib};
+
+/// The Java classes and native library an Android app needs for one FMOD API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndroidFiles {
+    /// The ABI the library is for, such as `arm64-v8a`.
+    pub abi: String,
+    /// `fmod.jar`, which only the Core API has.
+    pub jar: Option<Utf8PathBuf>,
+    /// The shared library, such as `libfmod.so`.
+    pub lib: Utf8PathBuf,
+}
+
+impl AndroidFiles {
+    /// Find the files that go with a library linked from `lib_dir`.
+    #[must_use]
+    pub fn find(lib_dir: &LibDir, linked: &LinkedLib, abi: &str) -> Self {
+        // The jar sits above the per-ABI directories in the SDK, and is looked for next to
+        // the library too, for flat layouts.
+        let jar_dirs = match lib_dir {
+            LibDir::Sdk(root) => vec![root.clone()],
+            LibDir::Flat(dir) => [Some(dir.as_path()), dir.parent()]
+                .into_iter()
+                .flatten()
+                .map(Utf8Path::to_path_buf)
+                .collect(),
+        };
+        Self {
+            abi: abi.to_owned(),
+            jar: jar_dirs
+                .into_iter()
+                .map(|dir| dir.join("fmod.jar"))
+                .find(|jar| jar.is_file()),
+            lib: linked.lib_dir.join(&linked.file_name),
+        }
+    }
+
+    /// Copy the files into `staging_dir`, laid out as `cargo-apk`'s `runtime_libs` and
+    /// xbuild expect: `<abi>/lib<name>.so`, with `fmod.jar` at the top.
+    ///
+    /// # Panics
+    /// Panics if any of the files can't be copied.
+    pub fn stage(&self, staging_dir: &Utf8Path) {
+        let abi_dir = staging_dir.join(&self.abi);
+        fs::create_dir_all(&abi_dir)
+            .unwrap_or_else(|err| panic!("couldn't create {abi_dir}: {err}"));
+        let copies = self
+            .jar
+            .iter()
+            .map(|jar| (jar, staging_dir.join("fmod.jar")))
+            .chain([(&self.lib, abi_dir.join(self.lib.file_name().unwrap()))]);
+        for (from, to) in copies {
+            fs::copy(from, &to).unwrap_or_else(|err| panic!("couldn't copy {from} to {to}: {err}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LinkKind;
+
+    fn touch(path: &Utf8Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, path.as_str()).unwrap();
+    }
+
+    fn linked(lib_dir: Utf8PathBuf, file_name: &str) -> LinkedLib {
+        LinkedLib {
+            lib_dir,
+            file_name: file_name.to_owned(),
+            kind: LinkKind::Dylib,
+        }
+    }
+
+    #[test]
+    fn find_and_stage() {
+        let root = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fmod-android-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        // The SDK layout, with the jar above the per-ABI directories.
+        let core_lib = root.join("sdk/api/core/lib");
+        touch(&core_lib.join("fmod.jar"));
+        touch(&core_lib.join("arm64-v8a/libfmod.so"));
+        let studio_lib = root.join("sdk/api/studio/lib");
+        touch(&studio_lib.join("arm64-v8a/libfmodstudio.so"));
+        let core = AndroidFiles::find(
+            &LibDir::Sdk(core_lib.clone()),
+            &linked(core_lib.join("arm64-v8a"), "libfmod.so"),
+            "arm64-v8a",
+        );
+        assert_eq!(
+            core,
+            AndroidFiles {
+                abi: "arm64-v8a".to_owned(),
+                jar: Some(core_lib.join("fmod.jar")),
+                lib: core_lib.join("arm64-v8a/libfmod.so"),
+            }
+        );
+        let studio = AndroidFiles::find(
+            &LibDir::Sdk(studio_lib.clone()),
+            &linked(studio_lib.join("arm64-v8a"), "libfmodstudio.so"),
+            "arm64-v8a",
+        );
+        assert_eq!(studio.jar, None);
+
+        // Flat layouts, with the jar next to the library or above it.
+        let flat = root.join("flat/arm64-v8a");
+        touch(&flat.join("libfmod.so"));
+        touch(&root.join("flat/fmod.jar"));
+        let found = AndroidFiles::find(
+            &LibDir::Flat(flat.clone()),
+            &linked(flat.clone(), "libfmod.so"),
+            "arm64-v8a",
+        );
+        assert_eq!(found.jar, Some(root.join("flat/fmod.jar")));
+        touch(&flat.join("fmod.jar"));
+        let found = AndroidFiles::find(
+            &LibDir::Flat(flat.clone()),
+            &linked(flat.clone(), "libfmod.so"),
+            "arm64-v8a",
+        );
+        assert_eq!(found.jar, Some(flat.join("fmod.jar")));
+
+        let staging = root.join("staging");
+        core.stage(&staging);
+        studio.stage(&staging);
+        let staged = |path: &str| fs::read_to_string(staging.join(path)).unwrap();
+        assert_eq!(staged("fmod.jar"), core_lib.join("fmod.jar"));
+        assert_eq!(
+            staged("arm64-v8a/libfmod.so"),
+            core_lib.join("arm64-v8a/libfmod.so")
+        );
+        assert_eq!(
+            staged("arm64-v8a/libfmodstudio.so"),
+            studio_lib.join("arm64-v8a/libfmodstudio.so")
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,530 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Diffs of the APIs two FMOD SDKs declare, for writing upgrade notes and checking SDK
+//! upgrades in CI.
+//!
+//! Each API is read from the bindings bindgen generates for it, so the diff is of what the
+//! sys crates would expose, rather than of the headers' text.
+
+use std::{collections::BTreeMap, fmt::Write, fs};
+
+use camino::Utf8Path;
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{Expr, Fields, ImplItem, Item, ReturnType, Type};
+
+use crate::{api_trait::foreign_functions, make_bindings_builders, Api, ApiPaths, FmodVersion};
+
+/// What one API declares, with each item rendered as it appears in the bindings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiSurface {
+    /// Functions, by name, with their signatures.
+    pub functions: BTreeMap<String, String>,
+    /// Enum variants, as `ENUM::VARIANT`, with their values.
+    pub enum_variants: BTreeMap<String, String>,
+    /// Constants, by name, with their values.
+    pub constants: BTreeMap<String, String>,
+    /// Struct fields, as `STRUCT.field`, with their types.
+    pub struct_fields: BTreeMap<String, String>,
+}
+
+impl ApiSurface {
+    /// Read the surface of an API from the bindings generated for it, as returned by
+    /// [`generate_bindings`](crate::generate_bindings).
+    ///
+    /// # Panics
+    /// Panics if the bindings aren't valid Rust.
+    #[must_use]
+    pub fn from_bindings(fns_only: &str, no_fns: &str) -> Self {
+        let fns_only = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+        let no_fns = syn::parse_file(no_fns).expect("bindgen should generate valid Rust");
+        let mut surface = Self::default();
+        for function in foreign_functions(&fns_only) {
+            let params = function
+                .sig
+                .inputs
+                .iter()
+                .map(|param| match param {
+                    syn::FnArg::Typed(param) => render(&param.ty),
+                    syn::FnArg::Receiver(_) => panic!("FMOD functions don't take self"),
+                })
+                .collect::<Vec<_>>();
+            let signature = match &function.sig.output {
+                ReturnType::Default => format!("fn({})", params.join(", ")),
+                ReturnType::Type(_, ty) => format!("fn({}) -> {}", params.join(", "), render(ty)),
+            };
+            surface
+                .functions
+                .insert(function.sig.ident.to_string(), signature);
+        }
+        for item in &no_fns.items {
+            match item {
+                Item::Const(item) => {
+                    surface
+                        .constants
+                        .insert(item.ident.to_string(), render(&item.expr));
+                }
+                // bindgen declares enum variants as constants of a newtype.
+                Item::Impl(item) if item.trait_.is_none() => {
+                    let Type::Path(ty) = &*item.self_ty else {
+                        continue;
+                    };
+                    let Some(ty) = ty.path.segments.last() else {
+                        continue;
+                    };
+                    for variant in item.items.iter().filter_map(|it| match it {
+                        ImplItem::Const(it) => Some(it),
+                        _ => None,
+                    }) {
+                        let value = match &variant.expr {
+                            Expr::Call(call) if call.args.len() == 1 => render(&call.args[0]),
+                            expr => render(expr),
+                        };
+                        surface
+                            .enum_variants
+                            .insert(format!("{}::{}", ty.ident, variant.ident), value);
+                    }
+                }
+                Item::Struct(item) => {
+                    surface.add_fields(&item.ident, &item.fields);
+                }
+                Item::Union(item) => {
+                    surface.add_fields(&item.ident, &Fields::Named(item.fields.clone()));
+                }
+                _ => {}
+            }
+        }
+        surface
+    }
+
+    fn add_fields(&mut self, ty: &syn::Ident, fields: &Fields) {
+        let Fields::Named(fields) = fields else {
+            return;
+        };
+        for field in &fields.named {
+            let Some(name) = &field.ident else {
+                continue;
+            };
+            // Opaque structs only have this placeholder.
+            if name == "_unused" {
+                continue;
+            }
+            self.struct_fields
+                .insert(format!("{ty}.{name}"), render(&field.ty));
+        }
+    }
+
+    /// Generate the bindings for `api` in the SDK at `sdk_dir`, and read its surface.
+    /// Returns `None` if the SDK doesn't have `api`, as FSBank is missing on Linux.
+    ///
+    /// # Panics
+    /// Panics if the bindings can't be generated.
+    #[must_use]
+    pub fn from_sdk(sdk_dir: &Utf8Path, api: Api) -> Option<Self> {
+        let include_dir = ApiPaths::in_sdk(sdk_dir, api).include_dir;
+        if !include_dir.is_dir() {
+            return None;
+        }
+        let core_include_dir = ApiPaths::in_sdk(sdk_dir, Api::Core).include_dir;
+        let extra_include_dirs = if api == Api::Studio {
+            vec![core_include_dir.as_path()]
+        } else {
+            Vec::new()
+        };
+
+        // The same headers the sys crates include in their `src/bindgen.h`.
+        let headers: &[&str] = match api {
+            Api::Core => &[
+                "fmod.h",
+                "fmod_codec.h",
+                "fmod_dsp.h",
+                "fmod_dsp_effects.h",
+                "fmod_errors.h",
+                "fmod_output.h",
+            ],
+            Api::Studio => &["fmod_studio.h"],
+            Api::Fsbank => &["fsbank.h", "fsbank_errors.h"],
+        };
+        let wrapper = std::env::temp_dir().join(format!(
+            "fmod-api-diff-{}-{}.h",
+            std::process::id(),
+            api.dir_name()
+        ));
+        let wrapper = camino::Utf8PathBuf::from_path_buf(wrapper)
+            .expect("the temporary directory should be UTF-8");
+        let includes = headers
+            .iter()
+            .map(|it| format!("#include <{it}>\n"))
+            .collect::<String>();
+        fs::write(&wrapper, includes).expect("Should be able to write the wrapper header");
+        let (fns_only, no_fns) =
+            make_bindings_builders(wrapper.as_str(), &include_dir, &extra_include_dirs);
+        let _ = fs::remove_file(&wrapper);
+        Some(Self::from_bindings(&fns_only, &no_fns))
+    }
+}
+
+/// Render `tokens` about the way they'd be written, with the `core::ffi` paths bindgen
+/// spells out taken off.
+fn render(tokens: &impl ToTokens) -> String {
+    let tokens: TokenStream = tokens.to_token_stream();
+    [
+        (":: core :: ffi :: ", ""),
+        (":: std :: os :: raw :: ", ""),
+        (" :: ", "::"),
+        (":: ", "::"),
+        ("* mut ", "*mut "),
+        ("* const ", "*const "),
+        (" ,", ","),
+        (" ;", ";"),
+        ("( ", "("),
+        (" )", ")"),
+        (" (", "("),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" < ", "<"),
+        (" >", ">"),
+    ]
+    .iter()
+    .fold(tokens.to_string(), |it, (from, to)| it.replace(from, to))
+}
+
+/// How one kind of item changed between two SDKs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// Items only in the new SDK, with how they're declared.
+    pub added: BTreeMap<String, String>,
+    /// Items only in the old SDK, with how they were declared.
+    pub removed: BTreeMap<String, String>,
+    /// Items declared differently, with the old declaration and the new.
+    pub changed: BTreeMap<String, (String, String)>,
+}
+
+impl Changes {
+    fn new(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Self {
+        let mut changes = Self::default();
+        for (name, old_value) in old {
+            match new.get(name) {
+                None => {
+                    changes.removed.insert(name.clone(), old_value.clone());
+                }
+                Some(new_value) if new_value != old_value => {
+                    changes
+                        .changed
+                        .insert(name.clone(), (old_value.clone(), new_value.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, new_value) in new {
+            if !old.contains_key(name) {
+                changes.added.insert(name.clone(), new_value.clone());
+            }
+        }
+        changes
+    }
+
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// How one API changed between two SDKs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiDiff {
+    pub functions: Changes,
+    pub enum_variants: Changes,
+    pub constants: Changes,
+    pub struct_fields: Changes,
+}
+
+impl ApiDiff {
+    /// Diff the surfaces of the same API in two SDKs.
+    #[must_use]
+    pub fn new(old: &ApiSurface, new: &ApiSurface) -> Self {
+        Self {
+            functions: Changes::new(&old.functions, &new.functions),
+            enum_variants: Changes::new(&old.enum_variants, &new.enum_variants),
+            constants: Changes::new(&old.constants, &new.constants),
+            struct_fields: Changes::new(&old.struct_fields, &new.struct_fields),
+        }
+    }
+
+    /// Whether code written against the old SDK could break against the new one: anything
+    /// was removed, or a function, enum variant or struct field changed. Constants, such as
+    /// `FMOD_VERSION`, are expected to change.
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        self.kinds().iter().any(|(_, it)| !it.removed.is_empty())
+            || !self.functions.changed.is_empty()
+            || !self.enum_variants.changed.is_empty()
+            || !self.struct_fields.changed.is_empty()
+    }
+
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.kinds().iter().all(|(_, it)| it.is_empty())
+    }
+
+    fn kinds(&self) -> [(&'static str, &Changes); 4] {
+        [
+            ("functions", &self.functions),
+            ("enum_variants", &self.enum_variants),
+            ("constants", &self.constants),
+            ("struct_fields", &self.struct_fields),
+        ]
+    }
+}
+
+/// How the APIs changed between two SDKs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdkDiff {
+    /// The version of the old SDK, if its headers say.
+    pub old_version: Option<FmodVersion>,
+    /// The version of the new SDK, if its headers say.
+    pub new_version: Option<FmodVersion>,
+    /// The diff of each API either SDK has.
+    pub apis: Vec<(Api, ApiDiff)>,
+}
+
+impl SdkDiff {
+    /// Generate the bindings of every API in the SDKs at `old` and `new`, and diff them. An
+    /// API only one of them has is diffed against nothing.
+    ///
+    /// # Panics
+    /// Panics if bindings can't be generated for an API that's present.
+    #[must_use]
+    pub fn between(old: &Utf8Path, new: &Utf8Path) -> Self {
+        let version =
+            |sdk_dir| FmodVersion::from_headers(&ApiPaths::in_sdk(sdk_dir, Api::Core).include_dir);
+        let apis = [Api::Core, Api::Studio, Api::Fsbank]
+            .into_iter()
+            .filter_map(|api| {
+                match (
+                    ApiSurface::from_sdk(old, api),
+                    ApiSurface::from_sdk(new, api),
+                ) {
+                    (None, None) => None,
+                    (old, new) => Some((
+                        api,
+                        ApiDiff::new(&old.unwrap_or_default(), &new.unwrap_or_default()),
+                    )),
+                }
+            })
+            .collect();
+        Self {
+            old_version: version(old),
+            new_version: version(new),
+            apis,
+        }
+    }
+
+    /// Whether any API changed in a way that could break code, as in
+    /// [`ApiDiff::is_breaking`].
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        self.apis.iter().any(|(_, it)| it.is_breaking())
+    }
+
+    fn versions(&self) -> (String, String) {
+        let version =
+            |it: Option<FmodVersion>| it.map_or_else(|| "unknown".to_owned(), |it| it.to_string());
+        (version(self.old_version), version(self.new_version))
+    }
+
+    /// The diff as a Markdown report, with a section for each API.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let (old, new) = self.versions();
+        let mut out = format!("# FMOD API changes from {old} to {new}\n");
+        for (api, diff) in &self.apis {
+            let title = match api {
+                Api::Core => "Core",
+                Api::Studio => "Studio",
+                Api::Fsbank => "FSBank",
+            };
+            let _ = write!(out, "\n## {title}\n");
+            if diff.is_empty() {
+                out.push_str("\nNo changes.\n");
+                continue;
+            }
+            for (kind, changes) in diff.kinds() {
+                if changes.is_empty() {
+                    continue;
+                }
+                let kind = kind.replace('_', " ");
+                let mut kind_chars = kind.chars();
+                let heading = kind_chars
+                    .next()
+                    .map(|it| it.to_uppercase().chain(kind_chars).collect::<String>())
+                    .unwrap_or_default();
+                let _ = write!(out, "\n### {heading}\n");
+                for (label, items) in [("Added", &changes.added), ("Removed", &changes.removed)] {
+                    if items.is_empty() {
+                        continue;
+                    }
+                    let _ = write!(out, "\n{label}:\n\n");
+                    for (name, value) in items {
+                        let _ = writeln!(out, "- `{name}`: `{value}`");
+                    }
+                }
+                if !changes.changed.is_empty() {
+                    out.push_str("\nChanged:\n\n");
+                    for (name, (old, new)) in &changes.changed {
+                        let _ = writeln!(out, "- `{name}`: `{old}` → `{new}`");
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// The diff as JSON, as in
+    /// `{"old_version": "2.02.20", "new_version": "2.02.22", "apis": {"core": {"functions":
+    /// {"added": {name: declaration}, "removed": {...}, "changed": {name: {"old": ...,
+    /// "new": ...}}}, ...}}}`. Unknown versions are `null`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let version = |it: Option<FmodVersion>| {
+            it.map_or_else(|| "null".to_owned(), |it| json_string(&it.to_string()))
+        };
+        let apis = self
+            .apis
+            .iter()
+            .map(|(api, diff)| {
+                let kinds = diff
+                    .kinds()
+                    .iter()
+                    .map(|(kind, changes)| {
+                        let map = |items: &BTreeMap<String, String>| {
+                            json_object(
+                                items.iter().map(|(name, value)| (name, json_string(value))),
+                            )
+                        };
+                        let changed =
+                            json_object(changes.changed.iter().map(|(name, (old, new))| {
+                                (
+                                    name,
+                                    json_object([
+                                        ("old", json_string(old)),
+                                        ("new", json_string(new)),
+                                    ]),
+                                )
+                            }));
+                        (
+                            *kind,
+                            json_object([
+                                ("added", map(&changes.added)),
+                                ("removed", map(&changes.removed)),
+                                ("changed", changed),
+                            ]),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                (api.dir_name(), json_object(kinds))
+            })
+            .collect::<Vec<_>>();
+        json_object([
+            ("old_version", version(self.old_version)),
+            ("new_version", version(self.new_version)),
+            ("apis", json_object(apis)),
+        ])
+    }
+}
+
+fn json_object<K: AsRef<str>>(entries: impl IntoIterator<Item = (K, String)>) -> String {
+    let entries = entries
+        .into_iter()
+        .map(|(key, value)| format!("{}:{value}", json_string(key.as_ref())))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", entries.join(","))
+}
+
+fn json_string(string: &str) -> String {
+    let mut out = String::with_capacity(string.len() + 2);
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        let old = ApiSurface::from_bindings(
+            r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint) -> FMOD_RESULT;
+                pub fn FMOD_System_Old(system: *mut FMOD_SYSTEM) -> FMOD_RESULT;
+            }
+            "#,
+            r"
+            pub const FMOD_VERSION: i32 = 131600;
+            impl FMOD_RESULT { pub const FMOD_OK: FMOD_RESULT = FMOD_RESULT(0); }
+            pub struct FMOD_SYSTEM { _unused: [u8; 0] }
+            pub struct FMOD_VECTOR { pub x: f32, pub y: f32 }
+            ",
+        );
+        let new = ApiSurface::from_bindings(
+            r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint, flags: FMOD_INITFLAGS) -> FMOD_RESULT;
+                pub fn FMOD_System_New(name: *const ::core::ffi::c_char);
+            }
+            "#,
+            r"
+            pub const FMOD_VERSION: i32 = 131606;
+            impl FMOD_RESULT { pub const FMOD_OK: FMOD_RESULT = FMOD_RESULT(0); pub const FMOD_ERR_NEW: FMOD_RESULT = FMOD_RESULT(82); }
+            pub struct FMOD_SYSTEM { _unused: [u8; 0] }
+            pub struct FMOD_VECTOR { pub x: f32, pub y: f32, pub z: f32 }
+            ",
+        );
+        let diff = ApiDiff::new(&old, &new);
+        assert_eq!(diff.functions.added["FMOD_System_New"], "fn(*const c_char)");
+        assert!(diff.functions.removed.contains_key("FMOD_System_Old"));
+        assert_eq!(
+            diff.functions.changed["FMOD_System_Create"].1,
+            "fn(*mut *mut FMOD_SYSTEM, c_uint, FMOD_INITFLAGS) -> FMOD_RESULT"
+        );
+        assert_eq!(diff.enum_variants.added["FMOD_RESULT::FMOD_ERR_NEW"], "82");
+        assert_eq!(
+            diff.constants.changed["FMOD_VERSION"],
+            ("131600".to_owned(), "131606".to_owned())
+        );
+        assert_eq!(diff.struct_fields.added["FMOD_VECTOR.z"], "f32");
+        assert!(diff.is_breaking());
+
+        let sdk = SdkDiff {
+            old_version: Some(FmodVersion::from_encoded(0x0002_0210)),
+            new_version: Some(FmodVersion::from_encoded(0x0002_0216)),
+            apis: vec![(Api::Core, diff), (Api::Studio, ApiDiff::default())],
+        };
+        let markdown = sdk.to_markdown();
+        assert!(markdown.starts_with("# FMOD API changes from 2.02.10 to 2.02.16\n"));
+        assert!(markdown
+            .contains("\n### Enum variants\n\nAdded:\n\n- `FMOD_RESULT::FMOD_ERR_NEW`: `82`\n"));
+        assert!(markdown.contains("- `FMOD_VERSION`: `131600` → `131606`\n"));
+        assert!(markdown.ends_with("## Studio\n\nNo changes.\n"));
+        let json = sdk.to_json();
+        assert!(json.starts_with(r#"{"old_version":"2.02.10","new_version":"2.02.16","apis":{"core":{"functions":{"added":{"FMOD_System_New":"fn(*const c_char)"}"#));
+        assert!(json.contains(r#""constants":{"added":{},"removed":{},"changed":{"FMOD_VERSION":{"old":"131600","new":"131606"}}}"#));
+    }
+}
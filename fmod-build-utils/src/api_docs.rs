@@ -0,0 +1,394 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Docs for the generated bindings, linking each item to the FMOD API reference.
+//!
+//! The SDK headers carry almost no comments, so the docs are derived from names instead.
+//! A function `FMOD_<Class>_<Method>` is documented on its class's page, as
+//! `<class>_<method>`, except for the methods channels and channel groups share, which are
+//! documented once for `ChannelControl`. Types are documented on the page of the header that
+//! declares them, under their own name. The reference is versioned by the SDK's major
+//! version, as in `https://www.fmod.com/docs/2.02/api/`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use camino::Utf8Path;
+use syn::{Attribute, FnArg, ForeignItem, Item, Type};
+
+use crate::{record_calls::type_name, shims::family, FmodVersion};
+
+/// The reference page documenting the types declared in each header.
+const HEADER_PAGES: &[(&str, &str)] = &[
+    ("fmod_common.h", "core-api-common.html"),
+    ("fmod_errors.h", "core-api-common.html"),
+    ("fmod_dsp_effects.h", "core-api-common-dsp-effects.html"),
+    ("fmod_dsp.h", "plugin-api-dsp.html"),
+    ("fmod_codec.h", "plugin-api-codec.html"),
+    ("fmod_output.h", "plugin-api-output.html"),
+    ("fmod_studio_common.h", "studio-api-common.html"),
+    ("fsbank.h", "fsbank-api.html"),
+];
+
+/// What's needed to document the bindings generated from some headers.
+pub(crate) struct ApiDocs {
+    version: Option<FmodVersion>,
+    /// The header each type is declared in.
+    headers: HashMap<String, String>,
+}
+
+impl ApiDocs {
+    /// Read the type declarations of the headers in `include_dirs`, and the SDK version from
+    /// whichever of them has `fmod_common.h`.
+    pub(crate) fn from_include_dirs(include_dirs: &[&Utf8Path]) -> Self {
+        let mut headers = HashMap::new();
+        for dir in include_dirs {
+            let Ok(entries) = dir.read_dir_utf8() else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension() != Some("h") {
+                    continue;
+                }
+                let Ok(text) = fs::read_to_string(path) else {
+                    continue;
+                };
+                for name in typedef_names(&text) {
+                    headers.insert(name, entry.file_name().to_owned());
+                }
+            }
+        }
+        Self {
+            version: include_dirs
+                .iter()
+                .find_map(|it| FmodVersion::from_headers(it)),
+            headers,
+        }
+    }
+
+    fn url(&self, page: &str, anchor: Option<&str>) -> Option<String> {
+        let version = self.version?;
+        let anchor = anchor.map(|it| format!("#{it}")).unwrap_or_default();
+        Some(format!(
+            "https://www.fmod.com/docs/{:x}.{:02x}/api/{page}{anchor}",
+            version.product, version.major
+        ))
+    }
+
+    /// Add docs to each function, struct, union and type alias in the bindings.
+    pub(crate) fn inject(&self, fns_only: &str, no_fns: &str) -> (String, String) {
+        let mut fns_file = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+        let mut types_file = syn::parse_file(no_fns).expect("bindgen should generate valid Rust");
+
+        let functions = fns_file
+            .items
+            .iter_mut()
+            .filter_map(|item| match item {
+                Item::ForeignMod(item) => Some(&mut item.items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                ForeignItem::Fn(function) => Some(function),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Families whose functions take a handle of their own, such as `Channel` taking
+        // `FMOD_CHANNEL`, which are classes in the reference.
+        let classes = functions
+            .iter()
+            .filter_map(|function| {
+                let family = family(&function.sig.ident.to_string());
+                let Some(FnArg::Typed(first)) = function.sig.inputs.first() else {
+                    return None;
+                };
+                let Type::Ptr(ptr) = &*first.ty else {
+                    return None;
+                };
+                (type_name(&ptr.elem)? == format!("FMOD_{}", family.to_uppercase()))
+                    .then_some(family)
+            })
+            .collect::<HashSet<_>>();
+        let names = functions
+            .iter()
+            .map(|it| it.sig.ident.to_string())
+            .collect::<HashSet<_>>();
+        for function in functions {
+            let name = function.sig.ident.to_string();
+            let docs = self.function_docs(&name, &classes, &names);
+            prepend_docs(&mut function.attrs, &docs);
+        }
+
+        for item in &mut types_file.items {
+            let (name, attrs) = match item {
+                Item::Struct(item) => (item.ident.to_string(), &mut item.attrs),
+                Item::Union(item) => (item.ident.to_string(), &mut item.attrs),
+                Item::Type(item) => (item.ident.to_string(), &mut item.attrs),
+                _ => continue,
+            };
+            let docs = self.type_docs(&name, &classes);
+            prepend_docs(attrs, &docs);
+        }
+
+        (
+            prettyplease::unparse(&fns_file),
+            prettyplease::unparse(&types_file),
+        )
+    }
+
+    fn function_docs(
+        &self,
+        name: &str,
+        classes: &HashSet<String>,
+        names: &HashSet<String>,
+    ) -> Vec<String> {
+        let family = family(name);
+        let Some(method) = name
+            .strip_prefix("FMOD_")
+            .unwrap_or(name)
+            .strip_prefix(&format!("{family}_"))
+        else {
+            return Vec::new();
+        };
+        if !classes.contains(&family) {
+            // Functions that aren't of a class are documented under their own name.
+            let page = if name.starts_with("FSBank_") {
+                "fsbank-api.html"
+            } else if family.starts_with("Studio") {
+                "studio-api-common.html"
+            } else {
+                "core-api-common.html"
+            };
+            return self
+                .url(page, Some(&name.to_lowercase()))
+                .map(|url| vec![format!(" [`{name}` in the FMOD API reference]({url})")])
+                .unwrap_or_default();
+        }
+
+        // Channels and channel groups share the methods of `ChannelControl`.
+        let shared = ["Channel", "ChannelGroup"].contains(&family.as_str())
+            && names.contains(&format!("FMOD_Channel_{method}"))
+            && names.contains(&format!("FMOD_ChannelGroup_{method}"));
+        let (api, class, page_prefix, anchor_prefix) = match family.strip_prefix("Studio_") {
+            Some(class) => ("Studio", class, "studio-api", "studio_"),
+            None if shared => ("Core", "ChannelControl", "core-api", ""),
+            None => ("Core", family.as_str(), "core-api", ""),
+        };
+        let mut lower_method = method.to_owned();
+        lower_method[..1].make_ascii_lowercase();
+        let cpp_name = if api == "Studio" {
+            format!("Studio::{class}::{lower_method}")
+        } else {
+            format!("{class}::{lower_method}")
+        };
+        let class_lower = class.to_lowercase();
+        let mut docs = vec![format!(" `{cpp_name}` of the FMOD {api} API.")];
+        if let Some(url) = self.url(
+            &format!("{page_prefix}-{class_lower}.html"),
+            Some(&format!(
+                "{anchor_prefix}{class_lower}_{}",
+                method.to_lowercase()
+            )),
+        ) {
+            docs.push(String::new());
+            docs.push(format!(" [FMOD API reference]({url})"));
+        }
+        docs
+    }
+
+    fn type_docs(&self, name: &str, classes: &HashSet<String>) -> Vec<String> {
+        // Handles are documented as their class.
+        let class = classes
+            .iter()
+            .find(|it| format!("FMOD_{}", it.to_uppercase()) == name);
+        if let Some(class) = class {
+            let (api, class, page) = match class.strip_prefix("Studio_") {
+                Some(class) => (
+                    "Studio",
+                    class,
+                    format!("studio-api-{}.html", class.to_lowercase()),
+                ),
+                None => (
+                    "Core",
+                    class.as_str(),
+                    format!("core-api-{}.html", class.to_lowercase()),
+                ),
+            };
+            let mut docs = vec![format!(" A handle to a `{class}` of the FMOD {api} API.")];
+            if let Some(url) = self.url(&page, None) {
+                docs.push(String::new());
+                docs.push(format!(" [FMOD API reference]({url})"));
+            }
+            return docs;
+        }
+
+        let Some(header) = self.headers.get(name) else {
+            return Vec::new();
+        };
+        let mut docs = vec![format!(" Declared in `{header}`.")];
+        let page = HEADER_PAGES
+            .iter()
+            .find(|(it, _)| it == header)
+            .map(|(_, page)| page);
+        if let Some(url) = page.and_then(|page| self.url(page, Some(&name.to_lowercase()))) {
+            docs.push(String::new());
+            docs.push(format!(" [FMOD API reference]({url})"));
+        }
+        docs
+    }
+}
+
+/// Put `docs` before any docs the item already has.
+fn prepend_docs(attrs: &mut Vec<Attribute>, docs: &[String]) {
+    if docs.is_empty() {
+        return;
+    }
+    let has_docs = attrs.iter().any(|it| it.path().is_ident("doc"));
+    let mut new_attrs = docs
+        .iter()
+        .map(|line| syn::parse_quote!(#[doc = #line]))
+        .collect::<Vec<Attribute>>();
+    if has_docs {
+        new_attrs.push(syn::parse_quote!(#[doc = ""]));
+    }
+    new_attrs.append(attrs);
+    *attrs = new_attrs;
+}
+
+/// The names of the types a header declares with `typedef`.
+fn typedef_names(header: &str) -> Vec<String> {
+    let header = strip_comments(header);
+    let mut names = Vec::new();
+    let mut rest = header.as_str();
+    while let Some(start) = rest.find("typedef") {
+        let declaration = &rest[start..];
+        // The declaration ends at the first `;` outside of braces.
+        let mut depth = 0_i32;
+        let end = declaration
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    ';' if depth == 0 => return true,
+                    _ => {}
+                }
+                false
+            })
+            .map_or(declaration.len(), |(index, _)| index);
+        let declaration = &declaration[..end];
+        rest = &rest[start + end..];
+
+        // Function pointers are named inside parentheses, as in `(F_CALL *NAME)(...)`.
+        let name = if let Some(open) = declaration.find('(').filter(|_| !declaration.contains('{'))
+        {
+            declaration[open + 1..]
+                .split(')')
+                .next()
+                .and_then(|it| it.rsplit('*').next())
+                .map(str::trim)
+        } else {
+            declaration
+                .rsplit(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .find(|it| !it.is_empty())
+        };
+        if let Some(name) = name.filter(|it| !it.is_empty()) {
+            names.push(name.to_owned());
+        }
+    }
+    names
+}
+
+fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let line = rest.find("//");
+        let block = rest.find("/*");
+        let (start, end_marker) = match (line, block) {
+            (Some(line), Some(block)) if line < block => (line, "\n"),
+            (Some(line), None) => (line, "\n"),
+            (_, Some(block)) => (block, "*/"),
+            (None, None) => {
+                out.push_str(rest);
+                return out;
+            }
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+        rest = rest
+            .find(end_marker)
+            .map_or("", |end| &rest[end + end_marker.len()..]);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typedefs() {
+        let header = r"
+            #ifdef __cplusplus
+            extern 'C' {
+            #endif
+            typedef struct FMOD_SYSTEM FMOD_SYSTEM; // A comment; with a semicolon.
+            typedef unsigned int FMOD_MODE;
+            /* A typedef in a comment. */
+            typedef enum FMOD_RESULT { FMOD_OK, FMOD_ERR_BADCOMMAND } FMOD_RESULT;
+            typedef struct FMOD_VECTOR { float x; float y; float z; } FMOD_VECTOR;
+            typedef FMOD_RESULT (F_CALL *FMOD_SYSTEM_CALLBACK)(FMOD_SYSTEM *system, void *userdata);
+        ";
+        assert_eq!(
+            typedef_names(header),
+            [
+                "FMOD_SYSTEM",
+                "FMOD_MODE",
+                "FMOD_RESULT",
+                "FMOD_VECTOR",
+                "FMOD_SYSTEM_CALLBACK"
+            ]
+        );
+    }
+
+    #[test]
+    fn docs() {
+        let docs = ApiDocs {
+            version: Some(FmodVersion::from_encoded(0x0002_0222)),
+            headers: HashMap::from([("FMOD_VECTOR".to_owned(), "fmod_common.h".to_owned())]),
+        };
+        let (fns_only, no_fns) = docs.inject(
+            r#"extern "C" {
+                pub fn FMOD_Channel_SetVolume(channel: *mut FMOD_CHANNEL, volume: f32) -> FMOD_RESULT;
+                pub fn FMOD_ChannelGroup_SetVolume(channelgroup: *mut FMOD_CHANNELGROUP, volume: f32) -> FMOD_RESULT;
+                pub fn FMOD_Channel_SetFrequency(channel: *mut FMOD_CHANNEL, frequency: f32) -> FMOD_RESULT;
+                pub fn FMOD_Studio_EventInstance_Get3DAttributes(eventinstance: *mut FMOD_STUDIO_EVENTINSTANCE, attributes: *mut FMOD_3D_ATTRIBUTES) -> FMOD_RESULT;
+                pub fn FMOD_Memory_Initialize(poolmem: *mut c_void) -> FMOD_RESULT;
+            }"#,
+            "pub struct FMOD_VECTOR { pub x: f32 } pub struct FMOD_CHANNEL { _unused: [u8; 0] }",
+        );
+        let base = "https://www.fmod.com/docs/2.02/api";
+        for expected in [
+            " `ChannelControl::setVolume` of the FMOD Core API.".to_owned(),
+            format!("{base}/core-api-channelcontrol.html#channelcontrol_setvolume)"),
+            " `Channel::setFrequency` of the FMOD Core API.".to_owned(),
+            format!("{base}/core-api-channel.html#channel_setfrequency)"),
+            " `Studio::EventInstance::get3DAttributes` of the FMOD Studio API.".to_owned(),
+            format!("{base}/studio-api-eventinstance.html#studio_eventinstance_get3dattributes)"),
+            format!("{base}/core-api-common.html#fmod_memory_initialize)"),
+        ] {
+            assert!(fns_only.contains(&expected), "{expected}");
+        }
+        assert!(no_fns.contains(&format!(
+            "/// Declared in `fmod_common.h`.\n///\n/// [FMOD API reference]({base}/core-api-common.html#fmod_vector)"
+        )));
+        assert!(no_fns.contains(&format!(
+            "/// A handle to a `Channel` of the FMOD Core API.\n///\n/// [FMOD API reference]({base}/core-api-channel.html)"
+        )));
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! A trait over every function of an API, so that the API can be injected, and mocked per
+//! instance rather than per process.
+
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use syn::{File, FnArg, ForeignItem, ForeignItemFn, Item, Pat};
+
+/// Generate a trait named `trait_name`, with an unsafe method for every function in
+/// `fns_only`, and a unit struct named `real_name` that implements it by calling the
+/// functions in the `functions` module.
+///
+/// The trait is mocked as `Mock<trait_name>` when the `mockall` feature is enabled.
+///
+/// # Panics
+/// Panics if `fns_only` isn't valid Rust.
+#[must_use]
+pub fn api_trait(fns_only: &str, trait_name: &str, real_name: &str) -> String {
+    let file = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+    let functions = foreign_functions(&file);
+
+    let declarations = functions.iter().map(|function| {
+        let ForeignItemFn { attrs, sig, .. } = function;
+        let docs = attrs.iter().filter(|it| it.path().is_ident("doc"));
+        let name = &sig.ident;
+        let params = &sig.inputs;
+        let output = &sig.output;
+        quote! {
+            #(#docs)*
+            unsafe fn #name(&self, #params) #output;
+        }
+    });
+    let forwards = functions.iter().map(|function| {
+        let sig = &function.sig;
+        let name = &sig.ident;
+        let params = &sig.inputs;
+        let output = &sig.output;
+        let args = sig.inputs.iter().map(|param| match param {
+            FnArg::Typed(param) => match &*param.pat {
+                Pat::Ident(ident) => &ident.ident,
+                _ => panic!("bindgen should only generate named parameters"),
+            },
+            FnArg::Receiver(_) => panic!("FMOD functions don't take self"),
+        });
+        quote! {
+            #[inline]
+            unsafe fn #name(&self, #params) #output {
+                functions::#name(#(#args),*)
+            }
+        }
+    });
+
+    let trait_name = Ident::new(trait_name, Span::call_site());
+    let real_name = Ident::new(real_name, Span::call_site());
+    let trait_doc = format!(
+        " Every function in [`functions`], as a method, so that the API can be passed in \
+        rather than called directly. [`{real_name}`] calls the real functions, and \
+        `Mock{trait_name}` is a mock with the `mockall` feature.\n\n # Safety\n Each method \
+        has the safety requirements of the function it's named after."
+    );
+    let real_doc = " The real API, calling the functions in [`functions`]. Its methods are \
+        inlined, so it costs nothing over calling them directly.";
+    let tokens = quote! {
+        #[doc = #trait_doc]
+        #[cfg_attr(feature = "mockall", automock)]
+        #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+        pub trait #trait_name {
+            #(#declarations)*
+        }
+
+        #[doc = #real_doc]
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct #real_name;
+
+        #[allow(clippy::too_many_arguments)]
+        impl #trait_name for #real_name {
+            #(#forwards)*
+        }
+    };
+    prettyplease::unparse(&syn::parse2(tokens).expect("the API trait should be valid Rust"))
+}
+
+/// The non-variadic functions declared in `file`'s `extern` blocks.
+pub(crate) fn foreign_functions(file: &File) -> Vec<&ForeignItemFn> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::ForeignMod(foreign) => Some(&foreign.items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            ForeignItem::Fn(function) if function.sig.variadic.is_none() => Some(function),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methods_forward() {
+        let fns_only = r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint) -> FMOD_RESULT;
+                pub fn FMOD_Studio_System_IsValid(system: *mut FMOD_STUDIO_SYSTEM) -> FMOD_BOOL;
+            }
+        "#;
+        let generated = api_trait(fns_only, "FmodApi", "RealFmod");
+        let generated = generated.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(generated.contains(
+            "unsafe fn FMOD_System_Create( &self, system: *mut *mut FMOD_SYSTEM, \
+            headerversion: ::core::ffi::c_uint, ) -> FMOD_RESULT;"
+        ));
+        assert!(generated.contains("impl FmodApi for RealFmod"));
+        assert!(generated.contains("functions::FMOD_Studio_System_IsValid(system)"));
+    }
+}
@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Which FMOD version introduced each item of the APIs, so that the bindings can say so, and
+//! crates supporting several SDK versions can compile their use of newer items only when
+//! the SDK has them.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use syn::{Attribute, Fields, ForeignItem, ImplItem, Item, Type};
+
+use crate::{ApiDiff, FmodVersion};
+
+/// The database kept in this crate, at `availability.txt`.
+const BUILTIN: &str = include_str!("../availability.txt");
+
+/// The FMOD version that introduced each item of the APIs, named as in
+/// [`ApiSurface`](crate::ApiSurface).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Availability {
+    since: BTreeMap<String, FmodVersion>,
+}
+
+impl Availability {
+    /// The database kept in this crate.
+    ///
+    /// # Panics
+    /// Panics if the database is malformed.
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN).expect("the builtin availability database should be valid")
+    }
+
+    /// Parse a database written as `<version> <item>` lines. Blank lines and lines starting
+    /// with `#` are skipped.
+    ///
+    /// # Errors
+    /// Fails on a line that isn't a version and an item.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut since = BTreeMap::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (version, item) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("`{line}` should be `<version> <item>`"))?;
+            since.insert(item.trim().to_owned(), version.parse()?);
+        }
+        Ok(Self { since })
+    }
+
+    /// The version that introduced `item`, if it's known.
+    #[must_use]
+    pub fn since(&self, item: &str) -> Option<FmodVersion> {
+        self.since.get(item).copied()
+    }
+
+    /// Note that everything `diff` added was introduced in `version`, unless it's already
+    /// known from an earlier version.
+    pub fn record(&mut self, version: FmodVersion, diff: &ApiDiff) {
+        let added = [
+            &diff.functions,
+            &diff.enum_variants,
+            &diff.constants,
+            &diff.struct_fields,
+        ]
+        .into_iter()
+        .flat_map(|it| it.added.keys());
+        for item in added {
+            self.since
+                .entry(item.clone())
+                .and_modify(|it| *it = (*it).min(version))
+                .or_insert(version);
+        }
+    }
+
+    /// The entries of the database, as [`parse`](Self::parse) reads them, sorted by version.
+    #[must_use]
+    pub fn to_entries(&self) -> String {
+        let mut entries = self.since.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(item, version)| (**version, *item));
+        let mut out = String::new();
+        for (item, version) in entries {
+            let _ = writeln!(out, "{version} {item}");
+        }
+        out
+    }
+
+    /// Tell Cargo about a `fmod_at_least_*` cfg for every version in the database, and set
+    /// the ones up to `version`, which is always set itself. Call this from a build script.
+    pub fn emit_cfgs(&self, version: FmodVersion) {
+        let mut versions = self.since.values().copied().collect::<Vec<_>>();
+        versions.push(version);
+        versions.sort_unstable();
+        versions.dedup();
+        for it in versions {
+            let cfg = it.cfg_name();
+            println!("cargo::rustc-check-cfg=cfg({cfg})");
+            if it <= version {
+                println!("cargo::rustc-cfg={cfg}");
+            }
+        }
+    }
+
+    /// Add a note to the docs of each item in the bindings that the database knows the
+    /// version of. The bindings are returned as they were if there's nothing to note.
+    pub(crate) fn annotate(&self, fns_only: &str, no_fns: &str) -> (String, String) {
+        if self.since.is_empty() {
+            return (fns_only.to_owned(), no_fns.to_owned());
+        }
+        let annotate_file = |bindings: &str| {
+            let mut file = syn::parse_file(bindings).expect("bindgen should generate valid Rust");
+            let mut noted = false;
+            let mut note = |name: &str, attrs: &mut Vec<Attribute>| {
+                if let Some(version) = self.since(name) {
+                    let cfg = version.cfg_name();
+                    let note = format!(" Available since FMOD {version}, as `cfg({cfg})`.");
+                    attrs.push(syn::parse_quote!(#[doc = ""]));
+                    attrs.push(syn::parse_quote!(#[doc = #note]));
+                    noted = true;
+                }
+            };
+            for item in &mut file.items {
+                match item {
+                    Item::ForeignMod(item) => {
+                        for function in &mut item.items {
+                            if let ForeignItem::Fn(function) = function {
+                                note(&function.sig.ident.to_string(), &mut function.attrs);
+                            }
+                        }
+                    }
+                    Item::Const(item) => note(&item.ident.to_string(), &mut item.attrs),
+                    Item::Impl(item) if item.trait_.is_none() => {
+                        let Type::Path(ty) = &*item.self_ty else {
+                            continue;
+                        };
+                        let Some(ty) = ty.path.segments.last().map(|it| it.ident.to_string())
+                        else {
+                            continue;
+                        };
+                        for variant in &mut item.items {
+                            if let ImplItem::Const(variant) = variant {
+                                note(&format!("{ty}::{}", variant.ident), &mut variant.attrs);
+                            }
+                        }
+                    }
+                    Item::Struct(item) => {
+                        if let Fields::Named(fields) = &mut item.fields {
+                            for field in &mut fields.named {
+                                if let Some(name) = &field.ident {
+                                    note(&format!("{}.{name}", item.ident), &mut field.attrs);
+                                }
+                            }
+                        }
+                    }
+                    Item::Union(item) => {
+                        for field in &mut item.fields.named {
+                            if let Some(name) = &field.ident {
+                                note(&format!("{}.{name}", item.ident), &mut field.attrs);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if noted {
+                prettyplease::unparse(&file)
+            } else {
+                bindings.to_owned()
+            }
+        };
+        (annotate_file(fns_only), annotate_file(no_fns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApiSurface;
+
+    #[test]
+    fn availability() {
+        let mut availability = Availability::parse(
+            "# A comment.\n\n2.02.10 FMOD_Studio_EventInstance_SetParameterByIDWithLabel\n",
+        )
+        .unwrap();
+        assert!(Availability::parse("2.02.10").is_err());
+        assert_eq!(
+            Availability::builtin().since("FMOD_Studio_EventInstance_SetParameterByIDWithLabel"),
+            Some("2.02.00".parse().unwrap())
+        );
+
+        let old = ApiSurface::default();
+        let new = ApiSurface::from_bindings(
+            r#"extern "C" { pub fn FMOD_Studio_EventInstance_SetParameterByIDWithLabel(); }"#,
+            r"
+            impl FMOD_DSP_TYPE { pub const FMOD_DSP_TYPE_MULTIBAND_DYNAMICS: FMOD_DSP_TYPE = FMOD_DSP_TYPE(35); }
+            pub struct FMOD_STUDIO_PARAMETER_DESCRIPTION { pub guid: FMOD_GUID }
+            ",
+        );
+        availability.record("2.02.20".parse().unwrap(), &ApiDiff::new(&old, &new));
+        assert_eq!(
+            availability.to_entries(),
+            "2.02.10 FMOD_Studio_EventInstance_SetParameterByIDWithLabel\n\
+            2.02.20 FMOD_DSP_TYPE::FMOD_DSP_TYPE_MULTIBAND_DYNAMICS\n\
+            2.02.20 FMOD_STUDIO_PARAMETER_DESCRIPTION.guid\n"
+        );
+
+        let (fns_only, no_fns) = availability.annotate(
+            r#"extern "C" { pub fn FMOD_Studio_EventInstance_SetParameterByIDWithLabel(); pub fn FMOD_Old(); }"#,
+            "pub const FMOD_OLD: i32 = 1;",
+        );
+        assert!(fns_only.contains(
+            "/// Available since FMOD 2.02.10, as `cfg(fmod_at_least_2_02_10)`.\n    \
+            pub fn FMOD_Studio_EventInstance_SetParameterByIDWithLabel();"
+        ));
+        assert_eq!(fns_only.matches("Available since").count(), 1);
+        assert_eq!(no_fns, "pub const FMOD_OLD: i32 = 1;");
+    }
+}
@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Report what changed in the FMOD APIs between two SDKs.
+//!
+//! Usage: `fmod-api-diff <old-sdk-dir> <new-sdk-dir> [--json] [--deny-breaking]
+//! [--availability <file>]`
+//!
+//! The report is written to stdout as Markdown, or as JSON with `--json`. With
+//! `--deny-breaking`, the exit code is 1 if anything changed in a way that could break
+//! code written against the old SDK. With `--availability`, the items the new SDK added
+//! are also noted in that availability database, such as the one in this crate.
+
+use std::fs;
+
+use camino::Utf8PathBuf;
+use fmod_build_utils::{Availability, SdkDiff};
+
+fn main() {
+    let usage = "usage: fmod-api-diff <old-sdk-dir> <new-sdk-dir> [--json] [--deny-breaking] \
+        [--availability <file>]";
+    let mut args = std::env::args().skip(1);
+    let old = Utf8PathBuf::from(args.next().expect(usage));
+    let new = Utf8PathBuf::from(args.next().expect(usage));
+    let (mut json, mut deny_breaking, mut availability) = (false, false, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--deny-breaking" => deny_breaking = true,
+            "--availability" => availability = Some(Utf8PathBuf::from(args.next().expect(usage))),
+            _ => panic!("{usage}"),
+        }
+    }
+
+    let diff = SdkDiff::between(&old, &new);
+    if json {
+        println!("{}", diff.to_json());
+    } else {
+        print!("{}", diff.to_markdown());
+    }
+    if let Some(path) = availability {
+        let version = diff
+            .new_version
+            .expect("the new SDK's version should be in its headers");
+        // Keep the comments at the top of the file, and rewrite the entries.
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        let mut database = Availability::parse(&text).unwrap_or_else(|err| panic!("{err}"));
+        for (_, api) in &diff.apis {
+            database.record(version, api);
+        }
+        let header = text
+            .lines()
+            .take_while(|it| it.is_empty() || it.starts_with('#'))
+            .map(|it| format!("{it}\n"))
+            .collect::<String>();
+        let header = if header.is_empty() || header.ends_with("\n\n") {
+            header
+        } else {
+            header + "\n"
+        };
+        fs::write(&path, header + &database.to_entries())
+            .expect("Should be able to write the availability database");
+    }
+    if deny_breaking && diff.is_breaking() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,24 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Write a stub FMOD SDK, for building the sys crates without the real one.
+//!
+//! Usage: `fmod-stub-sdk <dir> [--version 2.02.22] [--target <triple>]`
+
+use fmod_build_utils::StubSdk;
+
+fn main() {
+    let usage = "usage: fmod-stub-sdk <dir> [--version 2.02.22] [--target <triple>]";
+    let mut args = std::env::args().skip(1);
+    let mut sdk = StubSdk::new(args.next().expect(usage));
+    while let Some(arg) = args.next() {
+        let value = args.next().expect(usage);
+        sdk = match arg.as_str() {
+            "--version" => sdk.version(value.parse().unwrap_or_else(|err| panic!("{err}"))),
+            "--target" => sdk.target(value),
+            _ => panic!("{usage}"),
+        };
+    }
+    sdk.write();
+}
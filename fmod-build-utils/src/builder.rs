@@ -0,0 +1,669 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use std::fs;
+
+use bindgen::Builder as BindingBuilder;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    android::AndroidFiles,
+    api_docs::ApiDocs,
+    api_trait, base_bindings_builder, emit_lib_link, generate_bindings, handle_ext, lib_layout,
+    mock_guards,
+    sdk::{env_override, find_sdk_dir_in, process_env, ApiPaths, Env},
+    shims, signature_checks, Availability, FmodVersion, LibDir, LinkKind, LinkedLib, StubSdk,
+};
+
+/// Which FMOD API a build is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Api {
+    /// The FMOD Core API.
+    Core,
+    /// The FMOD Studio API.
+    Studio,
+    /// The FSBank API.
+    Fsbank,
+}
+
+impl Api {
+    /// The directory under `<sdk>/api` that holds this API's headers and libraries.
+    #[must_use]
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Api::Core => "core",
+            Api::Studio => "studio",
+            Api::Fsbank => "fsbank",
+        }
+    }
+
+    /// The names of the trait over this API's functions, and of its real implementation.
+    #[must_use]
+    pub fn trait_names(self) -> (&'static str, &'static str) {
+        match self {
+            Api::Core => ("FmodApi", "RealFmod"),
+            Api::Studio => ("FmodStudioApi", "RealFmodStudio"),
+            Api::Fsbank => ("FsbankApi", "RealFsbank"),
+        }
+    }
+
+    /// The base name of this API's library, without the logging suffix or any platform
+    /// decorations.
+    #[must_use]
+    pub fn lib_name(self) -> &'static str {
+        match self {
+            Api::Core => "fmod",
+            Api::Studio => "fmodstudio",
+            Api::Fsbank => "fsbank",
+        }
+    }
+}
+
+type BindgenHook = Box<dyn Fn(BindingBuilder) -> BindingBuilder>;
+
+/// Configuration for a build script that links against, and generates bindings for, one of
+/// the FMOD APIs.
+///
+/// Everything that isn't set explicitly is taken from the environment Cargo gives a build
+/// script. The simplest build script is:
+///
+/// ``` no_run
+/// use fmod_build_utils::{Api, FmodBuild};
+///
+/// FmodBuild::new(Api::Core).build();
+/// ```
+pub struct FmodBuild {
+    api: Api,
+    sdk_dir: Option<Utf8PathBuf>,
+    include_dir: Option<Utf8PathBuf>,
+    lib_dir: Option<Utf8PathBuf>,
+    debug_logging: Option<bool>,
+    link_kind: Option<LinkKind>,
+    link: bool,
+    bindings: bool,
+    header: Utf8PathBuf,
+    extra_include_dirs: Vec<Utf8PathBuf>,
+    clang_args: Vec<String>,
+    bindings_prelude: String,
+    bindgen_hooks: Vec<BindgenHook>,
+    out_path: Option<Utf8PathBuf>,
+    android_staging_dir: Option<Utf8PathBuf>,
+    docs_only: Option<bool>,
+    stored_bindings: Utf8PathBuf,
+}
+
+/// What an [`FmodBuild`] resolved and produced.
+#[derive(Debug, Clone)]
+pub struct FmodBuildOutput {
+    /// The root of the FMOD SDK that was used, if one was needed.
+    pub sdk_dir: Option<Utf8PathBuf>,
+    /// The include and library directories of the API that was built. Documentation-only
+    /// builds have none, unless they generated bindings from the stub SDK's headers.
+    pub paths: Option<ApiPaths>,
+    /// The library that was linked, if linking wasn't turned off.
+    pub linked: Option<LinkedLib>,
+    /// What an Android app needs at runtime, when building for Android.
+    pub android: Option<AndroidFiles>,
+    /// Whether the logging variant of the library was linked.
+    pub debug_logging: bool,
+    /// The version of the SDK, if it could be read from the headers.
+    pub version: Option<FmodVersion>,
+    /// Where the bindings were written, if they were generated.
+    pub bindings_path: Option<Utf8PathBuf>,
+}
+
+impl FmodBuild {
+    /// A build of `api`, with everything else taken from the environment until it's set.
+    /// Bindings are generated from `src/bindgen.h`, and the library is linked.
+    #[must_use]
+    pub fn new(api: Api) -> Self {
+        Self {
+            api,
+            sdk_dir: None,
+            include_dir: None,
+            lib_dir: None,
+            debug_logging: None,
+            link_kind: None,
+            link: true,
+            bindings: true,
+            header: Utf8PathBuf::from("src/bindgen.h"),
+            extra_include_dirs: Vec::new(),
+            clang_args: Vec::new(),
+            bindings_prelude: String::new(),
+            bindgen_hooks: Vec::new(),
+            out_path: None,
+            android_staging_dir: None,
+            docs_only: None,
+            stored_bindings: Utf8PathBuf::from("bindings/reference.rs"),
+        }
+    }
+
+    /// Use this SDK root instead of looking for one with [`find_sdk_dir`](crate::find_sdk_dir).
+    #[must_use]
+    pub fn sdk_dir(mut self, sdk_dir: impl Into<Utf8PathBuf>) -> Self {
+        self.sdk_dir = Some(sdk_dir.into());
+        self
+    }
+
+    /// Link the logging (`L`) variant of the library, or not. Defaults to Cargo's `DEBUG`.
+    #[must_use]
+    pub fn debug_logging(mut self, debug_logging: bool) -> Self {
+        self.debug_logging = Some(debug_logging);
+        self
+    }
+
+    /// Link statically or dynamically, instead of reading `FMOD_LINK_KIND` and the
+    /// `link-static` feature.
+    #[must_use]
+    pub fn link_kind(mut self, link_kind: LinkKind) -> Self {
+        self.link_kind = Some(link_kind);
+        self
+    }
+
+    /// Whether to emit link directives at all. Defaults to `true`.
+    #[must_use]
+    pub fn link(mut self, link: bool) -> Self {
+        self.link = link;
+        self
+    }
+
+    /// Whether to generate bindings at all. Defaults to `true`.
+    #[must_use]
+    pub fn bindings(mut self, bindings: bool) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    /// The header to generate bindings from, relative to the crate root. Defaults to
+    /// `src/bindgen.h`.
+    #[must_use]
+    pub fn header(mut self, header: impl Into<Utf8PathBuf>) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// Add an include directory, searched before the API's own.
+    #[must_use]
+    pub fn extra_include_dir(mut self, dir: impl Into<Utf8PathBuf>) -> Self {
+        self.extra_include_dirs.push(dir.into());
+        self
+    }
+
+    /// Pass an extra argument to clang when generating bindings.
+    #[must_use]
+    pub fn clang_arg(mut self, arg: impl Into<String>) -> Self {
+        self.clang_args.push(arg.into());
+        self
+    }
+
+    /// Code placed at the top of the generated bindings, such as `use fmod_sys::*;`.
+    #[must_use]
+    pub fn bindings_prelude(mut self, prelude: impl Into<String>) -> Self {
+        self.bindings_prelude = prelude.into();
+        self
+    }
+
+    /// Customise the bindgen builder before bindings are generated. Hooks run in the order
+    /// they were added.
+    #[must_use]
+    pub fn customize_bindings(
+        mut self,
+        hook: impl Fn(BindingBuilder) -> BindingBuilder + 'static,
+    ) -> Self {
+        self.bindgen_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Where to write the bindings. Defaults to `$OUT_DIR/bindings.rs`.
+    #[must_use]
+    pub fn out_path(mut self, out_path: impl Into<Utf8PathBuf>) -> Self {
+        self.out_path = Some(out_path.into());
+        self
+    }
+
+    /// On Android, copy the files the app needs at runtime into this directory, instead of
+    /// the one in `FMOD_ANDROID_STAGING_DIR`. See [`AndroidFiles::stage`].
+    #[must_use]
+    pub fn android_staging_dir(mut self, staging_dir: impl Into<Utf8PathBuf>) -> Self {
+        self.android_staging_dir = Some(staging_dir.into());
+        self
+    }
+
+    /// Use this include directory instead of the one in the SDK.
+    #[must_use]
+    pub fn include_dir(mut self, include_dir: impl Into<Utf8PathBuf>) -> Self {
+        self.include_dir = Some(include_dir.into());
+        self
+    }
+
+    /// Link against the libraries in this directory instead of the ones in the SDK. The
+    /// directory should hold the libraries for the target directly.
+    #[must_use]
+    pub fn lib_dir(mut self, lib_dir: impl Into<Utf8PathBuf>) -> Self {
+        self.lib_dir = Some(lib_dir.into());
+        self
+    }
+
+    /// Whether this build only needs to produce documentation. Documentation-only builds
+    /// neither need an SDK nor link. Defaults to whether `DOCS_RS` is set or the `docs-only`
+    /// feature is enabled.
+    #[must_use]
+    pub fn docs_only(mut self, docs_only: bool) -> Self {
+        self.docs_only = Some(docs_only);
+        self
+    }
+
+    /// Bindings for a reference SDK, relative to the crate root, used by documentation-only
+    /// builds. Defaults to `bindings/reference.rs`. Building with `FMOD_STORE_BINDINGS` set
+    /// refreshes them from the SDK being built against.
+    #[must_use]
+    pub fn stored_bindings(mut self, stored_bindings: impl Into<Utf8PathBuf>) -> Self {
+        self.stored_bindings = stored_bindings.into();
+        self
+    }
+
+    /// Find the include and library directories.
+    ///
+    /// Directories set on the builder win, then `FMOD_<API>_INCLUDE_DIR` and
+    /// `FMOD_<API>_LIB_DIR`, then pkg-config if the `pkg-config` feature is enabled, and
+    /// finally the SDK layout under the SDK root. Variables are looked up in `env`.
+    fn resolve_paths(&self, env: Env<'_>) -> (Option<Utf8PathBuf>, ApiPaths) {
+        let mut include_dir = self
+            .include_dir
+            .clone()
+            .or_else(|| env_override(self.api, "INCLUDE_DIR", env));
+        let mut lib_dir = self
+            .lib_dir
+            .clone()
+            .or_else(|| env_override(self.api, "LIB_DIR", env));
+
+        #[cfg(feature = "pkg-config")]
+        if include_dir.is_none() || lib_dir.is_none() {
+            if let Some((pc_include_dir, pc_lib_dir)) = crate::sdk::probe_pkg_config(self.api) {
+                include_dir = include_dir.or(pc_include_dir);
+                lib_dir = lib_dir.or(pc_lib_dir);
+            }
+        }
+
+        let sdk_dir = if include_dir.is_some() && lib_dir.is_some() {
+            self.sdk_dir.clone()
+        } else {
+            Some(self.sdk_dir.clone().unwrap_or_else(|| {
+                find_sdk_dir_in(self.api, env).expect(
+                    "FMOD_SDK_DIR should be set to the root of your Fmod SDK, \
+                    as none was found in the usual places",
+                )
+            }))
+        };
+        let in_sdk = sdk_dir
+            .as_deref()
+            .map(|sdk_dir| ApiPaths::in_sdk(sdk_dir, self.api));
+        let paths = ApiPaths {
+            include_dir: include_dir
+                .take()
+                .or_else(|| in_sdk.as_ref().map(|it| it.include_dir.clone()))
+                .unwrap(),
+            lib_dir: lib_dir
+                .take()
+                .map(LibDir::Flat)
+                .or_else(|| in_sdk.map(|it| it.lib_dir))
+                .unwrap(),
+        };
+        (sdk_dir, paths)
+    }
+
+    /// Emit link directives and metadata, and generate bindings.
+    ///
+    /// # Panics
+    /// This is meant to be called from a build script, and panics on any failure.
+    pub fn build(self) -> FmodBuildOutput {
+        let out_dir =
+            Utf8PathBuf::from(std::env::var("OUT_DIR").expect("Cargo should set OUT_DIR"));
+        let out_path = self
+            .out_path
+            .clone()
+            .unwrap_or_else(|| out_dir.join("bindings.rs"));
+        let stored_bindings = Utf8PathBuf::from(
+            std::env::var("CARGO_MANIFEST_DIR").expect("Cargo should set CARGO_MANIFEST_DIR"),
+        )
+        .join(&self.stored_bindings);
+
+        if self.docs_only.unwrap_or_else(is_docs_only) {
+            return self.build_docs_only(&out_dir, out_path, &stored_bindings);
+        }
+
+        let debug_logging = self.debug_logging.unwrap_or_else(|| {
+            std::env::var("DEBUG")
+                .expect("Cargo should set DEBUG, but it didn't?")
+                .parse::<bool>()
+                .unwrap()
+        });
+
+        let (
+            sdk_dir,
+            ApiPaths {
+                include_dir,
+                lib_dir,
+            },
+        ) = self.resolve_paths(&process_env);
+        let target = std::env::var("TARGET").expect("Couldn't get TARGET env var");
+        // The Windows FSBank SDK only ships `fsbank_vc.lib` and `fsbank.dll`, with no
+        // logging (`L`) variant, so the release library is linked even in debug builds.
+        let debug_logging =
+            debug_logging && !(self.api == Api::Fsbank && target.contains("windows"));
+        let core_include_dir = if self.api == Api::Core {
+            Some(include_dir.clone())
+        } else {
+            core_include_dir(sdk_dir.as_deref())
+        };
+        let version = core_include_dir
+            .as_deref()
+            .and_then(FmodVersion::from_headers);
+
+        let linked = self.link.then(|| {
+            let link_kind = self.link_kind.or_else(LinkKind::from_env);
+            if self.api == Api::Fsbank {
+                emit_fsbank_link(&lib_dir, &target, debug_logging, link_kind)
+            } else {
+                emit_lib_link(&lib_dir, self.api.lib_name(), debug_logging, link_kind)
+            }
+        });
+
+        // Published to dependents' build scripts as `DEP_<LINKS>_<KEY>`.
+        println!("cargo::metadata=include={include_dir}");
+        println!("cargo::metadata=logging={debug_logging}");
+        if let Some(version) = version {
+            println!("cargo::metadata=version={version}");
+        }
+        if let Some(linked) = &linked {
+            println!("cargo::metadata=lib_dir={}", linked.lib_dir);
+            println!("cargo::metadata=lib_file={}", linked.file_name);
+            println!("cargo::metadata=link_kind={}", linked.kind);
+        }
+
+        let android = linked
+            .as_ref()
+            .filter(|linked| target.contains("android") && linked.kind == LinkKind::Dylib)
+            .map(|linked| {
+                let abi = lib_layout(&target, self.api.lib_name(), debug_logging)
+                    .expect("the target should be supported, as it was linked for")
+                    .arch_dir;
+                let files = AndroidFiles::find(&lib_dir, linked, abi.as_str());
+                if let Some(jar) = &files.jar {
+                    println!("cargo::metadata=android_jar={jar}");
+                }
+                println!("cargo::metadata=android_lib={}", files.lib);
+
+                println!("cargo::rerun-if-env-changed=FMOD_ANDROID_STAGING_DIR");
+                let staging_dir = self.android_staging_dir.clone().or_else(|| {
+                    std::env::var("FMOD_ANDROID_STAGING_DIR")
+                        .ok()
+                        .map(Utf8PathBuf::from)
+                });
+                if let Some(staging_dir) = staging_dir {
+                    files.stage(&staging_dir);
+                }
+                files
+            });
+
+        let bindings_path = self.bindings.then(|| {
+            let bindings =
+                self.write_bindings(&include_dir, core_include_dir.as_deref(), &out_path);
+
+            println!("cargo::rerun-if-env-changed=FMOD_STORE_BINDINGS");
+            if std::env::var_os("FMOD_STORE_BINDINGS").is_some() {
+                let version = version.map_or_else(|| "unknown".to_owned(), |it| it.to_string());
+                if let Some(parent) = stored_bindings.parent() {
+                    fs::create_dir_all(parent)
+                        .expect("Should be able to create the stored bindings' directory");
+                }
+                fs::write(
+                    &stored_bindings,
+                    format!(
+                        "// Generated from FMOD {version} by fmod-build-utils. \
+                        Refresh by building with FMOD_STORE_BINDINGS set.\n{bindings}"
+                    ),
+                )
+                .expect("Should be able to store bindings");
+            }
+            out_path
+        });
+
+        FmodBuildOutput {
+            sdk_dir,
+            paths: Some(ApiPaths {
+                include_dir,
+                lib_dir,
+            }),
+            linked,
+            android,
+            debug_logging,
+            version,
+            bindings_path,
+        }
+    }
+
+    /// Write bindings without an SDK, and without linking or publishing metadata.
+    ///
+    /// The stored bindings are used if the crate has them. Otherwise, the bindings are
+    /// generated from the stub SDK's headers, which only declare part of each API.
+    fn build_docs_only(
+        &self,
+        out_dir: &Utf8Path,
+        out_path: Utf8PathBuf,
+        stored_bindings: &Utf8Path,
+    ) -> FmodBuildOutput {
+        let mut paths = None;
+        let bindings_path = self.bindings.then(|| {
+            if stored_bindings.is_file() {
+                println!("cargo::rerun-if-changed={stored_bindings}");
+                fs::copy(stored_bindings, &out_path).unwrap_or_else(|err| {
+                    panic!("couldn't use the stored bindings at {stored_bindings}: {err}")
+                });
+            } else {
+                let root = out_dir.join("stub-sdk");
+                StubSdk::new(&root).write_headers();
+                let stub_paths = ApiPaths::in_sdk(&root, self.api);
+                let core_include_dir = ApiPaths::in_sdk(&root, Api::Core).include_dir;
+                self.write_bindings(&stub_paths.include_dir, Some(&core_include_dir), &out_path);
+                paths = Some(stub_paths);
+            }
+            out_path
+        });
+
+        FmodBuildOutput {
+            sdk_dir: None,
+            paths,
+            linked: None,
+            android: None,
+            debug_logging: false,
+            version: None,
+            bindings_path,
+        }
+    }
+
+    /// Generate bindings from the headers in `include_dir`, write them to `out_path`, and
+    /// return them.
+    fn write_bindings(
+        &self,
+        include_dir: &Utf8Path,
+        core_include_dir: Option<&Utf8Path>,
+        out_path: &Utf8Path,
+    ) -> String {
+        let mut extra_include_dirs = self
+            .extra_include_dirs
+            .iter()
+            .map(Utf8PathBuf::as_path)
+            .collect::<Vec<_>>();
+        if self.api == Api::Studio {
+            extra_include_dirs.push(core_include_dir.expect(
+                "the Core include directory should be known through fmod-sys, \
+                FMOD_CORE_INCLUDE_DIR or FMOD_SDK_DIR",
+            ));
+        }
+
+        let mut builder =
+            base_bindings_builder(self.header.as_str(), include_dir, &extra_include_dirs)
+                .clang_args(&self.clang_args);
+        for hook in &self.bindgen_hooks {
+            builder = hook(builder);
+        }
+        let (fns_only, no_fns) = generate_bindings(builder);
+        let mut include_dirs = vec![include_dir];
+        include_dirs.extend_from_slice(&extra_include_dirs);
+        let (fns_only, no_fns) =
+            ApiDocs::from_include_dirs(&include_dirs).inject(&fns_only, &no_fns);
+        let (fns_only, no_fns) = Availability::builtin().annotate(&fns_only, &no_fns);
+
+        let bindings = assemble_bindings(self.api, &self.bindings_prelude, &fns_only, &no_fns);
+        fs::write(out_path, bindings.as_bytes()).expect("Should be able to write bindings");
+        bindings
+    }
+}
+
+/// The Core include directory, preferring the one fmod-sys published to an override, and an
+/// override to the one in this SDK.
+fn core_include_dir(sdk_dir: Option<&Utf8Path>) -> Option<Utf8PathBuf> {
+    println!("cargo::rerun-if-env-changed=DEP_FMOD_INCLUDE");
+    std::env::var("DEP_FMOD_INCLUDE")
+        .ok()
+        .map(Utf8PathBuf::from)
+        .or_else(|| env_override(Api::Core, "INCLUDE_DIR", &process_env))
+        .or_else(|| sdk_dir.map(|it| ApiPaths::in_sdk(it, Api::Core).include_dir))
+}
+
+/// Whether this is a documentation-only build, on docs.rs or with the `docs-only` feature.
+fn is_docs_only() -> bool {
+    println!("cargo::rerun-if-env-changed=DOCS_RS");
+    std::env::var_os("DOCS_RS").is_some() || std::env::var_os("CARGO_FEATURE_DOCS_ONLY").is_some()
+}
+
+/// FSBank has its own platform support, and its codecs live in separate libraries, which
+/// are linked the same way as FSBank itself.
+fn emit_fsbank_link(
+    lib_dir: &LibDir,
+    target: &str,
+    debug_logging: bool,
+    link_kind: Option<LinkKind>,
+) -> LinkedLib {
+    assert!(
+        [
+            "i686-pc-windows-msvc",
+            "x86_64-pc-windows-msvc",
+            "i686-unknown-linux-gnu",
+            "x86_64-unknown-linux-gnu",
+            "aarch64-apple-darwin",
+            "x86_64-apple-darwin"
+        ]
+        .contains(&target),
+        "FSBank is unsupported on {target}"
+    );
+    let linked = emit_lib_link(lib_dir, Api::Fsbank.lib_name(), debug_logging, link_kind);
+    if target.contains("windows") {
+        // There are no Windows archives, so FSBank itself was linked as a dylib, and the
+        // codecs' import libraries don't have the `_vc` suffix `emit_lib_link` expects.
+        let vorbis = if target == "i686-pc-windows-msvc" {
+            "libfsbvorbis"
+        } else {
+            "libfsbvorbis64"
+        };
+        println!("cargo::rustc-link-lib=dylib={vorbis}");
+        println!("cargo::rustc-link-lib=dylib=opus");
+    } else {
+        // The codecs have no logging variants.
+        for codec in ["fsbvorbis", "opus"] {
+            emit_lib_link(lib_dir, codec, false, Some(linked.kind));
+        }
+    }
+    linked
+}
+
+/// Wrap the function bindings in the `functions` module, which is doubled up as
+/// `mock_functions` when mocking, or replaced by shims with `trace-calls` or `record-calls`,
+/// and add the trait, handle extension traits, mock guards and signature checks over them.
+fn assemble_bindings(api: Api, prelude: &str, fns_only: &str, no_fns: &str) -> String {
+    let (trait_name, real_name) = api.trait_names();
+    [
+        prelude,
+        r#"#[cfg(feature = "mockall")]
+use mockall::automock;
+#[cfg(not(any(feature = "trace-calls", feature = "record-calls")))]
+#[cfg_attr(feature = "mockall", automock)]
+/// An artifact of mocking support.
+/// All functions live in this module.
+pub mod functions {
+    use super::*;"#,
+        fns_only,
+        "}",
+        no_fns,
+        &api_trait(fns_only, trait_name, real_name),
+        &handle_ext(fns_only, no_fns),
+        &mock_guards(fns_only),
+        &signature_checks(fns_only),
+        &shims(fns_only, no_fns, trait_name),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::test_env;
+
+    #[test]
+    fn overrides_need_no_sdk() {
+        let env = test_env(&[
+            ("FMOD_CORE_INCLUDE_DIR", "/usr/include/fmod"),
+            ("FMOD_CORE_LIB_DIR", "/usr/lib/fmod"),
+            ("FMOD_STUDIO_LIB_DIR", "/usr/lib/fmodstudio"),
+        ]);
+        let (sdk_dir, paths) = FmodBuild::new(Api::Core).resolve_paths(&env);
+        assert_eq!(sdk_dir, None);
+        assert_eq!(
+            paths,
+            ApiPaths {
+                include_dir: "/usr/include/fmod".into(),
+                lib_dir: LibDir::Flat("/usr/lib/fmod".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn builder_beats_env() {
+        let env = test_env(&[
+            ("FMOD_SDK_DIR", "/env/sdk"),
+            ("FMOD_CORE_INCLUDE_DIR", "/env/include"),
+            ("FMOD_CORE_LIB_DIR", "/env/lib"),
+        ]);
+        let (sdk_dir, paths) = FmodBuild::new(Api::Core)
+            .include_dir("/builder/include")
+            .resolve_paths(&env);
+        assert_eq!(sdk_dir, None);
+        assert_eq!(paths.include_dir, "/builder/include");
+        assert_eq!(paths.lib_dir, LibDir::Flat("/env/lib".into()));
+
+        let (sdk_dir, paths) = FmodBuild::new(Api::Studio)
+            .sdk_dir("/builder/sdk")
+            .resolve_paths(&env);
+        assert_eq!(sdk_dir.as_deref(), Some("/builder/sdk".into()));
+        assert_eq!(paths, ApiPaths::in_sdk("/builder/sdk".into(), Api::Studio));
+    }
+
+    #[test]
+    fn sdk_fills_in_the_rest() {
+        let env = test_env(&[
+            ("FMOD_SDK_DIR", "/sdk"),
+            ("FMOD_STUDIO_LIB_DIR", "/usr/lib/fmodstudio"),
+        ]);
+        let (sdk_dir, paths) = FmodBuild::new(Api::Studio).resolve_paths(&env);
+        assert_eq!(sdk_dir.as_deref(), Some("/sdk".into()));
+        assert_eq!(
+            paths,
+            ApiPaths {
+                include_dir: "/sdk/api/studio/inc".into(),
+                lib_dir: LibDir::Flat("/usr/lib/fmodstudio".into()),
+            }
+        );
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Extension traits over the handle types, turning `FMOD_<Type>_<Method>(handle, ...)` into
+//! `handle.method(...)`.
+//!
+//! A function becomes a method of the handle it's named after when that handle is its
+//! first parameter. Methods of functions that return `FMOD_RESULT` return a `Result`, and
+//! the out-parameters at the end of the function, which FMOD writes on success, are
+//! returned in it rather than taken. Buffers, and structs the caller has to size with
+//! `cbsize` first, are still taken as parameters, as is everything setters take, since
+//! some take what they set through a pointer that isn't `const`, such as
+//! `FMOD_Channel_Set3DConeOrientation`.
+
+use std::collections::BTreeMap;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{FnArg, ForeignItemFn, Pat, ReturnType, Type};
+
+use crate::{
+    api_trait::foreign_functions,
+    record_calls::{type_name, TypeInfo},
+    shims::family,
+};
+
+/// Generate the `ext` module, with a trait for each handle type that functions in
+/// `fns_only` take, implemented for pointers to it. `no_fns` holds the types they use.
+///
+/// # Panics
+/// Panics if `fns_only` or `no_fns` isn't valid Rust.
+#[must_use]
+pub fn handle_ext(fns_only: &str, no_fns: &str) -> String {
+    let file = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+    let info = TypeInfo::from_bindings(no_fns);
+
+    let mut families = BTreeMap::<String, (Type, Vec<&ForeignItemFn>)>::new();
+    for function in foreign_functions(&file) {
+        let name = function.sig.ident.to_string();
+        let family = family(&name);
+        let handle = format!("FMOD_{}", family.to_uppercase());
+        let Some(FnArg::Typed(first)) = function.sig.inputs.first() else {
+            continue;
+        };
+        let Type::Ptr(ptr) = &*first.ty else {
+            continue;
+        };
+        if ptr.mutability.is_none() || type_name(&ptr.elem).as_deref() != Some(handle.as_str()) {
+            continue;
+        }
+        families
+            .entry(family)
+            .or_insert_with(|| ((*first.ty).clone(), Vec::new()))
+            .1
+            .push(function);
+    }
+
+    let traits = families.iter().map(|(family, (handle, functions))| {
+        let trait_name = format_ident!("{}Ext", family.replace('_', ""));
+        let doc = format!(
+            " The functions taking a `FMOD_{}`, as methods of a pointer to it.",
+            family.to_uppercase()
+        );
+        let (declarations, definitions): (Vec<_>, Vec<_>) = functions
+            .iter()
+            .map(|function| method(&info, family, function))
+            .unzip();
+        quote! {
+            #[doc = #doc]
+            ///
+            /// # Safety
+            /// Each method has the safety requirements of the function it calls.
+            #[allow(
+                clippy::missing_safety_doc,
+                clippy::missing_errors_doc,
+                clippy::too_many_arguments
+            )]
+            pub trait #trait_name {
+                #(#declarations)*
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            impl #trait_name for #handle {
+                #(#definitions)*
+            }
+        }
+    });
+
+    let tokens = quote! {
+        /// Extension traits over the handle types, turning the functions named after each
+        /// handle into methods of it, as in `channel.set_volume(0.5)?` for
+        /// `FMOD_Channel_SetVolume(channel, 0.5)`.
+        ///
+        /// Methods of functions that return `FMOD_RESULT` return a `Result`, with the
+        /// out-parameters at the end of the function returned in it. The functions are
+        /// called through [`functions`].
+        pub mod ext {
+            use super::*;
+
+            #(#traits)*
+        }
+    };
+    prettyplease::unparse(&syn::parse2(tokens).expect("the extension traits should be valid Rust"))
+}
+
+/// The declaration and the definition of the method for `function`, in `family`.
+fn method(info: &TypeInfo, family: &str, function: &ForeignItemFn) -> (TokenStream, TokenStream) {
+    let name = &function.sig.ident;
+    let function_name = name.to_string();
+    let c_method_name = function_name
+        .strip_prefix(&format!("FMOD_{family}_"))
+        .expect("functions should be named after their family");
+    let method_name = snake_case(c_method_name);
+    let method_name = syn::parse_str::<Ident>(&method_name)
+        .unwrap_or_else(|_| Ident::new_raw(&method_name, Span::call_site()));
+    let params = function
+        .sig
+        .inputs
+        .iter()
+        .skip(1)
+        .map(|param| {
+            let FnArg::Typed(param) = param else {
+                panic!("FMOD functions don't take self");
+            };
+            let Pat::Ident(ident) = &*param.pat else {
+                panic!("bindgen should only generate named parameters");
+            };
+            (&ident.ident, &*param.ty)
+        })
+        .collect::<Vec<_>>();
+
+    let returns_result = matches!(
+        &function.sig.output,
+        ReturnType::Type(_, ty) if type_name(ty).as_deref() == Some("FMOD_RESULT")
+    );
+    let out_count = if returns_result && !c_method_name.starts_with("Set") {
+        params
+            .iter()
+            .rev()
+            .take_while(|(_, ty)| is_out(info, ty))
+            .count()
+    } else {
+        0
+    };
+    let (inputs, outs) = params.split_at(params.len() - out_count);
+    let input_params = inputs
+        .iter()
+        .map(|(name, ty)| quote!(#name: #ty))
+        .collect::<Vec<_>>();
+    let mut args = inputs
+        .iter()
+        .map(|(name, _)| quote!(#name))
+        .collect::<Vec<_>>();
+    args.extend(outs.iter().map(|(name, _)| quote!(&raw mut #name)));
+
+    let out_types = outs
+        .iter()
+        .map(|(_, ty)| match ty {
+            Type::Ptr(ptr) => &*ptr.elem,
+            _ => unreachable!("out-parameters are pointers"),
+        })
+        .collect::<Vec<_>>();
+    let out_names = outs.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    let (output, ok) = match out_types.as_slice() {
+        [ty] => (quote!(#ty), quote!(#(#out_names)*)),
+        _ => (quote!((#(#out_types),*)), quote!((#(#out_names),*))),
+    };
+    let doc = format!(" [`{name}`](functions::{name})");
+    let doc = if out_count > 0 {
+        format!("{doc}, returning its out-parameters.")
+    } else {
+        format!("{doc}.")
+    };
+
+    if returns_result {
+        (
+            quote! {
+                #[doc = #doc]
+                unsafe fn #method_name(self, #(#input_params),*) -> Result<#output, FMOD_RESULT>;
+            },
+            quote! {
+                #[inline]
+                unsafe fn #method_name(self, #(#input_params),*) -> Result<#output, FMOD_RESULT> {
+                    #(let mut #out_names: #out_types = std::mem::zeroed();)*
+                    let fmod_result = functions::#name(self, #(#args),*);
+                    if fmod_result == FMOD_RESULT::FMOD_OK {
+                        Ok(#ok)
+                    } else {
+                        Err(fmod_result)
+                    }
+                }
+            },
+        )
+    } else {
+        let output = &function.sig.output;
+        (
+            quote! {
+                #[doc = #doc]
+                unsafe fn #method_name(self, #(#input_params),*) #output;
+            },
+            quote! {
+                #[inline]
+                unsafe fn #method_name(self, #(#input_params),*) #output {
+                    functions::#name(self, #(#args),*)
+                }
+            },
+        )
+    }
+}
+
+/// Whether `ty` is an out-parameter, which FMOD writes, and the caller only reads.
+fn is_out(info: &TypeInfo, ty: &Type) -> bool {
+    let Type::Ptr(ptr) = ty else {
+        return false;
+    };
+    ptr.mutability.is_some()
+        && !info.is_handle(&ptr.elem)
+        && !info.is_sized(&ptr.elem)
+        // Buffers of bytes or characters have a length alongside.
+        && !matches!(type_name(&ptr.elem).as_deref(), Some("c_void" | "c_char"))
+}
+
+/// `SetVolume` as `set_volume`, keeping acronyms and numbers together, as in
+/// `Get3DAttributes` as `get_3d_attributes` and `GetDSPClock` as `get_dsp_clock`.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        let prev = index.checked_sub(1).map(|it| chars[it]);
+        let next = chars.get(index + 1);
+        let boundary = match prev {
+            None => false,
+            Some(prev) if c.is_ascii_uppercase() => {
+                prev.is_ascii_lowercase()
+                    || (prev.is_ascii_uppercase() && next.is_some_and(char::is_ascii_lowercase))
+            }
+            Some(prev) if c.is_ascii_digit() => prev.is_ascii_alphabetic(),
+            Some(_) => false,
+        };
+        if boundary {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snake_cases() {
+        assert_eq!(snake_case("SetVolume"), "set_volume");
+        assert_eq!(snake_case("Get3DAttributes"), "get_3d_attributes");
+        assert_eq!(snake_case("GetDSPClock"), "get_dsp_clock");
+        assert_eq!(
+            snake_case("SetParameterByIDWithLabel"),
+            "set_parameter_by_id_with_label"
+        );
+        assert_eq!(snake_case("Set3DMinMaxDistance"), "set_3d_min_max_distance");
+    }
+
+    #[test]
+    fn traits() {
+        let fns_only = r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint) -> FMOD_RESULT;
+                pub fn FMOD_System_CreateSound(system: *mut FMOD_SYSTEM, name_or_data: *const ::core::ffi::c_char, mode: FMOD_MODE, exinfo: *mut FMOD_CREATESOUNDEXINFO, sound: *mut *mut FMOD_SOUND) -> FMOD_RESULT;
+                pub fn FMOD_Channel_SetVolume(channel: *mut FMOD_CHANNEL, volume: f32) -> FMOD_RESULT;
+                pub fn FMOD_Channel_Get3DAttributes(channel: *mut FMOD_CHANNEL, pos: *mut FMOD_VECTOR, vel: *mut FMOD_VECTOR) -> FMOD_RESULT;
+                pub fn FMOD_Channel_Set3DConeOrientation(channel: *mut FMOD_CHANNEL, orientation: *mut FMOD_VECTOR) -> FMOD_RESULT;
+                pub fn FMOD_Studio_EventInstance_Set3DAttributes(eventinstance: *mut FMOD_STUDIO_EVENTINSTANCE, attributes: *mut FMOD_3D_ATTRIBUTES) -> FMOD_RESULT;
+                pub fn FMOD_Sound_GetName(sound: *mut FMOD_SOUND, name: *mut ::core::ffi::c_char, namelen: ::core::ffi::c_int) -> FMOD_RESULT;
+                pub fn FMOD_Studio_EventInstance_IsValid(eventinstance: *mut FMOD_STUDIO_EVENTINSTANCE) -> FMOD_BOOL;
+            }
+        "#;
+        let no_fns = r"
+            pub struct FMOD_STUDIO_EVENTINSTANCE { _unused: [u8; 0] }
+            pub struct FMOD_CREATESOUNDEXINFO { pub cbsize: ::core::ffi::c_int }
+        ";
+        let generated = handle_ext(fns_only, no_fns);
+        let generated = generated.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(generated.contains("/// The functions taking a `FMOD_SYSTEM`, as methods"));
+        assert!(generated.contains("impl SystemExt for *mut FMOD_SYSTEM {"));
+        assert!(!generated.contains("unsafe fn create("));
+        assert!(generated.contains(
+            "unsafe fn create_sound( self, name_or_data: *const ::core::ffi::c_char, mode: \
+            FMOD_MODE, exinfo: *mut FMOD_CREATESOUNDEXINFO, ) -> Result<*mut FMOD_SOUND, \
+            FMOD_RESULT>;"
+        ));
+        assert!(generated
+            .contains("unsafe fn set_volume(self, volume: f32) -> Result<(), FMOD_RESULT>;"));
+        assert!(generated.contains(
+            "unsafe fn get_3d_attributes( self, ) -> Result<(FMOD_VECTOR, FMOD_VECTOR), FMOD_RESULT>;"
+        ));
+        assert!(generated.contains("let mut pos: FMOD_VECTOR = std::mem::zeroed();"));
+        assert!(generated.contains(
+            "functions::FMOD_Channel_Get3DAttributes( self, &raw mut pos, &raw mut vel, );"
+        ));
+        // Setters take what they set, even through a pointer that isn't `const`.
+        assert!(generated.contains(
+            "unsafe fn set_3d_cone_orientation( self, orientation: *mut FMOD_VECTOR, ) -> \
+            Result<(), FMOD_RESULT>;"
+        ));
+        assert!(generated
+            .contains("functions::FMOD_Channel_Set3DConeOrientation( self, orientation, );"));
+        assert!(generated.contains(
+            "unsafe fn set_3d_attributes( self, attributes: *mut FMOD_3D_ATTRIBUTES, ) -> \
+            Result<(), FMOD_RESULT>;"
+        ));
+        assert!(generated.contains(
+            "unsafe fn get_name( self, name: *mut ::core::ffi::c_char, namelen: \
+            ::core::ffi::c_int, ) -> Result<(), FMOD_RESULT>;"
+        ));
+        assert!(
+            generated.contains("impl StudioEventInstanceExt for *mut FMOD_STUDIO_EVENTINSTANCE {")
+        );
+        assert!(generated.contains("unsafe fn is_valid(self) -> FMOD_BOOL;"));
+    }
+}
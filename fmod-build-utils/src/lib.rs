@@ -5,118 +5,64 @@
 use bindgen::{Builder as BindingBuilder, CodegenConfig, EnumVariation, MacroTypeVariation};
 use camino::Utf8Path;
 
-pub fn emit_lib_link(api_dir: &Utf8Path, lib_name: &str, debug_logging: bool) {
-    let lib_dir = api_dir.join("lib");
+use crate::api_docs::ApiDocs;
 
-    let target = std::env::var("TARGET").expect("Couldn't get TARGET env var");
-    let tgt_components = target.split('-').collect::<Vec<_>>();
-    let l_suffix = if debug_logging { "L" } else { "" };
-    let (lib_dir, lib_name, kind, modifiers) = match tgt_components.as_slice() {
-        [arch, "unknown", "linux", "gnu" | "gnueabihf"] => {
-            // Linux.
-            (
-                lib_dir.join(match *arch {
-                    "aarch64" => "arm64",
-                    "armv7" => "arm",
-                    "i686" => "x86",
-                    "x86_64" => arch,
-                    _ => panic!("unsupported Linux architecture {arch}!"),
-                }),
-                format!("{lib_name}{l_suffix}"),
-                "dylib",
-                None,
-            )
-        }
-        [arch, "pc", "windows", "msvc" | "gnu"] => {
-            // Windows.
-            (
-                lib_dir.join(match *arch {
-                    "i686" => "x86",
-                    "x86_64" => "x64",
-                    _ => panic!("unsupported Windows architecture {arch}!"),
-                }),
-                format!("{lib_name}{l_suffix}_vc"),
-                "dylib",
-                None,
-            )
-        }
-        ["x86_64" | "aarch64", "apple", "darwin"] => {
-            (lib_dir, format!("{lib_name}{l_suffix}"), "dylib", None)
-        }
-        [arch, "apple", os @ ("ios" | "tvos"), tail @ ..] => {
-            // iOS/tvOS
-            let is_sim = tail == ["sim"];
-            (
-                lib_dir,
-                format!(
-                    "{lib_name}{l_suffix}_{}",
-                    match (*arch, *os, is_sim) {
-                        ("aarch64" | "arm64e", "ios", false) => "iphoneos",
-                        ("aarch64" | "x86_64", "ios", true) => "iphonesimulator",
-                        ("arm64e", "tvos", false) => "appletvos",
-                        ("aarch64" | "x86_64", "tvos", true) => "appletvsimulator",
-                        (_, _, _) => panic!("unsupported iOS-like target {target}!"),
-                    }
-                ),
-                "static",
-                None,
-            )
-        }
-        ["wasm32", "unknown", "emscripten"] => (
-            lib_dir.join("upstream").join("w32"),
-            format!("{lib_name}{l_suffix}_wasm.a"),
-            "static",
-            Some("+verbatim"),
-        ),
-        [arch, "linux", "android" | "androideabi"] => {
-            // Android.
-            (
-                lib_dir.join(match *arch {
-                    "aarch64" => "arm64-v8a",
-                    "armv7" => "armeabi-v7a",
-                    "i686" => "x86",
-                    "x86_64" => arch,
-                    _ => panic!("unsupported Android architecture {arch}!"),
-                }),
-                format!("{lib_name}{l_suffix}"),
-                "dylib",
-                None,
-            )
-        }
-        [arch, "uwp", "windows", "msvc" | "gnu"] => {
-            // UWP.
-            (
-                lib_dir.join(match *arch {
-                    "i686" => "x86",
-                    "x86_64" => "x64",
-                    "thumbv7a" => "arm",
-                    _ => panic!("unsupported UWP architecture {arch}"),
-                }),
-                format!("{lib_name}{l_suffix}"),
-                "dylib",
-                None,
-            )
-        }
-        _ => panic!("unsupported target triple {target}"),
-    };
-    println!("cargo::rustc-link-search={lib_dir}");
-    println!(
-        "cargo::rustc-link-lib={}{}={}",
-        kind,
-        modifiers.map_or_else(|| String::new(), |m| format!(":{m}")),
-        lib_name
-    );
-}
+pub mod android;
+mod api_diff;
+mod api_docs;
+mod api_trait;
+mod availability;
+mod builder;
+mod handle_ext;
+mod link;
+mod metadata;
+pub mod mingw;
+mod mock_guards;
+mod record_calls;
+mod sdk;
+mod shims;
+mod signature_checks;
+mod stub_sdk;
+
+pub use api_diff::{ApiDiff, ApiSurface, Changes, SdkDiff};
+pub use api_trait::api_trait;
+pub use availability::Availability;
+pub use builder::{Api, FmodBuild, FmodBuildOutput};
+pub use handle_ext::handle_ext;
+pub use link::{emit_lib_link, lib_layout, LibDir, LibLayout, LinkKind, LinkedLib};
+pub use metadata::DepMetadata;
+pub use mock_guards::mock_guards;
+pub use sdk::{find_sdk_dir, ApiPaths, FmodVersion};
+pub use shims::shims;
+pub use signature_checks::signature_checks;
+pub use stub_sdk::StubSdk;
 
+/// Generate the bindings for `header`, returning the functions, and everything but the
+/// functions, separately, with docs linking each item to the FMOD API reference.
 pub fn make_bindings_builders(
     header: &str,
     include_dir: &Utf8Path,
     extra_include_dirs: &[&Utf8Path],
 ) -> (String, String) {
+    let (fns_only, no_fns) = generate_bindings(base_bindings_builder(
+        header,
+        include_dir,
+        extra_include_dirs,
+    ));
+    let mut include_dirs = vec![include_dir];
+    include_dirs.extend_from_slice(extra_include_dirs);
+    ApiDocs::from_include_dirs(&include_dirs).inject(&fns_only, &no_fns)
+}
+
+/// The bindgen configuration shared by all of the sys crates, before anything is generated.
+pub fn base_bindings_builder(
+    header: &str,
+    include_dir: &Utf8Path,
+    extra_include_dirs: &[&Utf8Path],
+) -> BindingBuilder {
     let mut base_builder = BindingBuilder::default()
         .header(header)
         .use_core()
-        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .prepend_enum_name(false)
         .default_enum_style(EnumVariation::NewType {
             is_bitfield: false,
@@ -129,13 +75,28 @@ pub fn make_bindings_builders(
             include_dir.to_string().replace('\\', "\\\\")
         ))
         .allowlist_file(format!(
-            r#"{}[\\/]src[\\/]bindgen\.h"#,
-            std::env::current_dir().unwrap().to_str().unwrap().replace('\\', "\\\\")
+            r#"{}[\\/]{}"#,
+            std::env::current_dir()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .replace('\\', "\\\\"),
+            header
+                .split(['/', '\\'])
+                .map(|it| it.replace('.', r"\."))
+                .collect::<Vec<_>>()
+                .join(r"[\\/]")
         ))
         .clang_args(extra_include_dirs.iter().map(|it| format!("-I{it}")))
         .clang_arg(format!("-I{include_dir}"))
         .merge_extern_blocks(true);
 
+    // Only build scripts should be told what to rerun on. Anything else, like
+    // `fmod-api-diff`, would get the instructions mixed into its output.
+    if std::env::var_os("OUT_DIR").is_some() {
+        base_builder = base_builder.parse_callbacks(Box::new(bindgen::CargoCallbacks::new()));
+    }
+
     if std::env::var("TARGET")
         .map(|it| it == "wasm32-unknown-emscripten")
         .unwrap_or(false)
@@ -143,6 +104,12 @@ pub fn make_bindings_builders(
         base_builder = base_builder.clang_args(&["-DDLL_EXPORTS", "-DF_USE_ATTRIBUTE"]);
     }
 
+    base_builder
+}
+
+/// Generate bindings from `base_builder`, returning the functions, and everything but the
+/// functions, separately.
+pub fn generate_bindings(base_builder: BindingBuilder) -> (String, String) {
     let bindings_fns_only = base_builder
         .clone()
         .with_codegen_config(CodegenConfig::FUNCTIONS)
@@ -0,0 +1,452 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{fs, str::FromStr};
+
+use camino::Utf8PathBuf;
+use target_lexicon::{
+    Aarch64Architecture, Architecture, ArmArchitecture, Environment, OperatingSystem, Triple,
+    Vendor,
+};
+
+use crate::mingw::make_import_lib;
+
+/// How the FMOD libraries are linked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkKind {
+    /// Link against a shared library (`.so`, `.dylib`, or a `.dll` import library).
+    Dylib,
+    /// Link a static archive into the final artifact.
+    Static,
+}
+
+impl LinkKind {
+    /// The link kind requested for this build, if any.
+    ///
+    /// `FMOD_LINK_KIND` (`dylib` or `static`) takes precedence over the `link-static`
+    /// feature. If neither is set, each target uses the kind the FMOD SDK ships for it.
+    ///
+    /// # Panics
+    /// Panics if `FMOD_LINK_KIND` is set to anything else.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        println!("cargo::rerun-if-env-changed=FMOD_LINK_KIND");
+        match std::env::var("FMOD_LINK_KIND") {
+            Ok(kind) => Some(
+                kind.parse()
+                    .unwrap_or_else(|err| panic!("FMOD_LINK_KIND is invalid: {err}")),
+            ),
+            Err(_) => std::env::var_os("CARGO_FEATURE_LINK_STATIC").map(|_| LinkKind::Static),
+        }
+    }
+
+    /// The name of this kind in `cargo::rustc-link-lib`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkKind::Dylib => "dylib",
+            LinkKind::Static => "static",
+        }
+    }
+}
+
+impl FromStr for LinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dylib" => Ok(LinkKind::Dylib),
+            "static" => Ok(LinkKind::Static),
+            other => Err(format!("`{other}` is neither `dylib` nor `static`")),
+        }
+    }
+}
+
+impl std::fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where to find an API's libraries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibDir {
+    /// An `api/<name>/lib` directory of the FMOD SDK, with a subdirectory per architecture
+    /// on the platforms that have them.
+    Sdk(Utf8PathBuf),
+    /// A directory that holds the libraries for the target directly, as distribution
+    /// packages install them.
+    Flat(Utf8PathBuf),
+}
+
+/// The library that [`emit_lib_link`] linked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkedLib {
+    /// The directory the library was found in.
+    pub lib_dir: Utf8PathBuf,
+    /// The file name of the library, or of its import library on Windows.
+    pub file_name: String,
+    pub kind: LinkKind,
+}
+
+/// Where, and under which names, the FMOD SDK keeps an API's library for a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibLayout {
+    /// The subdirectory of the SDK's `lib` directory that holds the target's libraries.
+    pub arch_dir: Utf8PathBuf,
+    /// The name to link the shared library by, if the target can have one.
+    pub dylib_name: Option<String>,
+    /// The file name of the shared library, or of its import library on Windows.
+    pub dylib_file: Option<String>,
+    /// The DLL that an import library has to be made from, for `*-windows-gnu` targets.
+    pub dll_file: Option<String>,
+    /// The file name of the static archive, if the target can have one.
+    pub archive_name: Option<String>,
+    /// The kind of library the SDK ships for the target.
+    pub shipped_kind: LinkKind,
+    /// The C++ runtime that a static archive needs, if the SDK doesn't ship one.
+    pub cxx_runtime: Option<&'static str>,
+}
+
+/// How the FMOD SDK lays out `lib_name` for `target`, or `None` if FMOD doesn't support the
+/// target.
+#[must_use]
+pub fn lib_layout(target: &str, lib_name: &str, debug_logging: bool) -> Option<LibLayout> {
+    // arm64e is aarch64 with pointer authentication, which target-lexicon doesn't know yet.
+    let triple = match target.strip_prefix("arm64e-") {
+        Some(rest) => Triple::from_str(&format!("aarch64-{rest}")),
+        None => Triple::from_str(target),
+    }
+    .ok()?;
+    let l_suffix = if debug_logging { "L" } else { "" };
+    let plain_name = format!("{lib_name}{l_suffix}");
+    let unix_archive = Some(format!("lib{plain_name}.a"));
+
+    let layout = match (triple.operating_system, triple.environment) {
+        (OperatingSystem::Linux, Environment::Android | Environment::Androideabi) => LibLayout {
+            arch_dir: Utf8PathBuf::from(match triple.architecture {
+                Architecture::Aarch64(Aarch64Architecture::Aarch64) => "arm64-v8a",
+                Architecture::Arm(
+                    ArmArchitecture::Armv7
+                    | ArmArchitecture::Armv7a
+                    | ArmArchitecture::Thumbv7a
+                    | ArmArchitecture::Thumbv7neon,
+                ) => "armeabi-v7a",
+                Architecture::X86_32(_) => "x86",
+                Architecture::X86_64 => "x86_64",
+                _ => return None,
+            }),
+            dylib_file: Some(format!("lib{plain_name}.so")),
+            dll_file: None,
+            dylib_name: Some(plain_name),
+            archive_name: unix_archive,
+            shipped_kind: LinkKind::Dylib,
+            cxx_runtime: Some("c++_shared"),
+        },
+        (
+            OperatingSystem::Linux,
+            Environment::Gnu
+            | Environment::Gnueabi
+            | Environment::Gnueabihf
+            | Environment::Musl
+            | Environment::Musleabi
+            | Environment::Musleabihf,
+        ) => LibLayout {
+            arch_dir: Utf8PathBuf::from(match triple.architecture {
+                Architecture::Aarch64(Aarch64Architecture::Aarch64) => "arm64",
+                Architecture::Arm(arm) if !arm.is_thumb() => "arm",
+                Architecture::X86_32(_) => "x86",
+                Architecture::X86_64 => "x86_64",
+                _ => return None,
+            }),
+            dylib_file: Some(format!("lib{plain_name}.so")),
+            dll_file: None,
+            dylib_name: Some(plain_name),
+            archive_name: unix_archive,
+            shipped_kind: LinkKind::Dylib,
+            cxx_runtime: Some("stdc++"),
+        },
+        (OperatingSystem::Windows, Environment::Gnu | Environment::GnuLlvm)
+            if triple.vendor != Vendor::Uwp =>
+        {
+            // MinGW can't link the SDK's import libraries, so one is made from the DLL.
+            LibLayout {
+                arch_dir: Utf8PathBuf::from(match triple.architecture {
+                    Architecture::X86_32(_) => "x86",
+                    Architecture::X86_64 => "x64",
+                    Architecture::Aarch64(Aarch64Architecture::Aarch64) => "arm64",
+                    _ => return None,
+                }),
+                dylib_file: Some(format!("lib{plain_name}.dll.a")),
+                dll_file: Some(format!("{plain_name}.dll")),
+                dylib_name: Some(plain_name),
+                archive_name: None,
+                shipped_kind: LinkKind::Dylib,
+                cxx_runtime: None,
+            }
+        }
+        (OperatingSystem::Windows, Environment::Msvc | Environment::Gnu) => {
+            let is_uwp = triple.vendor == Vendor::Uwp;
+            let arch_dir = match triple.architecture {
+                Architecture::X86_32(_) => "x86",
+                Architecture::X86_64 => "x64",
+                Architecture::Aarch64(Aarch64Architecture::Aarch64) => "arm64",
+                Architecture::Arm(ArmArchitecture::Thumbv7a) if is_uwp => "arm",
+                _ => return None,
+            };
+            // UWP libraries have no `_vc` suffix.
+            let dylib_name = if is_uwp {
+                plain_name
+            } else {
+                format!("{plain_name}_vc")
+            };
+            LibLayout {
+                arch_dir: Utf8PathBuf::from(arch_dir),
+                dylib_file: Some(format!("{dylib_name}.lib")),
+                dll_file: None,
+                dylib_name: Some(dylib_name),
+                archive_name: None,
+                shipped_kind: LinkKind::Dylib,
+                cxx_runtime: None,
+            }
+        }
+        // Mac Catalyst links against the macOS libraries.
+        (OperatingSystem::Darwin(_) | OperatingSystem::MacOSX(_), _)
+        | (OperatingSystem::IOS(_), Environment::Macabi) => {
+            if !matches!(
+                triple.architecture,
+                Architecture::Aarch64(Aarch64Architecture::Aarch64) | Architecture::X86_64
+            ) {
+                return None;
+            }
+            LibLayout {
+                arch_dir: Utf8PathBuf::new(),
+                dylib_file: Some(format!("lib{plain_name}.dylib")),
+                dll_file: None,
+                dylib_name: Some(plain_name),
+                archive_name: unix_archive,
+                shipped_kind: LinkKind::Dylib,
+                cxx_runtime: Some("c++"),
+            }
+        }
+        (os @ (OperatingSystem::IOS(_) | OperatingSystem::TvOS(_)), env) => {
+            let is_sim = match (triple.architecture, env) {
+                (Architecture::Aarch64(Aarch64Architecture::Aarch64), Environment::Sim)
+                | (Architecture::X86_64, _) => true,
+                (Architecture::Aarch64(Aarch64Architecture::Aarch64), _) => false,
+                _ => return None,
+            };
+            let platform = match (os, is_sim) {
+                (OperatingSystem::IOS(_), false) => "iphoneos",
+                (OperatingSystem::IOS(_), true) => "iphonesimulator",
+                (_, false) => "appletvos",
+                (_, true) => "appletvsimulator",
+            };
+            LibLayout {
+                arch_dir: Utf8PathBuf::new(),
+                dylib_name: None,
+                dylib_file: None,
+                dll_file: None,
+                archive_name: Some(format!("lib{plain_name}_{platform}.a")),
+                shipped_kind: LinkKind::Static,
+                cxx_runtime: None,
+            }
+        }
+        (OperatingSystem::Emscripten, _) if triple.architecture == Architecture::Wasm32 => {
+            LibLayout {
+                arch_dir: Utf8PathBuf::from("upstream").join("w32"),
+                dylib_name: None,
+                dylib_file: None,
+                dll_file: None,
+                archive_name: Some(format!("{plain_name}_wasm.a")),
+                shipped_kind: LinkKind::Static,
+                cxx_runtime: None,
+            }
+        }
+        _ => return None,
+    };
+    Some(layout)
+}
+
+/// Emit the link search path and library for one FMOD API.
+///
+/// `link_kind` of `None` uses whatever the FMOD SDK ships for the target. Asking for a kind
+/// the SDK doesn't ship only works if the archive has been put in the SDK's lib directory
+/// by hand, as licensees of some platforms have to.
+///
+/// # Panics
+/// Panics if the target is unsupported, or the requested link kind is unavailable.
+pub fn emit_lib_link(
+    lib_dir: &LibDir,
+    lib_name: &str,
+    debug_logging: bool,
+    link_kind: Option<LinkKind>,
+) -> LinkedLib {
+    let target = std::env::var("TARGET").expect("Couldn't get TARGET env var");
+    let LibLayout {
+        arch_dir,
+        dylib_name,
+        dylib_file,
+        dll_file,
+        archive_name,
+        shipped_kind,
+        cxx_runtime,
+    } = lib_layout(&target, lib_name, debug_logging)
+        .unwrap_or_else(|| panic!("unsupported target triple {target}"));
+
+    let mut lib_dir = match lib_dir {
+        LibDir::Sdk(root) => root.join(arch_dir),
+        LibDir::Flat(dir) => dir.clone(),
+    };
+
+    let kind = link_kind.unwrap_or(shipped_kind);
+    let (lib_name, modifiers, file_name) = match kind {
+        LinkKind::Dylib => {
+            let (Some(dylib_name), Some(dylib_file)) = (dylib_name, dylib_file) else {
+                panic!(
+                    "FMOD only provides static libraries for {target}, \
+                    so {lib_name} can't be linked as a dylib"
+                )
+            };
+            if let Some(dll_file) = dll_file.filter(|_| !lib_dir.join(&dylib_file).is_file()) {
+                let import_dir =
+                    Utf8PathBuf::from(std::env::var("OUT_DIR").expect("Cargo should set OUT_DIR"))
+                        .join("import-libs");
+                fs::create_dir_all(&import_dir)
+                    .expect("Should be able to create the import library directory");
+                let arch = target.split('-').next().unwrap();
+                make_import_lib(&lib_dir.join(dll_file), &import_dir.join(&dylib_file), arch);
+                // The DLL stays where it is, for running tests.
+                println!("cargo::rustc-link-search={lib_dir}");
+                lib_dir = import_dir;
+            }
+            (dylib_name, None, dylib_file)
+        }
+        LinkKind::Static => {
+            let archive_name = archive_name.unwrap_or_else(|| {
+                panic!(
+                    "FMOD doesn't provide static libraries for {target}, \
+                    so {lib_name} can't be linked statically"
+                )
+            });
+            if kind != shipped_kind {
+                assert!(
+                    lib_dir.join(&archive_name).is_file(),
+                    "static linking was requested, but {archive_name} isn't in {lib_dir}. \
+                    The FMOD SDK for {target} doesn't ship static libraries; \
+                    if your license provides them, place them there."
+                );
+                if let Some(cxx_runtime) = cxx_runtime {
+                    println!("cargo::rustc-link-lib=dylib={cxx_runtime}");
+                }
+            }
+            // Archives that don't follow the `lib<name>.a` convention are linked verbatim.
+            match archive_name
+                .strip_prefix("lib")
+                .and_then(|it| it.strip_suffix(".a"))
+            {
+                Some(name) => (name.to_owned(), None, archive_name.clone()),
+                None => (archive_name.clone(), Some("+verbatim"), archive_name),
+            }
+        }
+    };
+    println!("cargo::rustc-link-search={lib_dir}");
+    println!(
+        "cargo::rustc-link-lib={}{}={}",
+        kind,
+        modifiers.map_or_else(String::new, |m| format!(":{m}")),
+        lib_name
+    );
+    LinkedLib {
+        lib_dir,
+        file_name,
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every supported target, with the directory and file its Core library is expected in.
+    #[rustfmt::skip]
+    const SUPPORTED: &[(&str, &str, &str, LinkKind)] = &[
+        ("x86_64-unknown-linux-gnu", "x86_64", "libfmod.so", LinkKind::Dylib),
+        ("x86_64-unknown-linux-musl", "x86_64", "libfmod.so", LinkKind::Dylib),
+        ("i686-unknown-linux-gnu", "x86", "libfmod.so", LinkKind::Dylib),
+        ("aarch64-unknown-linux-gnu", "arm64", "libfmod.so", LinkKind::Dylib),
+        ("aarch64-unknown-linux-musl", "arm64", "libfmod.so", LinkKind::Dylib),
+        ("armv7-unknown-linux-gnueabihf", "arm", "libfmod.so", LinkKind::Dylib),
+        ("armv7-unknown-linux-musleabihf", "arm", "libfmod.so", LinkKind::Dylib),
+        ("arm-unknown-linux-gnueabihf", "arm", "libfmod.so", LinkKind::Dylib),
+        ("x86_64-pc-windows-msvc", "x64", "fmod_vc.lib", LinkKind::Dylib),
+        ("i686-pc-windows-msvc", "x86", "fmod_vc.lib", LinkKind::Dylib),
+        ("aarch64-pc-windows-msvc", "arm64", "fmod_vc.lib", LinkKind::Dylib),
+        ("x86_64-pc-windows-gnu", "x64", "libfmod.dll.a", LinkKind::Dylib),
+        ("i686-pc-windows-gnu", "x86", "libfmod.dll.a", LinkKind::Dylib),
+        ("x86_64-pc-windows-gnullvm", "x64", "libfmod.dll.a", LinkKind::Dylib),
+        ("aarch64-pc-windows-gnullvm", "arm64", "libfmod.dll.a", LinkKind::Dylib),
+        ("x86_64-uwp-windows-msvc", "x64", "fmod.lib", LinkKind::Dylib),
+        ("thumbv7a-uwp-windows-msvc", "arm", "fmod.lib", LinkKind::Dylib),
+        ("x86_64-apple-darwin", "", "libfmod.dylib", LinkKind::Dylib),
+        ("aarch64-apple-darwin", "", "libfmod.dylib", LinkKind::Dylib),
+        ("aarch64-apple-ios-macabi", "", "libfmod.dylib", LinkKind::Dylib),
+        ("x86_64-apple-ios-macabi", "", "libfmod.dylib", LinkKind::Dylib),
+        ("aarch64-apple-ios", "", "libfmod_iphoneos.a", LinkKind::Static),
+        ("arm64e-apple-ios", "", "libfmod_iphoneos.a", LinkKind::Static),
+        ("aarch64-apple-ios-sim", "", "libfmod_iphonesimulator.a", LinkKind::Static),
+        ("x86_64-apple-ios", "", "libfmod_iphonesimulator.a", LinkKind::Static),
+        ("aarch64-apple-tvos", "", "libfmod_appletvos.a", LinkKind::Static),
+        ("aarch64-apple-tvos-sim", "", "libfmod_appletvsimulator.a", LinkKind::Static),
+        ("x86_64-apple-tvos", "", "libfmod_appletvsimulator.a", LinkKind::Static),
+        ("aarch64-linux-android", "arm64-v8a", "libfmod.so", LinkKind::Dylib),
+        ("armv7-linux-androideabi", "armeabi-v7a", "libfmod.so", LinkKind::Dylib),
+        ("thumbv7neon-linux-androideabi", "armeabi-v7a", "libfmod.so", LinkKind::Dylib),
+        ("i686-linux-android", "x86", "libfmod.so", LinkKind::Dylib),
+        ("x86_64-linux-android", "x86_64", "libfmod.so", LinkKind::Dylib),
+        ("wasm32-unknown-emscripten", "upstream/w32", "fmod_wasm.a", LinkKind::Static),
+    ];
+
+    #[test]
+    fn supported_targets() {
+        for &(target, arch_dir, file, kind) in SUPPORTED {
+            let layout = lib_layout(target, "fmod", false)
+                .unwrap_or_else(|| panic!("{target} should be supported"));
+            assert_eq!(layout.arch_dir, arch_dir, "{target}");
+            assert_eq!(layout.shipped_kind, kind, "{target}");
+            let shipped_file = match kind {
+                LinkKind::Dylib => layout.dylib_file,
+                LinkKind::Static => layout.archive_name,
+            };
+            assert_eq!(shipped_file.as_deref(), Some(file), "{target}");
+        }
+    }
+
+    #[test]
+    fn logging_suffix() {
+        let layout = lib_layout("x86_64-pc-windows-msvc", "fmodstudio", true).unwrap();
+        assert_eq!(layout.dylib_name.as_deref(), Some("fmodstudioL_vc"));
+        let layout = lib_layout("aarch64-apple-ios", "fmodstudio", true).unwrap();
+        assert_eq!(
+            layout.archive_name.as_deref(),
+            Some("libfmodstudioL_iphoneos.a")
+        );
+    }
+
+    #[test]
+    fn unsupported_targets() {
+        for target in [
+            "riscv64gc-unknown-linux-gnu",
+            "x86_64-unknown-freebsd",
+            "wasm32-unknown-unknown",
+            "aarch64-apple-watchos",
+            "not-a-target",
+        ] {
+            assert!(
+                lib_layout(target, "fmod", false).is_none(),
+                "{target} shouldn't be supported"
+            );
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use camino::Utf8PathBuf;
+
+use crate::{
+    sdk::{process_env, Env},
+    Availability, FmodVersion, LinkKind,
+};
+
+/// What one of the sys crates published about the FMOD API it built against, for the
+/// build scripts of crates that depend on it.
+///
+/// A crate that compiles C++ against FMOD, such as an FMOD plugin, can use this to find the
+/// same headers and libraries as the sys crate it depends on:
+///
+/// ``` no_run
+/// use fmod_build_utils::DepMetadata;
+///
+/// let fmod = DepMetadata::from_env("fmod").expect("fmod-sys should be a dependency");
+/// let include_arg = format!("-I{}", fmod.include_dir);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepMetadata {
+    pub include_dir: Utf8PathBuf,
+    /// Whether the logging variant of the library was linked.
+    pub debug_logging: bool,
+    /// The SDK version, if it could be read from the headers.
+    pub version: Option<FmodVersion>,
+    /// The directory the library was linked from.
+    pub lib_dir: Option<Utf8PathBuf>,
+    /// The file name of the library, or of its import library on Windows.
+    pub lib_file: Option<String>,
+    pub link_kind: Option<LinkKind>,
+    /// `fmod.jar`, when building for Android against the Core API.
+    pub android_jar: Option<Utf8PathBuf>,
+    /// The shared library an Android app has to package, when building for Android.
+    pub android_lib: Option<Utf8PathBuf>,
+}
+
+impl DepMetadata {
+    /// Read the metadata published by the sys crate with the given `links` key: `fmod` for
+    /// fmod-sys, `fmodstudio` for fmod-studio-sys, or `fsbank` for fsbank-sys.
+    ///
+    /// Returns `None` if the crate isn't a direct dependency, or didn't publish an include
+    /// directory, as in documentation-only builds.
+    #[must_use]
+    pub fn from_env(links: &str) -> Option<Self> {
+        Self::from_env_in(links, &process_env)
+    }
+
+    /// [`DepMetadata::from_env`], with the variables looked up in `env`.
+    fn from_env_in(links: &str, env: Env<'_>) -> Option<Self> {
+        let var = |key: &str| {
+            let var = format!("DEP_{}_{}", links.to_uppercase(), key.to_uppercase());
+            println!("cargo::rerun-if-env-changed={var}");
+            env(&var)
+        };
+        Some(Self {
+            include_dir: var("include").map(Utf8PathBuf::from)?,
+            debug_logging: var("logging").is_some_and(|it| it == "true"),
+            version: var("version").and_then(|it| it.parse().ok()),
+            lib_dir: var("lib_dir").map(Utf8PathBuf::from),
+            lib_file: var("lib_file"),
+            link_kind: var("link_kind").and_then(|it| it.parse().ok()),
+            android_jar: var("android_jar").map(Utf8PathBuf::from),
+            android_lib: var("android_lib").map(Utf8PathBuf::from),
+        })
+    }
+
+    /// Set a `fmod_at_least_*` cfg for each version up to the one the sys crate built
+    /// against, so that the crate whose build script this is can use items from newer SDKs
+    /// only when they're there:
+    ///
+    /// ``` no_run
+    /// # use fmod_build_utils::DepMetadata;
+    /// if let Some(fmod) = DepMetadata::from_env("fmod") {
+    ///     fmod.emit_version_cfgs();
+    /// }
+    /// ```
+    ///
+    /// Nothing is set if the version is unknown.
+    pub fn emit_version_cfgs(&self) {
+        if let Some(version) = self.version {
+            Availability::builtin().emit_cfgs(version);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::test_env;
+
+    #[test]
+    fn from_env() {
+        let vars = [
+            ("DEP_FMODSTUDIO_INCLUDE", "/sdk/api/studio/inc"),
+            ("DEP_FMODSTUDIO_LOGGING", "true"),
+            ("DEP_FMODSTUDIO_VERSION", "2.02.22"),
+            ("DEP_FMODSTUDIO_LIB_DIR", "/sdk/api/studio/lib/x86_64"),
+            ("DEP_FMODSTUDIO_LIB_FILE", "libfmodstudioL.so"),
+            ("DEP_FMODSTUDIO_LINK_KIND", "dylib"),
+            ("DEP_FMOD_INCLUDE", "/sdk/api/core/inc"),
+            ("DEP_FMOD_LOGGING", "false"),
+            ("DEP_FMOD_VERSION", "not a version"),
+            ("DEP_FMOD_LINK_KIND", "neither"),
+        ];
+        let env = test_env(&vars);
+        assert_eq!(
+            DepMetadata::from_env_in("fmodstudio", &env),
+            Some(DepMetadata {
+                include_dir: "/sdk/api/studio/inc".into(),
+                debug_logging: true,
+                version: Some(FmodVersion::from_encoded(0x0002_0222)),
+                lib_dir: Some("/sdk/api/studio/lib/x86_64".into()),
+                lib_file: Some("libfmodstudioL.so".to_owned()),
+                link_kind: Some(LinkKind::Dylib),
+                android_jar: None,
+                android_lib: None,
+            })
+        );
+        // What can't be parsed is left out.
+        assert_eq!(
+            DepMetadata::from_env_in("fmod", &env),
+            Some(DepMetadata {
+                include_dir: "/sdk/api/core/inc".into(),
+                debug_logging: false,
+                version: None,
+                lib_dir: None,
+                lib_file: None,
+                link_kind: None,
+                android_jar: None,
+                android_lib: None,
+            })
+        );
+        // Nothing without an include directory.
+        assert_eq!(DepMetadata::from_env_in("fsbank", &env), None);
+    }
+}
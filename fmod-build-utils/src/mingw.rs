@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Import libraries for the `*-windows-gnu` targets, whose linker doesn't take the import
+//! libraries in the FMOD SDK.
+
+use std::{fmt::Write as _, fs, process::Command};
+
+use camino::Utf8Path;
+
+/// Make a GNU import library, `import_lib`, for `dll`, by reading the DLL's exports and
+/// handing them to `dlltool`.
+///
+/// `dlltool` is looked for as `DLLTOOL`, then `<arch>-w64-mingw32-dlltool`, `dlltool` and
+/// `llvm-dlltool`.
+///
+/// # Panics
+/// Panics if `dll` can't be read, has no exports, or no `dlltool` works.
+pub fn make_import_lib(dll: &Utf8Path, import_lib: &Utf8Path, arch: &str) {
+    let image = fs::read(dll).unwrap_or_else(|err| panic!("couldn't read {dll}: {err}"));
+    let exports = dll_exports(&image)
+        .filter(|it| !it.is_empty())
+        .unwrap_or_else(|| panic!("couldn't read the exports of {dll}"));
+
+    let dll_name = dll.file_name().unwrap();
+    let mut def = format!("LIBRARY {dll_name}\nEXPORTS\n");
+    for export in &exports {
+        writeln!(def, "    {export}").unwrap();
+    }
+    let def_path = import_lib.with_extension("def");
+    fs::write(&def_path, def).expect("Should be able to write the module definition");
+
+    let machine = match arch {
+        "x86_64" => "i386:x86-64",
+        "i686" | "i586" => "i386",
+        "aarch64" => "arm64",
+        _ => panic!("can't make import libraries for {arch}"),
+    };
+    println!("cargo::rerun-if-env-changed=DLLTOOL");
+    let candidates = std::env::var("DLLTOOL").into_iter().chain([
+        format!("{arch}-w64-mingw32-dlltool"),
+        "dlltool".to_owned(),
+        "llvm-dlltool".to_owned(),
+    ]);
+    let mut tried = Vec::new();
+    for dlltool in candidates {
+        let status = Command::new(&dlltool)
+            .args(["-m", machine, "-D", dll_name, "-d"])
+            .arg(&def_path)
+            .arg("-l")
+            .arg(import_lib)
+            .status();
+        match status {
+            Ok(status) if status.success() => return,
+            Ok(status) => tried.push(format!("{dlltool} ({status})")),
+            Err(err) => tried.push(format!("{dlltool} ({err})")),
+        }
+    }
+    panic!(
+        "couldn't make an import library for {dll}. Tried: {}. \
+        Install MinGW's binutils, or set DLLTOOL.",
+        tried.join(", ")
+    );
+}
+
+/// The names a PE image exports, or `None` if the image is truncated or malformed.
+fn dll_exports(image: &[u8]) -> Option<Vec<String>> {
+    let u16_at = |offset: usize| {
+        image
+            .get(offset..offset.checked_add(2)?)
+            .map(|it| u16::from_le_bytes(it.try_into().unwrap()))
+    };
+    let u32_at = |offset: usize| {
+        image
+            .get(offset..offset.checked_add(4)?)
+            .map(|it| u32::from_le_bytes(it.try_into().unwrap()))
+    };
+
+    let pe = u32_at(0x3c)? as usize;
+    if image.get(pe..pe + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe + 4;
+    let section_count = usize::from(u16_at(coff + 2)?);
+    let optional_header = coff + 20;
+    let optional_header_size = usize::from(u16_at(coff + 16)?);
+    let data_directories = match u16_at(optional_header)? {
+        // PE32
+        0x10b => optional_header + 96,
+        // PE32+
+        0x20b => optional_header + 112,
+        _ => return None,
+    };
+    let export_directory_rva = u32_at(data_directories)?;
+
+    let sections = optional_header + optional_header_size;
+    let to_offset = |rva: u32| {
+        for i in 0..section_count {
+            let section = sections + i * 40;
+            let virtual_size = u32_at(section + 8)?;
+            let virtual_address = u32_at(section + 12)?;
+            let raw_size = u32_at(section + 16)?;
+            let raw_offset = u32_at(section + 20)?;
+            // A section claiming to end past 4 GiB makes the image malformed.
+            let end = virtual_address.checked_add(virtual_size.max(raw_size))?;
+            if (virtual_address..end).contains(&rva) {
+                return Some((rva - virtual_address).checked_add(raw_offset)? as usize);
+            }
+        }
+        None
+    };
+
+    let export_directory = to_offset(export_directory_rva)?;
+    let name_count = u32_at(export_directory + 24)? as usize;
+    let names = to_offset(u32_at(export_directory + 32)?)?;
+    (0..name_count)
+        .map(|i| {
+            let name = to_offset(u32_at(names + i * 4)?)?;
+            let len = image.get(name..)?.iter().position(|&it| it == 0)?;
+            String::from_utf8(image[name..name + len].to_vec()).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Where the fixtures' one section is, in the image and in memory.
+    const SECTION_OFFSET: usize = 0xf8;
+    const SECTION_RVA: u32 = 0x1000;
+
+    fn put(image: &mut [u8], offset: usize, bytes: &[u8]) {
+        image[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// A PE32+ image with one section, holding an export directory for `exports`, and ending
+    /// with the last name.
+    fn image(exports: &[&str]) -> Vec<u8> {
+        let mut image = vec![0; SECTION_OFFSET];
+        put(&mut image, 0, b"MZ");
+        put(&mut image, 0x3c, &0x40_u32.to_le_bytes());
+        put(&mut image, 0x40, b"PE\0\0");
+        // The COFF header: one section, and an optional header with one data directory.
+        put(&mut image, 0x44, &0x8664_u16.to_le_bytes());
+        put(&mut image, 0x46, &1_u16.to_le_bytes());
+        put(&mut image, 0x54, &120_u16.to_le_bytes());
+        put(&mut image, 0x58, &0x20b_u16.to_le_bytes());
+        put(&mut image, 0xc8, &SECTION_RVA.to_le_bytes());
+
+        let names = SECTION_RVA + 40;
+        let mut name = names + 4 * u32::try_from(exports.len()).unwrap();
+        let mut section = vec![0; 40];
+        put(
+            &mut section,
+            24,
+            &u32::try_from(exports.len()).unwrap().to_le_bytes(),
+        );
+        put(&mut section, 32, &names.to_le_bytes());
+        for export in exports {
+            section.extend_from_slice(&name.to_le_bytes());
+            name += u32::try_from(export.len()).unwrap() + 1;
+        }
+        for export in exports {
+            section.extend_from_slice(export.as_bytes());
+            section.push(0);
+        }
+
+        let size = u32::try_from(section.len()).unwrap().to_le_bytes();
+        put(&mut image, 0xd0, b".edata");
+        put(&mut image, 0xd8, &size);
+        put(&mut image, 0xdc, &SECTION_RVA.to_le_bytes());
+        put(&mut image, 0xe0, &size);
+        put(
+            &mut image,
+            0xe4,
+            &u32::try_from(SECTION_OFFSET).unwrap().to_le_bytes(),
+        );
+        image.extend_from_slice(&section);
+        image
+    }
+
+    #[test]
+    fn exports() {
+        let exports = dll_exports(&image(&["FMOD_System_Create", "FMOD_System_Release"]));
+        assert_eq!(
+            exports.unwrap(),
+            ["FMOD_System_Create", "FMOD_System_Release"]
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let image = image(&["FMOD_System_Create"]);
+        for len in 0..image.len() {
+            assert_eq!(dll_exports(&image[..len]), None, "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn overflowing() {
+        // A section ending past 4 GiB.
+        let mut huge_section = image(&["FMOD_System_Create"]);
+        put(&mut huge_section, 0xd8, &u32::MAX.to_le_bytes());
+        assert_eq!(dll_exports(&huge_section), None);
+
+        // An export directory whose data would be past 4 GiB into the image.
+        let mut far_directory = image(&["FMOD_System_Create"]);
+        put(
+            &mut far_directory,
+            0xc8,
+            &(SECTION_RVA + 0xe000_0000).to_le_bytes(),
+        );
+        put(&mut far_directory, 0xd8, &0xf000_0000_u32.to_le_bytes());
+        put(&mut far_directory, 0xe4, &0x2000_0000_u32.to_le_bytes());
+        assert_eq!(dll_exports(&far_directory), None);
+    }
+}
@@ -0,0 +1,185 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Guards over the mocked functions, so that tests mocking the same function can run in
+//! parallel.
+//!
+//! mockall keeps the expectations of a mocked module's functions in statics, so two tests
+//! setting expectations on one function at the same time clobber each other. Each guard
+//! holds a lock for its function while a test uses its mock.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{FnArg, ForeignItemFn, ReturnType, Type};
+
+use crate::api_trait::foreign_functions;
+
+/// Generate the `mock_guards` module, with a function taking the mock of each function in
+/// `fns_only`, and a `default_ok` method on the guards of functions that return a result.
+///
+/// # Panics
+/// Panics if `fns_only` isn't valid Rust.
+#[must_use]
+pub fn mock_guards(fns_only: &str) -> String {
+    let file = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+    let guards = foreign_functions(&file).into_iter().map(|function| {
+        let name = &function.sig.ident;
+        let context_fn = format_ident!("{name}_context");
+        let context = format_ident!("__{name}");
+        let doc = format!(" Take the mock of [`functions::{name}`].");
+        let default_ok = default_ok(function).map(|body| {
+            quote! {
+                impl MockGuard<mock_functions::#context::Context> {
+                    /// Expect any number of calls, each returning success and writing a
+                    /// dummy handle through every handle out-parameter.
+                    pub fn default_ok(self) -> Self {
+                        #body
+                        self
+                    }
+                }
+            }
+        });
+        quote! {
+            #[doc = #doc]
+            pub fn #name() -> MockGuard<mock_functions::#context::Context> {
+                static LOCK: Mutex<()> = Mutex::new(());
+                MockGuard::new(&LOCK, mock_functions::#context_fn)
+            }
+
+            #default_ok
+        }
+    });
+
+    let tokens = quote! {
+        /// Guards over the mocks in [`mock_functions`], which let tests mocking the same
+        /// function run in parallel.
+        #[cfg(feature = "mockall")]
+        pub mod mock_guards {
+            use std::sync::{Mutex, MutexGuard, PoisonError};
+
+            use super::*;
+
+            /// Exclusive use of a mocked function, whose expectations are set through
+            /// the mockall `Context` this dereferences to. Tests taking the same function
+            /// wait for each other. Dropping the guard checks and clears the expectations
+            /// before letting the next test go ahead.
+            #[must_use]
+            pub struct MockGuard<C> {
+                // Dropped first, so the expectations are cleared while the lock is held.
+                context: C,
+                _lock: MutexGuard<'static, ()>,
+            }
+
+            impl<C> MockGuard<C> {
+                fn new(lock: &'static Mutex<()>, context: fn() -> C) -> Self {
+                    // A test failing while holding the lock doesn't break the mock.
+                    let lock = lock.lock().unwrap_or_else(PoisonError::into_inner);
+                    Self {
+                        context: context(),
+                        _lock: lock,
+                    }
+                }
+            }
+
+            impl<C> std::ops::Deref for MockGuard<C> {
+                type Target = C;
+
+                fn deref(&self) -> &C {
+                    &self.context
+                }
+            }
+
+            impl<C> std::fmt::Debug for MockGuard<C> {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("MockGuard").finish_non_exhaustive()
+                }
+            }
+
+            #(#guards)*
+        }
+    };
+    prettyplease::unparse(&syn::parse2(tokens).expect("the mock guards should be valid Rust"))
+}
+
+/// The body of `default_ok` for `function`, if it returns something that can succeed.
+fn default_ok(function: &ForeignItemFn) -> Option<TokenStream> {
+    let success = match &function.sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(path) => {
+                let ident = &path.path.segments.last()?.ident;
+                let name = ident.to_string();
+                if name == "FMOD_BOOL" {
+                    quote!(1)
+                } else {
+                    // `FMOD_RESULT::FMOD_OK`, `FSBANK_RESULT::FSBANK_OK` and so on.
+                    let ok = format_ident!("{}_OK", name.strip_suffix("_RESULT")?);
+                    quote!(#ident::#ok)
+                }
+            }
+            _ => return None,
+        },
+    };
+
+    let mut params = Vec::new();
+    let mut writes = Vec::new();
+    for (index, param) in function.sig.inputs.iter().enumerate() {
+        let FnArg::Typed(param) = param else {
+            return None;
+        };
+        if is_handle_out(&param.ty) {
+            let out = format_ident!("out{index}");
+            writes.push(quote! {
+                if !#out.is_null() {
+                    // The caller of the mocked function promises it's writable.
+                    unsafe { #out.write(std::ptr::NonNull::dangling().as_ptr()) };
+                }
+            });
+            params.push(quote!(#out));
+        } else {
+            params.push(quote!(_));
+        }
+    }
+    Some(quote! {
+        self.expect().returning(|#(#params),*| {
+            #(#writes)*
+            #success
+        });
+    })
+}
+
+/// Whether `ty` is `*mut *mut T`, through which FMOD returns handles.
+fn is_handle_out(ty: &Type) -> bool {
+    matches!(ty, Type::Ptr(outer)
+        if outer.mutability.is_some()
+            && matches!(&*outer.elem, Type::Ptr(inner) if inner.mutability.is_some()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guards() {
+        let fns_only = r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint) -> FMOD_RESULT;
+                pub fn FMOD_Studio_System_IsValid(system: *mut FMOD_STUDIO_SYSTEM) -> FMOD_BOOL;
+                pub fn FSBank_Release() -> FSBANK_RESULT;
+                pub fn FMOD_Memory_Alloc(size: ::core::ffi::c_uint) -> *mut ::core::ffi::c_void;
+            }
+        "#;
+        let generated = mock_guards(fns_only);
+        let generated = generated.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(generated.contains("pub fn FMOD_System_Create() -> MockGuard<"));
+        assert!(
+            generated.contains("MockGuard::new(&LOCK, mock_functions::FMOD_System_Create_context)")
+        );
+        assert!(generated.contains("returning(|out0, _| {"));
+        assert!(generated.contains("FMOD_RESULT::FMOD_OK"));
+        assert!(generated.contains("FSBANK_RESULT::FSBANK_OK"));
+        assert!(generated.contains("returning(|_| { 1 })"));
+        assert_eq!(generated.matches("pub fn default_ok").count(), 3);
+    }
+}
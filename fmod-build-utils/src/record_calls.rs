@@ -0,0 +1,896 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Recording the calls made through `functions` to a log, and replaying them, for the
+//! `record-calls` feature.
+//!
+//! A log starts with a header naming the functions it was recorded against, followed by
+//! one record per call: the function's index, its arguments, and what it returned. Handles
+//! are written as ids, given out in the order the handles were first seen, so a replay can
+//! map them to the handles it gets back. Other arguments are written as far as they can
+//! be replayed: values as their bytes, strings as their contents, values passed through
+//! `*const` pointers as their bytes, structs starting with `cbsize` as their first
+//! `cbsize` bytes, and other pointers only as whether they were null.
+
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Field, FnArg, ForeignItemFn, GenericArgument, Item, Pat, PathArguments, ReturnType, Type,
+};
+
+/// The Core API's handle types, which the Studio API uses without declaring.
+const CORE_HANDLES: &[&str] = &[
+    "FMOD_SYSTEM",
+    "FMOD_SOUND",
+    "FMOD_CHANNELCONTROL",
+    "FMOD_CHANNEL",
+    "FMOD_CHANNELGROUP",
+    "FMOD_SOUNDGROUP",
+    "FMOD_REVERB3D",
+    "FMOD_DSP",
+    "FMOD_DSPCONNECTION",
+    "FMOD_POLYGON",
+    "FMOD_GEOMETRY",
+    "FMOD_SYNCPOINT",
+];
+
+/// What the bindings other than the functions say about the types functions take.
+pub(crate) struct TypeInfo {
+    /// Opaque structs, which are only ever used through pointers, as handles.
+    handles: HashSet<String>,
+    /// Type aliases of optional function pointers.
+    callbacks: HashSet<String>,
+    /// Structs starting with a `cbsize` field, which callers set before FMOD fills them in.
+    sized: HashSet<String>,
+    /// The fields of the other structs.
+    structs: HashMap<String, Vec<Field>>,
+}
+
+impl TypeInfo {
+    /// Find the handle and callback types in `no_fns`.
+    pub(crate) fn from_bindings(no_fns: &str) -> Self {
+        let file = syn::parse_file(no_fns).expect("bindgen should generate valid Rust");
+        let mut handles = CORE_HANDLES
+            .iter()
+            .map(|&it| it.to_owned())
+            .collect::<HashSet<_>>();
+        let mut callbacks = HashSet::new();
+        let mut sized = HashSet::new();
+        let mut structs = HashMap::new();
+        for item in &file.items {
+            match item {
+                // bindgen declares opaque structs with just an `_unused` field.
+                Item::Struct(item)
+                    if item.fields.len() == 1
+                        && item.fields.iter().all(|field| {
+                            field.ident.as_ref().is_some_and(|it| it == "_unused")
+                        }) =>
+                {
+                    handles.insert(item.ident.to_string());
+                }
+                Item::Struct(item) => {
+                    let starts_with_size = item.fields.iter().next().is_some_and(|field| {
+                        field
+                            .ident
+                            .as_ref()
+                            .is_some_and(|it| it.to_string().eq_ignore_ascii_case("cbsize"))
+                    });
+                    if starts_with_size {
+                        sized.insert(item.ident.to_string());
+                    }
+                    structs.insert(
+                        item.ident.to_string(),
+                        item.fields.iter().cloned().collect(),
+                    );
+                }
+                Item::Type(item) if is_callback(&item.ty) => {
+                    callbacks.insert(item.ident.to_string());
+                }
+                _ => {}
+            }
+        }
+        Self {
+            handles,
+            callbacks,
+            sized,
+            structs,
+        }
+    }
+
+    pub(crate) fn is_handle(&self, ty: &Type) -> bool {
+        type_name(ty).is_some_and(|it| self.handles.contains(&it))
+    }
+
+    fn is_callback(&self, ty: &Type) -> bool {
+        is_callback(ty) || type_name(ty).is_some_and(|it| self.callbacks.contains(&it))
+    }
+
+    pub(crate) fn is_sized(&self, ty: &Type) -> bool {
+        type_name(ty).is_some_and(|it| self.sized.contains(&it))
+    }
+
+    /// The fields of the struct `ty` holding pointers or callbacks, including those of the
+    /// structs it holds, as paths from the struct.
+    fn pointer_fields(&self, ty: &Type) -> Vec<TokenStream> {
+        let Some(fields) = type_name(ty).and_then(|it| self.structs.get(&it)) else {
+            return Vec::new();
+        };
+        let mut paths = Vec::new();
+        for field in fields {
+            let Some(name) = &field.ident else {
+                continue;
+            };
+            if self.holds_pointer(&field.ty) {
+                paths.push(quote!(#name));
+            } else {
+                let inner = self.pointer_fields(&field.ty);
+                paths.extend(inner.into_iter().map(|path| quote!(#name.#path)));
+            }
+        }
+        paths
+    }
+
+    fn holds_pointer(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Ptr(_) => true,
+            Type::Array(array) => self.holds_pointer(&array.elem),
+            ty => self.is_callback(ty),
+        }
+    }
+}
+
+/// The last segment of `ty`'s path, if it's a path.
+pub(crate) fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|it| it.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is `Option<fn(...)>`.
+fn is_callback(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(last) = path.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return false;
+    };
+    last.ident == "Option"
+        && matches!(
+            args.args.first(),
+            Some(GenericArgument::Type(Type::BareFn(_)))
+        )
+}
+
+/// How a parameter is recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// A handle, as its id.
+    Handle { mutable: bool },
+    /// Where a handle is returned, as the id of the handle written there.
+    HandleOut,
+    /// A C string, as its contents.
+    Str,
+    /// A callback, as whether it was set. Replays don't set callbacks.
+    Callback,
+    /// A pointer to memory of unknown size, as whether it was null. Replays pass null.
+    Opaque { mutable: bool },
+    /// A pointer to a struct starting with `cbsize`, as its first `cbsize` bytes. Replays
+    /// pass a copy.
+    Sized { mutable: bool },
+    /// A `*const` pointer to a value, as the value's bytes. Replays pass a copy, followed by
+    /// zeroed memory in case it was the first of an array.
+    Pointee,
+    /// A pointer to something else, as whether it was null. Replays pass zeroed memory.
+    Pointer { mutable: bool },
+    /// Anything passed by value, as its bytes.
+    Value,
+}
+
+fn kind(info: &TypeInfo, param: &str, ty: &Type) -> Kind {
+    match ty {
+        Type::Ptr(outer) => {
+            let mutable = outer.mutability.is_some();
+            match &*outer.elem {
+                Type::Ptr(inner) if mutable && info.is_handle(&inner.elem) => Kind::HandleOut,
+                elem if info.is_handle(elem) => Kind::Handle { mutable },
+                // `FMOD_System_CreateSound` takes a name, or with `FMOD_OPENMEMORY` the data
+                // itself, so it can't be read as a string.
+                _ if param == "name_or_data" => Kind::Opaque { mutable },
+                elem if type_name(elem).as_deref() == Some("c_void") => Kind::Opaque { mutable },
+                elem if !mutable && type_name(elem).as_deref() == Some("c_char") => Kind::Str,
+                elem if info.is_sized(elem) => Kind::Sized { mutable },
+                // The values of pointers can't be replayed.
+                Type::Ptr(_) => Kind::Pointer { mutable },
+                _ if !mutable => Kind::Pointee,
+                _ => Kind::Pointer { mutable },
+            }
+        }
+        ty if info.is_callback(ty) => Kind::Callback,
+        _ => Kind::Value,
+    }
+}
+
+/// The named parameters of `function` and their types, with how each is recorded.
+fn params<'a>(
+    info: &TypeInfo,
+    function: &'a ForeignItemFn,
+) -> Vec<(&'a syn::Ident, &'a Type, Kind)> {
+    function
+        .sig
+        .inputs
+        .iter()
+        .map(|param| {
+            let FnArg::Typed(param) = param else {
+                panic!("FMOD functions don't take self");
+            };
+            let Pat::Ident(ident) = &*param.pat else {
+                panic!("bindgen should only generate named parameters");
+            };
+            let kind = kind(info, &ident.ident.to_string(), &param.ty);
+            (&ident.ident, &*param.ty, kind)
+        })
+        .collect()
+}
+
+/// An expression for whether the call to `function` returning `result` succeeded, which
+/// is always true for functions that don't return an FMOD or FSBank result.
+fn succeeded(function: &ForeignItemFn) -> TokenStream {
+    let ReturnType::Type(_, ty) = &function.sig.output else {
+        return quote!(true);
+    };
+    match type_name(ty).as_deref() {
+        Some("FMOD_RESULT") => quote!(result == FMOD_RESULT::FMOD_OK),
+        Some("FSBANK_RESULT") => quote!(result == FSBANK_RESULT::FSBANK_OK),
+        _ => quote!(true),
+    }
+}
+
+/// Statements for a shim, after the call, that record it as the function at `index`.
+pub(crate) fn record_call(info: &TypeInfo, function: &ForeignItemFn, index: usize) -> TokenStream {
+    let index = Literal::u16_unsuffixed(index.try_into().expect("there should be few functions"));
+    let params = params(info, function);
+    let succeeded = succeeded(function);
+    let writes = params.iter().map(|&(name, _, kind)| match kind {
+        Kind::Handle { mutable: true } => quote!(call.handle(#name.cast_const().cast());),
+        Kind::Handle { mutable: false } => quote!(call.handle(#name.cast());),
+        Kind::HandleOut => quote!(call.handle_out(#name.cast_const().cast(), #succeeded);),
+        Kind::Str => quote!(call.string(#name);),
+        Kind::Callback => quote!(call.flag(#name.is_some());),
+        Kind::Sized { mutable: true } => quote!(call.sized(#name.cast_const().cast());),
+        Kind::Sized { mutable: false } => quote!(call.sized(#name.cast());),
+        Kind::Pointee => quote!(call.pointee(#name);),
+        Kind::Opaque { .. } | Kind::Pointer { .. } => quote!(call.flag(!#name.is_null());),
+        Kind::Value => quote!(call.value(&#name);),
+    });
+    // Only reading through pointers is unsafe.
+    let unsafety = params
+        .iter()
+        .any(|(_, _, kind)| {
+            matches!(
+                kind,
+                Kind::HandleOut | Kind::Str | Kind::Sized { .. } | Kind::Pointee
+            )
+        })
+        .then(|| quote!(unsafe));
+    quote! {
+        if record::is_active() {
+            record::call(#index, |call| #unsafety {
+                #(#writes)*
+                call.value(&result);
+            });
+        }
+    }
+}
+
+/// A match arm replaying a call to `function`, recorded as the function at `index`.
+fn replay_arm(info: &TypeInfo, function: &ForeignItemFn, index: usize) -> TokenStream {
+    let index = Literal::u16_unsuffixed(index.try_into().expect("there should be few functions"));
+    let name = &function.sig.ident;
+    let params = params(info, function);
+    let mut reads = Vec::new();
+    let mut args = Vec::new();
+    let mut outs = Vec::new();
+    for (position, (_, ty, kind)) in params.iter().enumerate() {
+        let local = format_ident!("arg{position}");
+        let elem = match ty {
+            Type::Ptr(ptr) => Some(&*ptr.elem),
+            _ => None,
+        };
+        let (read, arg) = match kind {
+            Kind::Handle { mutable: true } => (
+                quote!(let #local = replay.handle()?;),
+                quote!(#local.cast()),
+            ),
+            Kind::Handle { mutable: false } => (
+                quote!(let #local = replay.handle()?;),
+                quote!(#local.cast_const().cast()),
+            ),
+            Kind::HandleOut => {
+                outs.push(quote!(replay.map_out(&#local);));
+                (
+                    quote!(let mut #local = replay.handle_out()?;),
+                    quote!(#local.as_ptr().cast()),
+                )
+            }
+            Kind::Str => (
+                quote!(let #local = replay.string()?;),
+                quote!(#local.as_ref().map_or(std::ptr::null(), |it| it.as_ptr())),
+            ),
+            Kind::Callback => (quote!(replay.flag()?;), quote!(None)),
+            Kind::Opaque { mutable } => {
+                let null = if *mutable {
+                    quote!(std::ptr::null_mut())
+                } else {
+                    quote!(std::ptr::null())
+                };
+                (quote!(replay.flag()?;), null)
+            }
+            Kind::Sized { .. } | Kind::Pointee => {
+                let elem = elem.expect("only pointers point to values");
+                // What the recorded pointers pointed to is gone.
+                let clear = info.pointer_fields(elem);
+                let read = if clear.is_empty() {
+                    quote!(let mut #local = replay.memory()?;)
+                } else {
+                    quote! {
+                        let mut #local = replay.memory()?;
+                        if let Some(memory) = &mut #local {
+                            let value = memory.as_mut_ptr().cast::<#elem>();
+                            #((*value).#clear = std::mem::zeroed();)*
+                        }
+                    }
+                };
+                let arg = if matches!(kind, Kind::Sized { mutable: true }) {
+                    quote!(scratch(&mut #local))
+                } else {
+                    quote!(scratch::<#elem>(&mut #local).cast_const())
+                };
+                (read, arg)
+            }
+            Kind::Pointer { mutable: true } => (
+                quote!(let mut #local = replay.pointer()?;),
+                quote!(scratch(&mut #local)),
+            ),
+            Kind::Pointer { mutable: false } => {
+                let elem = elem.expect("only pointers are pointers");
+                (
+                    quote!(let mut #local = replay.pointer()?;),
+                    quote!(scratch::<#elem>(&mut #local).cast_const()),
+                )
+            }
+            Kind::Value => (quote!(let #local = replay.value()?;), quote!(#local)),
+        };
+        reads.push(read);
+        args.push(arg);
+    }
+    let succeeded = succeeded(function);
+    let outs = (!outs.is_empty()).then(|| {
+        quote! {
+            if #succeeded {
+                #(#outs)*
+            }
+        }
+    });
+    quote! {
+        #index => {
+            #(#reads)*
+            let recorded = replay.value()?;
+            let result = api.#name(#(#args),*);
+            #outs
+            diverged(&recorded, &result)
+        }
+    }
+}
+
+/// A hash of the function names, so a log is only replayed against the functions it was
+/// recorded against.
+fn functions_hash(names: &[String]) -> u64 {
+    // FNV-1a, which is simple enough to be the same wherever the log is read.
+    names
+        .join("\n")
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// The `record` module, for `functions`, to be replayed through `trait_name`.
+pub(crate) fn record_module(
+    info: &TypeInfo,
+    functions: &[&ForeignItemFn],
+    trait_name: &str,
+) -> TokenStream {
+    let names = functions
+        .iter()
+        .map(|it| it.sig.ident.to_string())
+        .collect::<Vec<_>>();
+    let hash = functions_hash(&names);
+    let arms = functions
+        .iter()
+        .enumerate()
+        .map(|(index, function)| replay_arm(info, function, index));
+    let trait_name = format_ident!("{trait_name}");
+    quote! {
+        /// Recording the calls made through [`functions`] to a log, and replaying them.
+        ///
+        /// A log holds, for each call, the function, its arguments and what it returned.
+        /// Handles are written as ids, so that a replay can use the handles it gets back in
+        /// their place. Strings and values are kept, as are values passed through `*const`
+        /// pointers and the first `cbsize` bytes of structs starting with `cbsize`, which a
+        /// replay passes copies of with any pointers in them cleared. Other pointers are
+        /// only noted as null or not: a replay passes zeroed memory for them, or null for
+        /// `void` pointers and for the data of sounds opened from memory, and sets no
+        /// callbacks. Only the first value of an array is kept. Calls that depended on what
+        /// wasn't kept may return something else when replayed, and are reported as
+        /// divergences.
+        ///
+        /// Recording should start before the first handle is created, since handles that
+        /// were created before are unknown to a replay.
+        #[cfg(feature = "record-calls")]
+        pub mod record {
+            use std::{
+                collections::HashMap,
+                ffi::{c_char, c_int, c_void, CStr, CString},
+                io::{self, Read, Write},
+                sync::{
+                    atomic::{AtomicBool, Ordering},
+                    Mutex, PoisonError,
+                },
+            };
+
+            use super::*;
+
+            const MAGIC: &[u8; 8] = b"FMODCALL";
+            const FORMAT: u16 = 2;
+            const FUNCTIONS: &[&str] = &[#(#names),*];
+            #[allow(clippy::unreadable_literal)]
+            const FUNCTIONS_HASH: u64 = #hash;
+            /// How much memory a replay passes for each pointer, at least.
+            const SCRATCH_WORDS: usize = 8192;
+
+            static ACTIVE: AtomicBool = AtomicBool::new(false);
+            static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+            struct Recorder {
+                log: Box<dyn Write + Send>,
+                handles: HashMap<usize, u32>,
+                next_id: u32,
+                buffer: Vec<u8>,
+                error: Option<io::Error>,
+            }
+
+            /// Start recording the calls made through [`functions`] to `log`, which should
+            /// be buffered. A recording that was already going is stopped first.
+            ///
+            /// # Errors
+            /// Fails if the header can't be written, or if stopping a recording fails.
+            pub fn start(mut log: impl Write + Send + 'static) -> io::Result<()> {
+                stop()?;
+                log.write_all(MAGIC)?;
+                log.write_all(&FORMAT.to_le_bytes())?;
+                log.write_all(&FUNCTIONS_HASH.to_le_bytes())?;
+                *RECORDER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Recorder {
+                    log: Box::new(log),
+                    handles: HashMap::new(),
+                    next_id: 1,
+                    buffer: Vec::new(),
+                    error: None,
+                });
+                ACTIVE.store(true, Ordering::Release);
+                Ok(())
+            }
+
+            /// Stop recording, and flush the log.
+            ///
+            /// # Errors
+            /// Fails with the first error writing the log hit, which also stopped the
+            /// recording when it happened.
+            pub fn stop() -> io::Result<()> {
+                ACTIVE.store(false, Ordering::Release);
+                let recorder = RECORDER.lock().unwrap_or_else(PoisonError::into_inner).take();
+                match recorder {
+                    Some(Recorder { error: Some(error), .. }) => Err(error),
+                    Some(mut recorder) => recorder.log.flush(),
+                    None => Ok(()),
+                }
+            }
+
+            #[inline]
+            pub(crate) fn is_active() -> bool {
+                ACTIVE.load(Ordering::Acquire)
+            }
+
+            /// Write a call to the function at `function`, with `write` writing the rest.
+            pub(crate) fn call(function: u16, write: impl FnOnce(&mut Call<'_>)) {
+                let mut recorder = RECORDER.lock().unwrap_or_else(PoisonError::into_inner);
+                let Some(recorder) = recorder.as_mut().filter(|it| it.error.is_none()) else {
+                    return;
+                };
+                let mut buffer = std::mem::take(&mut recorder.buffer);
+                buffer.clear();
+                buffer.extend_from_slice(&function.to_le_bytes());
+                write(&mut Call {
+                    buffer: &mut buffer,
+                    handles: &mut recorder.handles,
+                    next_id: &mut recorder.next_id,
+                });
+                if let Err(error) = recorder.log.write_all(&buffer) {
+                    recorder.error = Some(error);
+                    ACTIVE.store(false, Ordering::Release);
+                }
+                recorder.buffer = buffer;
+            }
+
+            /// One call being written.
+            pub(crate) struct Call<'a> {
+                buffer: &'a mut Vec<u8>,
+                handles: &'a mut HashMap<usize, u32>,
+                next_id: &'a mut u32,
+            }
+
+            impl Call<'_> {
+                fn fresh_id(&mut self, handle: usize) -> u32 {
+                    let id = *self.next_id;
+                    *self.next_id += 1;
+                    self.handles.insert(handle, id);
+                    id
+                }
+
+                pub(crate) fn handle(&mut self, handle: *const c_void) {
+                    let id = match handle.addr() {
+                        0 => 0,
+                        addr => match self.handles.get(&addr) {
+                            Some(&id) => id,
+                            None => self.fresh_id(addr),
+                        },
+                    };
+                    self.buffer.extend_from_slice(&id.to_le_bytes());
+                }
+
+                /// Write where a handle was returned, and the handle, if the call `succeeded`.
+                ///
+                /// # Safety
+                /// `out` must be null, or readable if the call succeeded.
+                pub(crate) unsafe fn handle_out(&mut self, out: *const *const c_void, succeeded: bool) {
+                    self.flag(!out.is_null());
+                    // Failed calls may not have written a handle.
+                    let handle = match out.as_ref() {
+                        Some(handle) if succeeded => handle.addr(),
+                        _ => 0,
+                    };
+                    // Handles that are given out again are new as far as a replay knows.
+                    let id = match handle {
+                        0 => 0,
+                        addr => self.fresh_id(addr),
+                    };
+                    self.buffer.extend_from_slice(&id.to_le_bytes());
+                }
+
+                /// # Safety
+                /// `string` must be null, or a C string.
+                pub(crate) unsafe fn string(&mut self, string: *const c_char) {
+                    if string.is_null() {
+                        self.buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+                        return;
+                    }
+                    let string = CStr::from_ptr(string).to_bytes();
+                    let len = u32::try_from(string.len()).unwrap_or(u32::MAX - 1);
+                    self.buffer.extend_from_slice(&len.to_le_bytes());
+                    self.buffer.extend_from_slice(&string[..len as usize]);
+                }
+
+                /// # Safety
+                /// `pointer` must be null, or readable for a `T`.
+                pub(crate) unsafe fn pointee<T>(&mut self, pointer: *const T) {
+                    self.memory(pointer.cast(), size_of::<T>());
+                }
+
+                /// # Safety
+                /// `pointer` must be null, or point to a struct starting with its size as a
+                /// `c_int`, and be readable for that size.
+                pub(crate) unsafe fn sized(&mut self, pointer: *const c_void) {
+                    let size = pointer
+                        .cast::<c_int>()
+                        .as_ref()
+                        .map_or(0, |&it| usize::try_from(it).unwrap_or(0));
+                    self.memory(pointer.cast(), size);
+                }
+
+                /// # Safety
+                /// `pointer` must be null, or readable for `len` bytes.
+                unsafe fn memory(&mut self, pointer: *const u8, len: usize) {
+                    if pointer.is_null() {
+                        self.buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+                        return;
+                    }
+                    let len = u32::try_from(len).unwrap_or(u32::MAX - 1);
+                    self.buffer.extend_from_slice(&len.to_le_bytes());
+                    self.buffer
+                        .extend_from_slice(std::slice::from_raw_parts(pointer, len as usize));
+                }
+
+                pub(crate) fn flag(&mut self, flag: bool) {
+                    self.buffer.push(u8::from(flag));
+                }
+
+                /// Write `value` as its bytes. Values are scalars, or structs of them.
+                pub(crate) fn value<T: Copy>(&mut self, value: &T) {
+                    // SAFETY: `value` is a reference, so it's readable for its size.
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(
+                            std::ptr::from_ref(value).cast::<u8>(),
+                            size_of::<T>(),
+                        )
+                    };
+                    self.buffer.extend_from_slice(bytes);
+                }
+            }
+
+            /// A call whose replay returned something other than what was recorded.
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct Divergence {
+                /// The position of the call in the log, from 0.
+                pub call: usize,
+                /// The function called.
+                pub function: &'static str,
+                /// What the call returned when recorded.
+                pub recorded: String,
+                /// What the call returned when replayed.
+                pub replayed: String,
+            }
+
+            struct Replay<R> {
+                log: R,
+                handles: HashMap<u32, *mut c_void>,
+            }
+
+            /// Where a replayed call returns a handle.
+            struct HandleOut {
+                slot: Option<Box<*mut c_void>>,
+                id: u32,
+            }
+
+            impl HandleOut {
+                fn as_ptr(&mut self) -> *mut *mut c_void {
+                    self.slot
+                        .as_deref_mut()
+                        .map_or(std::ptr::null_mut(), std::ptr::from_mut)
+                }
+            }
+
+            impl<R: Read> Replay<R> {
+                fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+                    let mut bytes = [0; N];
+                    self.log.read_exact(&mut bytes)?;
+                    Ok(bytes)
+                }
+
+                /// The next function, or `None` at the end of the log.
+                fn function(&mut self) -> io::Result<Option<u16>> {
+                    let mut first = [0];
+                    if self.log.read(&mut first)? == 0 {
+                        return Ok(None);
+                    }
+                    let [second] = self.bytes()?;
+                    Ok(Some(u16::from_le_bytes([first[0], second])))
+                }
+
+                fn id(&mut self) -> io::Result<u32> {
+                    Ok(u32::from_le_bytes(self.bytes()?))
+                }
+
+                fn handle(&mut self) -> io::Result<*mut c_void> {
+                    let id = self.id()?;
+                    Ok(self.handles.get(&id).copied().unwrap_or(std::ptr::null_mut()))
+                }
+
+                fn handle_out(&mut self) -> io::Result<HandleOut> {
+                    let slot = self.flag()?.then(|| Box::new(std::ptr::null_mut()));
+                    Ok(HandleOut { slot, id: self.id()? })
+                }
+
+                fn map_out(&mut self, out: &HandleOut) {
+                    if let Some(&handle) = out.slot.as_deref() {
+                        if out.id != 0 && !handle.is_null() {
+                            self.handles.insert(out.id, handle);
+                        }
+                    }
+                }
+
+                fn string(&mut self) -> io::Result<Option<CString>> {
+                    let len = self.id()?;
+                    if len == u32::MAX {
+                        return Ok(None);
+                    }
+                    let mut string = vec![0; len as usize];
+                    self.log.read_exact(&mut string)?;
+                    CString::new(string)
+                        .map(Some)
+                        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+                }
+
+                fn flag(&mut self) -> io::Result<bool> {
+                    let [flag] = self.bytes()?;
+                    Ok(flag != 0)
+                }
+
+                fn pointer(&mut self) -> io::Result<Option<Vec<u64>>> {
+                    Ok(self.flag()?.then(|| vec![0; SCRATCH_WORDS]))
+                }
+
+                /// Memory starting with the recorded bytes, or `None` for null.
+                fn memory(&mut self) -> io::Result<Option<Vec<u64>>> {
+                    let len = self.id()?;
+                    if len == u32::MAX {
+                        return Ok(None);
+                    }
+                    let len = len as usize;
+                    let mut memory = vec![0_u64; SCRATCH_WORDS.max(len.div_ceil(8))];
+                    // SAFETY: the words are initialized, and at least `len` bytes long.
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts_mut(memory.as_mut_ptr().cast::<u8>(), len)
+                    };
+                    self.log.read_exact(bytes)?;
+                    Ok(Some(memory))
+                }
+
+                /// # Safety
+                /// The next bytes must have been written from a `T`.
+                unsafe fn value<T: Copy>(&mut self) -> io::Result<T> {
+                    let mut bytes = vec![0; size_of::<T>()];
+                    self.log.read_exact(&mut bytes)?;
+                    Ok(bytes.as_ptr().cast::<T>().read_unaligned())
+                }
+            }
+
+            fn scratch<T>(scratch: &mut Option<Vec<u64>>) -> *mut T {
+                scratch
+                    .as_mut()
+                    .map_or(std::ptr::null_mut(), |it| it.as_mut_ptr().cast())
+            }
+
+            fn diverged<T: PartialEq + std::fmt::Debug>(
+                recorded: &T,
+                replayed: &T,
+            ) -> Option<(String, String)> {
+                (recorded != replayed).then(|| (format!("{recorded:?}"), format!("{replayed:?}")))
+            }
+
+            /// Make the calls recorded in `log` through `api`, which can be the real
+            /// functions or a mock, and return the calls that returned something other
+            /// than what was recorded.
+            ///
+            /// # Errors
+            /// Fails if `log` can't be read, or wasn't recorded against these functions.
+            ///
+            /// # Safety
+            /// The calls are made as described in the [module docs](self), which can still
+            /// hand FMOD less memory than it uses, such as when it fills an array. `log`
+            /// should come from the same program, against the same version of FMOD.
+            #[allow(clippy::too_many_lines)]
+            pub unsafe fn replay(
+                log: impl Read,
+                api: &impl #trait_name,
+            ) -> io::Result<Vec<Divergence>> {
+                let mut replay = Replay {
+                    log,
+                    handles: HashMap::new(),
+                };
+                let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+                if &replay.bytes::<8>()? != MAGIC {
+                    return Err(invalid("not a log of FMOD calls"));
+                }
+                if u16::from_le_bytes(replay.bytes()?) != FORMAT {
+                    return Err(invalid("the log is from another version of the recorder"));
+                }
+                if u64::from_le_bytes(replay.bytes()?) != FUNCTIONS_HASH {
+                    return Err(invalid("the log was recorded against other functions"));
+                }
+
+                let mut divergences = Vec::new();
+                let mut call = 0;
+                while let Some(function) = replay.function()? {
+                    let diverged = match function {
+                        #(#arms)*
+                        _ => return Err(invalid("the log calls an unknown function")),
+                    };
+                    if let Some((recorded, replayed)) = diverged {
+                        divergences.push(Divergence {
+                            call,
+                            function: FUNCTIONS[usize::from(function)],
+                            recorded,
+                            replayed,
+                        });
+                    }
+                    call += 1;
+                }
+                Ok(divergences)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds() {
+        let info = TypeInfo::from_bindings(
+            r"
+            pub struct FMOD_STUDIO_SYSTEM { _unused: [u8; 0] }
+            pub struct FMOD_VECTOR { pub x: f32, pub y: f32, pub z: f32 }
+            pub struct FMOD_CREATESOUNDEXINFO { pub cbsize: ::core::ffi::c_int, pub length: ::core::ffi::c_uint }
+            pub type FMOD_STUDIO_SYSTEM_CALLBACK = ::core::option::Option<unsafe extern fn(system: *mut FMOD_STUDIO_SYSTEM)>;
+            ",
+        );
+        let kind = |param: &str, ty: &str| kind(&info, param, &syn::parse_str(ty).unwrap());
+        assert_eq!(
+            kind("system", "*mut *mut FMOD_STUDIO_SYSTEM"),
+            Kind::HandleOut
+        );
+        assert_eq!(kind("system", "*mut *mut FMOD_SYSTEM"), Kind::HandleOut);
+        assert_eq!(
+            kind("system", "*const FMOD_STUDIO_SYSTEM"),
+            Kind::Handle { mutable: false }
+        );
+        assert_eq!(kind("path", "*const ::core::ffi::c_char"), Kind::Str);
+        assert_eq!(
+            kind("name", "*mut ::core::ffi::c_char"),
+            Kind::Pointer { mutable: true }
+        );
+        assert_eq!(
+            kind("name_or_data", "*const ::core::ffi::c_char"),
+            Kind::Opaque { mutable: false }
+        );
+        assert_eq!(
+            kind("userdata", "*mut *mut ::core::ffi::c_void"),
+            Kind::Pointer { mutable: true }
+        );
+        assert_eq!(
+            kind("names", "*const *const ::core::ffi::c_char"),
+            Kind::Pointer { mutable: false }
+        );
+        assert_eq!(kind("position", "*const FMOD_VECTOR"), Kind::Pointee);
+        assert_eq!(
+            kind("position", "*mut FMOD_VECTOR"),
+            Kind::Pointer { mutable: true }
+        );
+        assert_eq!(
+            kind("exinfo", "*mut FMOD_CREATESOUNDEXINFO"),
+            Kind::Sized { mutable: true }
+        );
+        assert_eq!(
+            kind("callback", "FMOD_STUDIO_SYSTEM_CALLBACK"),
+            Kind::Callback
+        );
+        assert_eq!(kind("volume", "f32"), Kind::Value);
+    }
+
+    #[test]
+    fn pointer_fields() {
+        let info = TypeInfo::from_bindings(
+            r"
+            pub struct FMOD_VECTOR { pub x: f32, pub y: f32, pub z: f32 }
+            pub struct FMOD_ASYNCREADINFO { pub handle: *mut ::core::ffi::c_void, pub offset: ::core::ffi::c_uint }
+            pub struct FMOD_3D_ATTRIBUTES { pub position: FMOD_VECTOR, pub read: FMOD_ASYNCREADINFO, pub done: FMOD_FILE_ASYNCDONE_FUNC }
+            pub type FMOD_FILE_ASYNCDONE_FUNC = ::core::option::Option<unsafe extern fn(info: *mut FMOD_ASYNCREADINFO)>;
+            ",
+        );
+        let fields = |ty: &str| {
+            info.pointer_fields(&syn::parse_str(ty).unwrap())
+                .iter()
+                .map(|it| it.to_string().replace(' ', ""))
+                .collect::<Vec<_>>()
+        };
+        assert!(fields("FMOD_VECTOR").is_empty());
+        assert_eq!(fields("FMOD_3D_ATTRIBUTES"), ["read.handle", "done"]);
+    }
+}
@@ -0,0 +1,409 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{fmt, fs, str::FromStr};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{Api, LibDir};
+
+/// An FMOD version, as encoded in `FMOD_VERSION`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FmodVersion {
+    pub product: u16,
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl FmodVersion {
+    /// Decode `FMOD_VERSION`, which is laid out as `0xaaaabbcc`.
+    #[must_use]
+    pub fn from_encoded(encoded: u32) -> Self {
+        Self {
+            product: (encoded >> 16) as u16,
+            major: (encoded >> 8) as u8,
+            minor: encoded as u8,
+        }
+    }
+
+    /// The name of the cfg set when building against this version or a later one, as in
+    /// `fmod_at_least_2_02_22`.
+    #[must_use]
+    pub fn cfg_name(self) -> String {
+        format!(
+            "fmod_at_least_{:x}_{:02x}_{:02x}",
+            self.product, self.major, self.minor
+        )
+    }
+
+    /// The version of the SDK whose Core headers are in `core_include_dir`, as read from
+    /// `fmod_common.h`.
+    #[must_use]
+    pub fn from_headers(core_include_dir: &Utf8Path) -> Option<Self> {
+        let header = fs::read_to_string(core_include_dir.join("fmod_common.h")).ok()?;
+        header.lines().find_map(|line| {
+            let mut words = line.split_whitespace();
+            if words.next()? != "#define" || words.next()? != "FMOD_VERSION" {
+                return None;
+            }
+            let encoded = words.next()?;
+            let encoded = encoded
+                .strip_prefix("0x")
+                .or_else(|| encoded.strip_prefix("0X"))?;
+            u32::from_str_radix(encoded, 16)
+                .ok()
+                .map(Self::from_encoded)
+        })
+    }
+}
+
+impl FromStr for FmodVersion {
+    type Err = String;
+
+    /// Parses a version written the way FMOD writes it, as in `2.02.22`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{s}` isn't an FMOD version like `2.02.22`");
+        let mut parts = s.split('.');
+        let mut part = || parts.next().ok_or_else(invalid);
+        let (product, major, minor) = (part()?, part()?, part()?);
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            product: u16::from_str_radix(product, 16).map_err(|_| invalid())?,
+            major: u8::from_str_radix(major, 16).map_err(|_| invalid())?,
+            minor: u8::from_str_radix(minor, 16).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for FmodVersion {
+    /// Formats the version the way FMOD writes it, as in `2.02.22`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:x}.{:02x}.{:02x}",
+            self.product, self.major, self.minor
+        )
+    }
+}
+
+/// The include and library directories of one FMOD API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiPaths {
+    pub include_dir: Utf8PathBuf,
+    pub lib_dir: LibDir,
+}
+
+impl ApiPaths {
+    /// The paths of `api` in the official SDK layout, `api/<name>/{inc,lib}`.
+    #[must_use]
+    pub fn in_sdk(sdk_dir: &Utf8Path, api: Api) -> Self {
+        let api_dir = sdk_dir.join("api").join(api.dir_name());
+        Self {
+            include_dir: api_dir.join("inc"),
+            lib_dir: LibDir::Sdk(api_dir.join("lib")),
+        }
+    }
+}
+
+/// Looks up an environment variable. Build scripts read the process environment, with
+/// [`process_env`], and tests pass their own.
+pub(crate) type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Look up `var` in the environment Cargo runs the build script in.
+pub(crate) fn process_env(var: &str) -> Option<String> {
+    std::env::var(var).ok()
+}
+
+/// Read a per-API directory override, such as `FMOD_STUDIO_LIB_DIR`.
+pub(crate) fn env_override(api: Api, what: &str, env: Env<'_>) -> Option<Utf8PathBuf> {
+    let var = format!("FMOD_{}_{what}", api.dir_name().to_uppercase());
+    println!("cargo::rerun-if-env-changed={var}");
+    env(&var).map(Utf8PathBuf::from)
+}
+
+/// Ask pkg-config where `api` is installed, returning its include and library directories.
+#[cfg(feature = "pkg-config")]
+pub(crate) fn probe_pkg_config(api: Api) -> Option<(Option<Utf8PathBuf>, Option<Utf8PathBuf>)> {
+    let package = api.lib_name();
+    let library = pkg_config::Config::new()
+        .cargo_metadata(false)
+        .env_metadata(true)
+        .probe(package)
+        .ok()?;
+    // pkg-config leaves out the system directories, so fall back to the package's variables.
+    let variable = |name| {
+        pkg_config::get_variable(package, name)
+            .ok()
+            .filter(|it| !it.is_empty())
+            .map(Utf8PathBuf::from)
+    };
+    let include_dir = library
+        .include_paths
+        .iter()
+        .find_map(|it| Utf8PathBuf::from_path_buf(it.clone()).ok())
+        .or_else(|| variable("includedir"));
+    let lib_dir = library
+        .link_paths
+        .iter()
+        .find_map(|it| Utf8PathBuf::from_path_buf(it.clone()).ok())
+        .or_else(|| variable("libdir"));
+    Some((include_dir, lib_dir))
+}
+
+/// Find the root of the FMOD SDK to build `api` against.
+///
+/// `FMOD_SDK_DIR_<TARGET>` is preferred, so that cross builds can use a different SDK
+/// package for each target, then `FMOD_SDK_DIR`, then `FMOD_DIR`, which older versions of
+/// these crates used. When none of them are set, some well-known
+/// places are searched, and the newest SDK that has `api` is used:
+///
+/// - `fmod-sdk` in the crate's directory or its workspace root;
+/// - `~/.local/share/fmod`;
+/// - the SDK packages' own extraction folders, such as `fmodstudioapi20222linux`, in any of
+///   the above, or in `/opt`.
+///
+/// The chosen SDK's `fmod_common.h` is registered with `cargo::rerun-if-changed`, so that
+/// upgrading the SDK in place re-runs the build script.
+#[must_use]
+pub fn find_sdk_dir(api: Api) -> Option<Utf8PathBuf> {
+    find_sdk_dir_in(api, &process_env)
+}
+
+/// [`find_sdk_dir`], with the variables looked up in `env`.
+pub(crate) fn find_sdk_dir_in(api: Api, env: Env<'_>) -> Option<Utf8PathBuf> {
+    let target = env("TARGET");
+    let target_vars = target
+        .iter()
+        .flat_map(|target| {
+            [
+                format!("FMOD_SDK_DIR_{target}"),
+                format!("FMOD_SDK_DIR_{}", target.replace('-', "_")),
+            ]
+        })
+        .chain(["FMOD_SDK_DIR".to_owned(), "FMOD_DIR".to_owned()]);
+    for var in target_vars {
+        println!("cargo::rerun-if-env-changed={var}");
+        if let Some(dir) = env(&var) {
+            let dir = Utf8PathBuf::from(dir);
+            rerun_if_sdk_changed(&dir);
+            return Some(dir);
+        }
+    }
+
+    let (dir, version) = sdk_candidates(env)
+        .filter(|dir| ApiPaths::in_sdk(dir, api).include_dir.is_dir())
+        .filter_map(|dir| {
+            let version =
+                FmodVersion::from_headers(&ApiPaths::in_sdk(&dir, Api::Core).include_dir)?;
+            Some((dir, version))
+        })
+        .max_by_key(|(_, version)| *version)?;
+    println!("cargo::warning=FMOD_SDK_DIR is unset; using FMOD {version} found at {dir}");
+    rerun_if_sdk_changed(&dir);
+    Some(dir)
+}
+
+/// Re-run the build script when the SDK at `dir` is replaced; `fmod_common.h` carries the
+/// version, so it changes with every release.
+fn rerun_if_sdk_changed(dir: &Utf8Path) {
+    let header = ApiPaths::in_sdk(dir, Api::Core)
+        .include_dir
+        .join("fmod_common.h");
+    println!("cargo::rerun-if-changed={header}");
+}
+
+/// Every directory that might hold an FMOD SDK, whether or not it exists.
+///
+/// Only these directories, `/opt` and the crate and workspace roots are read.
+fn sdk_candidates(env: Env<'_>) -> impl Iterator<Item = Utf8PathBuf> {
+    let roots = env("CARGO_MANIFEST_DIR")
+        .map(|dir| {
+            let dir = Utf8PathBuf::from(dir);
+            let workspace = workspace_root(&dir);
+            let mut roots = vec![dir];
+            roots.extend(workspace.filter(|it| *it != roots[0]));
+            roots
+        })
+        .unwrap_or_default();
+    let user_dir = env("HOME").or_else(|| env("USERPROFILE")).map(|home| {
+        Utf8PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join("fmod")
+    });
+
+    let named = roots
+        .iter()
+        .map(|dir| dir.join("fmod-sdk"))
+        .chain(user_dir)
+        .collect::<Vec<_>>();
+    let extracted = named
+        .iter()
+        .chain(&roots)
+        .cloned()
+        .chain([Utf8PathBuf::from("/opt")])
+        .flat_map(|dir| extraction_folders(&dir))
+        .collect::<Vec<_>>();
+    named.into_iter().chain(extracted)
+}
+
+/// The outermost ancestor of `manifest_dir` with a `Cargo.toml`, which is the workspace
+/// root for crates built from a checkout.
+///
+/// Cargo doesn't tell build scripts where the workspace is, and crates from a registry have
+/// none, in which case this is `manifest_dir` itself.
+fn workspace_root(manifest_dir: &Utf8Path) -> Option<Utf8PathBuf> {
+    manifest_dir
+        .ancestors()
+        .filter(|dir| dir.join("Cargo.toml").is_file())
+        .last()
+        .map(Utf8Path::to_path_buf)
+}
+
+/// The folders in `dir` that are named like the FMOD SDK packages extract to.
+fn extraction_folders(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let Ok(entries) = dir.read_dir_utf8() else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().starts_with("fmodstudioapi"))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// An [`Env`] with just `vars` set.
+#[cfg(test)]
+pub(crate) fn test_env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    |var| {
+        vars.iter()
+            .find(|(name, _)| *name == var)
+            .map(|(_, value)| (*value).to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch directory for the test called `name`.
+    fn scratch(name: &str) -> Utf8PathBuf {
+        let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fmod-sdk-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Lay out an SDK at `dir` with the headers of `apis`, at the encoded `version`.
+    fn write_sdk(dir: &Utf8Path, version: u32, apis: &[Api]) {
+        let core = ApiPaths::in_sdk(dir, Api::Core).include_dir;
+        fs::create_dir_all(&core).unwrap();
+        fs::write(
+            core.join("fmod_common.h"),
+            format!("#define FMOD_VERSION    0x{version:08x}    /* 0xaaaabbcc */\n"),
+        )
+        .unwrap();
+        for &api in apis {
+            fs::create_dir_all(ApiPaths::in_sdk(dir, api).include_dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn version_from_headers() {
+        let dir = scratch("version");
+        assert_eq!(FmodVersion::from_headers(&dir), None);
+        fs::write(dir.join("fmod_common.h"), "#define FMOD_VERSION_H\n").unwrap();
+        assert_eq!(FmodVersion::from_headers(&dir), None);
+        fs::write(
+            dir.join("fmod_common.h"),
+            "#define FMOD_VERSION_H\n#define FMOD_VERSION    0X00020222 /* comment */\n",
+        )
+        .unwrap();
+        assert_eq!(
+            FmodVersion::from_headers(&dir),
+            Some(FmodVersion::from_encoded(0x0002_0222))
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn version_strings() {
+        for (string, encoded) in [
+            ("2.02.22", 0x0002_0222),
+            ("2.03.00", 0x0002_0300),
+            ("10.0a.ff", 0x0010_0aff),
+        ] {
+            let version = string.parse::<FmodVersion>().unwrap();
+            assert_eq!(version, FmodVersion::from_encoded(encoded));
+            assert_eq!(version.to_string(), string);
+        }
+        for invalid in ["", "2", "2.02", "2.02.22.1", "2.02.x", "2.0222.00"] {
+            assert!(invalid.parse::<FmodVersion>().is_err(), "{invalid}");
+        }
+        assert_eq!(
+            FmodVersion::from_encoded(0x0002_0222).cfg_name(),
+            "fmod_at_least_2_02_22"
+        );
+    }
+
+    #[test]
+    fn sdk_dir_precedence() {
+        let mut vars = vec![
+            ("TARGET", "x86_64-unknown-linux-gnu"),
+            ("FMOD_SDK_DIR_x86_64-unknown-linux-gnu", "/target"),
+            (
+                "FMOD_SDK_DIR_x86_64_unknown_linux_gnu",
+                "/target_underscored",
+            ),
+            ("FMOD_SDK_DIR", "/sdk"),
+            ("FMOD_DIR", "/old"),
+        ];
+        for expected in ["/target", "/target_underscored", "/sdk", "/old"] {
+            let found = find_sdk_dir_in(Api::Core, &test_env(&vars));
+            assert_eq!(found.as_deref(), Some(expected.into()));
+            vars.remove(1);
+        }
+    }
+
+    #[test]
+    fn newest_sdk_is_discovered() {
+        let dir = scratch("discovery");
+        let home = dir.join("home");
+        // Newer than anything that could be installed in `/opt`.
+        write_sdk(&dir.join("fmod-sdk"), 0xffff_0001, &[Api::Studio]);
+        write_sdk(&home.join(".local/share/fmod"), 0xffff_0100, &[Api::Studio]);
+        write_sdk(&dir.join("fmodstudioapiffff0200linux"), 0xffff_0200, &[]);
+        // Not an SDK at all.
+        fs::create_dir_all(dir.join("fmodstudioapi-notes")).unwrap();
+
+        let vars = [
+            ("CARGO_MANIFEST_DIR", dir.as_str()),
+            ("HOME", home.as_str()),
+        ];
+        let env = test_env(&vars);
+        assert_eq!(
+            find_sdk_dir_in(Api::Core, &env),
+            Some(dir.join("fmodstudioapiffff0200linux"))
+        );
+        // The newest SDK doesn't have Studio.
+        assert_eq!(
+            find_sdk_dir_in(Api::Studio, &env),
+            Some(home.join(".local/share/fmod"))
+        );
+        // Overrides win over whatever could be found.
+        let vars = [
+            ("CARGO_MANIFEST_DIR", dir.as_str()),
+            ("FMOD_SDK_DIR", "/sdk"),
+        ];
+        assert_eq!(
+            find_sdk_dir_in(Api::Core, &test_env(&vars)).as_deref(),
+            Some("/sdk".into())
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
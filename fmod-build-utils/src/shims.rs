@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Shims over every function of an API, which log calls with `tracing` for the
+//! `trace-calls` feature, and record them for the `record-calls` feature.
+//!
+//! With either feature, `functions` holds shims with the signatures of the real functions,
+//! which call them through the private `raw_functions` module. For tracing, each shim
+//! belongs to a family, named after its function with the API prefix and the method name
+//! taken off, such as `System` or `Studio_EventInstance`, and each family's level can be
+//! set at runtime through the generated `trace` module. Recording is controlled through the
+//! generated `record` module, described in [`record_calls`](crate::record_calls).
+
+use std::collections::BTreeSet;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::quote;
+use syn::{FnArg, ForeignItem, ForeignItemFn, Item, LitStr, Pat, Type};
+
+use crate::record_calls::{record_call, record_module, TypeInfo};
+
+/// Generate the shimmed `functions` module, `raw_functions`, and the `trace` and `record`
+/// modules for the functions in `fns_only`. `no_fns` holds the types they use, and
+/// `trait_name` is the API trait replays go through. `fns_only` should also be placed in a
+/// `functions` module that is only built without the `trace-calls` and `record-calls`
+/// features.
+///
+/// # Panics
+/// Panics if `fns_only` or `no_fns` isn't valid Rust.
+#[must_use]
+pub fn shims(fns_only: &str, no_fns: &str, trait_name: &str) -> String {
+    let file = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+    let info = TypeInfo::from_bindings(no_fns);
+    let foreign = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::ForeignMod(foreign) => Some(foreign),
+            _ => None,
+        })
+        .flat_map(|foreign| {
+            foreign.items.iter().filter_map(move |item| match item {
+                ForeignItem::Fn(function) if function.sig.variadic.is_none() => {
+                    Some((&foreign.abi, function))
+                }
+                _ => None,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let families = foreign
+        .iter()
+        .map(|(_, function)| family(&function.sig.ident.to_string()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let shims = foreign.iter().enumerate().map(|(index, (abi, function))| {
+        let family = families
+            .binary_search(&family(&function.sig.ident.to_string()))
+            .expect("every family should be known");
+        let family = Literal::usize_unsuffixed(family);
+        shim(abi, function, &family, &record_call(&info, function, index))
+    });
+    let family_count = families.len();
+    let raw = &file.items;
+    let functions = foreign.iter().map(|(_, it)| *it).collect::<Vec<_>>();
+    let record = record_module(&info, &functions, trait_name);
+
+    let tokens = quote! {
+        /// All functions live in this module. With the `trace-calls` feature, each one logs
+        /// its calls through `trace`, and with `record-calls`, records them through
+        /// `record`, before returning what FMOD returned.
+        #[cfg(any(feature = "trace-calls", feature = "record-calls"))]
+        #[cfg_attr(feature = "mockall", automock)]
+        #[allow(
+            clippy::missing_safety_doc,
+            clippy::must_use_candidate,
+            clippy::too_many_arguments
+        )]
+        pub mod functions {
+            use super::*;
+
+            #(#shims)*
+        }
+
+        /// The functions the shims in [`functions`] call.
+        #[cfg(any(feature = "trace-calls", feature = "record-calls"))]
+        mod raw_functions {
+            use super::*;
+
+            #(#raw)*
+        }
+
+        /// Control over how calls through [`functions`] are logged.
+        ///
+        /// Every call is logged as a `tracing` event with the target `fmod_calls`, with the
+        /// function, its arguments, how long it took and what it returned. The level of each
+        /// family of functions, such as `System` or `Studio_EventInstance`, can be set, or
+        /// the family silenced. All families start at `DEBUG`.
+        #[cfg(feature = "trace-calls")]
+        pub mod trace {
+            use std::{
+                fmt,
+                sync::atomic::{AtomicU8, Ordering},
+                time::Duration,
+            };
+
+            pub use tracing::Level;
+
+            /// The families of functions, whose levels can be set.
+            pub const FAMILIES: &[&str] = &[#(#families),*];
+
+            const OFF: u8 = 0;
+            const DEBUG: u8 = 4;
+            static LEVELS: [AtomicU8; #family_count] =
+                [const { AtomicU8::new(DEBUG) }; #family_count];
+
+            /// Log calls to the functions of `family` at `level`, or not at all with `None`.
+            /// Returns `false` if there's no such family.
+            pub fn set_level(family: &str, level: Option<Level>) -> bool {
+                let Some(index) = FAMILIES.iter().position(|it| *it == family) else {
+                    return false;
+                };
+                LEVELS[index].store(encode(level), Ordering::Relaxed);
+                true
+            }
+
+            /// Log calls to all functions at `level`, or not at all with `None`.
+            pub fn set_all_levels(level: Option<Level>) {
+                for it in &LEVELS {
+                    it.store(encode(level), Ordering::Relaxed);
+                }
+            }
+
+            fn encode(level: Option<Level>) -> u8 {
+                match level {
+                    None => OFF,
+                    Some(Level::ERROR) => 1,
+                    Some(Level::WARN) => 2,
+                    Some(Level::INFO) => 3,
+                    Some(Level::DEBUG) => DEBUG,
+                    Some(Level::TRACE) => 5,
+                }
+            }
+
+            #[inline]
+            pub(crate) fn level(family: usize) -> Option<Level> {
+                match LEVELS[family].load(Ordering::Relaxed) {
+                    1 => Some(Level::ERROR),
+                    2 => Some(Level::WARN),
+                    3 => Some(Level::INFO),
+                    DEBUG => Some(Level::DEBUG),
+                    5 => Some(Level::TRACE),
+                    _ => None,
+                }
+            }
+
+            pub(crate) fn event(
+                level: Level,
+                function: &'static str,
+                args: fmt::Arguments<'_>,
+                elapsed: Duration,
+                result: &dyn fmt::Debug,
+            ) {
+                // Levels have to be known where an event is declared.
+                macro_rules! event {
+                    ($level:expr) => {
+                        tracing::event!(
+                            target: "fmod_calls",
+                            $level,
+                            function,
+                            args = %args,
+                            ?elapsed,
+                            result = ?result,
+                        )
+                    };
+                }
+                match level {
+                    Level::ERROR => event!(Level::ERROR),
+                    Level::WARN => event!(Level::WARN),
+                    Level::INFO => event!(Level::INFO),
+                    Level::DEBUG => event!(Level::DEBUG),
+                    Level::TRACE => event!(Level::TRACE),
+                }
+            }
+        }
+
+        #record
+    };
+    prettyplease::unparse(&syn::parse2(tokens).expect("the shims should be valid Rust"))
+}
+
+/// The family of the function `name`: `FMOD_Studio_EventInstance_Start` is in
+/// `Studio_EventInstance`, and `FSBank_Build` is in `FSBank`.
+pub(crate) fn family(name: &str) -> String {
+    let name = name.strip_prefix("FMOD_").unwrap_or(name);
+    match name.rsplit_once('_') {
+        Some((family, _)) => family.to_owned(),
+        None => name.to_owned(),
+    }
+}
+
+/// A shim for `function`, in the family at `family`, which runs `record` after the call.
+fn shim(
+    abi: &syn::Abi,
+    function: &ForeignItemFn,
+    family: &Literal,
+    record: &TokenStream,
+) -> TokenStream {
+    let ForeignItemFn { attrs, sig, .. } = function;
+    let docs = attrs.iter().filter(|it| it.path().is_ident("doc"));
+    let name = &sig.ident;
+    let params = &sig.inputs;
+    let output = &sig.output;
+    let (args, formats): (Vec<_>, Vec<_>) = sig
+        .inputs
+        .iter()
+        .map(|param| {
+            let FnArg::Typed(param) = param else {
+                panic!("FMOD functions don't take self");
+            };
+            let Pat::Ident(ident) = &*param.pat else {
+                panic!("bindgen should only generate named parameters");
+            };
+            let format = if matches!(*param.ty, Type::Ptr(_)) {
+                format!("{0}={{{0}:p}}", ident.ident)
+            } else {
+                format!("{0}={{{0}:?}}", ident.ident)
+            };
+            (&ident.ident, format)
+        })
+        .unzip();
+    let format = LitStr::new(&formats.join(", "), Span::call_site());
+    let name_str = LitStr::new(&name.to_string(), Span::call_site());
+    quote! {
+        #(#docs)*
+        #[inline]
+        pub unsafe #abi fn #name(#params) #output {
+            #[cfg(feature = "trace-calls")]
+            let traced = trace::level(#family).map(|level| (level, std::time::Instant::now()));
+            let result = raw_functions::#name(#(#args),*);
+            #[cfg(feature = "trace-calls")]
+            if let Some((level, start)) = traced {
+                trace::event(
+                    level,
+                    #name_str,
+                    format_args!(#format),
+                    start.elapsed(),
+                    &result,
+                );
+            }
+            #[cfg(feature = "record-calls")]
+            #record
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn families() {
+        assert_eq!(family("FMOD_System_Create"), "System");
+        assert_eq!(
+            family("FMOD_Studio_EventInstance_Start"),
+            "Studio_EventInstance"
+        );
+        assert_eq!(family("FSBank_Build"), "FSBank");
+    }
+
+    #[test]
+    fn generated() {
+        let fns_only = r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint) -> FMOD_RESULT;
+                pub fn FMOD_Studio_System_IsValid(system: *mut FMOD_STUDIO_SYSTEM) -> FMOD_BOOL;
+            }
+        "#;
+        let no_fns = "pub struct FMOD_STUDIO_SYSTEM { _unused: [u8; 0] }";
+        let generated = shims(fns_only, no_fns, "FmodApi");
+        let generated = generated.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(
+            generated.contains("pub const FAMILIES: &[&str] = &[\"Studio_System\", \"System\"];")
+        );
+        assert!(generated.contains("let traced = trace::level(1)"));
+        assert!(generated.contains("record::call( 0, |call| unsafe { call.handle_out("));
+        assert!(generated.contains("record::call( 1, |call| { call.handle(system.cast_const()"));
+        assert!(generated
+            .contains("format_args!(\"system={system:p}, headerversion={headerversion:?}\")"));
+        assert!(generated.contains("mod raw_functions { use super::*; extern \"C\" {"));
+    }
+}
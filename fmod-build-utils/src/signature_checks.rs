@@ -0,0 +1,127 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Checks that the mocked functions have the signatures of the real ones.
+//!
+//! With the `mockall` feature, code is written against `functions` and tested against
+//! `mock_functions`, so an upgrade of bindgen or mockall that made them differ would only
+//! show up in the tests of the crates using them. The checks are built with the sys crates'
+//! own tests instead, and fail to compile if any function's parameters or return type
+//! differ.
+
+use proc_macro2::Span;
+use quote::quote;
+use syn::{FnArg, ForeignItem, Item, LitStr};
+
+/// Generate the `signature_checks` module for the functions in `fns_only`.
+///
+/// # Panics
+/// Panics if `fns_only` isn't valid Rust.
+#[must_use]
+pub fn signature_checks(fns_only: &str) -> String {
+    let file = syn::parse_file(fns_only).expect("bindgen should generate valid Rust");
+    let mut checks = Vec::new();
+    let mut shim_checks = Vec::new();
+    for foreign in file.items.iter().filter_map(|item| match item {
+        Item::ForeignMod(foreign) => Some(foreign),
+        _ => None,
+    }) {
+        let abi = foreign
+            .abi
+            .name
+            .as_ref()
+            .map_or_else(|| "C".to_owned(), LitStr::value);
+        // mockall gives mocks of "C" functions the "C-unwind" ABI, so that they can panic.
+        let mock_abi = if abi == "C" { "C-unwind" } else { &abi };
+        let (abi, mock_abi) = (
+            LitStr::new(&abi, Span::call_site()),
+            LitStr::new(mock_abi, Span::call_site()),
+        );
+        for function in foreign.items.iter().filter_map(|item| match item {
+            ForeignItem::Fn(function) if function.sig.variadic.is_none() => Some(function),
+            _ => None,
+        }) {
+            let name = &function.sig.ident;
+            let params = function.sig.inputs.iter().map(|input| match input {
+                FnArg::Typed(param) => &param.ty,
+                FnArg::Receiver(_) => panic!("foreign functions don't take self"),
+            });
+            let params = quote!(#(#params),*);
+            let output = &function.sig.output;
+            checks.push(quote! {
+                {
+                    let real: unsafe extern #abi fn(#params) #output = functions::#name;
+                    let mock: unsafe extern #mock_abi fn(#params) #output = mock_functions::#name;
+                    (stringify!(#name), real as usize, mock as usize)
+                }
+            });
+            // With `trace-calls` or `record-calls`, the mocks are of the shims, which keep
+            // the ABI, and it's the functions the shims call that resolve to the mocks.
+            shim_checks.push(quote! {
+                {
+                    let _: unsafe extern #abi fn(#params) #output = functions::#name;
+                    let raw: unsafe extern #abi fn(#params) #output = raw_functions::#name;
+                    let mock: unsafe extern #abi fn(#params) #output = mock_functions::#name;
+                    (stringify!(#name), raw as usize, mock as usize)
+                }
+            });
+        }
+    }
+
+    let count = checks.len();
+    let tokens = quote! {
+        /// Checks that each function in [`mock_functions`] takes and returns the same types
+        /// as the one in [`functions`], by assigning both to the same function pointer type.
+        /// mockall exports each mock under the name of the C function, so the tests also
+        /// check that calling the C function calls the mock.
+        #[cfg(all(test, feature = "mockall"))]
+        mod signature_checks {
+            use super::*;
+
+            #[cfg(not(any(feature = "trace-calls", feature = "record-calls")))]
+            #[test]
+            fn mocks_match_functions() {
+                let functions: [(&str, usize, usize); #count] = [#(#checks),*];
+                for (name, real, mock) in functions {
+                    assert_eq!(real, mock, "{name} should resolve to its mock");
+                }
+            }
+
+            #[cfg(any(feature = "trace-calls", feature = "record-calls"))]
+            #[test]
+            fn mocks_match_functions() {
+                let functions: [(&str, usize, usize); #count] = [#(#shim_checks),*];
+                for (name, real, mock) in functions {
+                    assert_eq!(real, mock, "{name} should resolve to its mock");
+                }
+            }
+        }
+    };
+    prettyplease::unparse(&syn::parse2(tokens).expect("the signature checks should be valid Rust"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks() {
+        let fns_only = r#"
+            extern "C" {
+                pub fn FMOD_System_Create(system: *mut *mut FMOD_SYSTEM, headerversion: ::core::ffi::c_uint) -> FMOD_RESULT;
+                pub fn FSBank_Release() -> FSBANK_RESULT;
+            }
+        "#;
+        let generated = signature_checks(fns_only);
+        let generated = generated.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(generated.contains(
+            "let mock: unsafe extern \"C-unwind\" fn( *mut *mut FMOD_SYSTEM, ::core::ffi::c_uint, ) -> FMOD_RESULT = mock_functions::FMOD_System_Create;"
+        ));
+        assert!(generated.contains(
+            "let real: unsafe extern \"C\" fn() -> FSBANK_RESULT = functions::FSBank_Release;"
+        ));
+        assert!(generated.contains("[(&str, usize, usize); 2usize]"));
+        assert!(generated.contains("= raw_functions::FMOD_System_Create;"));
+    }
+}
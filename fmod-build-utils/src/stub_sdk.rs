@@ -0,0 +1,354 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! A stand-in for the FMOD SDK, for exercising the build scripts where the real SDK can't
+//! be, such as in CI.
+//!
+//! The stub SDK has the official layout, with headers that declare a representative subset
+//! of each API: enums, flag macros, `cbsize` structs and callbacks. Its libraries export
+//! every declared function. They return `FMOD_OK`, and write a dummy handle through any
+//! handle out-parameter, without doing anything else.
+//!
+//! Building the libraries needs the `stub-sdk` feature. The headers are always available,
+//! as documentation-only builds generate bindings from them when a crate has no stored
+//! bindings.
+
+#[cfg(feature = "stub-sdk")]
+use std::fmt::Write as _;
+use std::fs;
+
+use camino::Utf8PathBuf;
+
+#[cfg(feature = "stub-sdk")]
+use crate::lib_layout;
+use crate::{Api, ApiPaths, FmodVersion};
+
+const CORE_HEADERS: &[(&str, &str)] = &[
+    ("fmod.h", include_str!("../stub-sdk/core/fmod.h")),
+    (
+        "fmod_codec.h",
+        include_str!("../stub-sdk/core/fmod_codec.h"),
+    ),
+    (
+        "fmod_common.h",
+        include_str!("../stub-sdk/core/fmod_common.h"),
+    ),
+    ("fmod_dsp.h", include_str!("../stub-sdk/core/fmod_dsp.h")),
+    (
+        "fmod_dsp_effects.h",
+        include_str!("../stub-sdk/core/fmod_dsp_effects.h"),
+    ),
+    (
+        "fmod_errors.h",
+        include_str!("../stub-sdk/core/fmod_errors.h"),
+    ),
+    (
+        "fmod_output.h",
+        include_str!("../stub-sdk/core/fmod_output.h"),
+    ),
+];
+const STUDIO_HEADERS: &[(&str, &str)] = &[
+    (
+        "fmod_studio.h",
+        include_str!("../stub-sdk/studio/fmod_studio.h"),
+    ),
+    (
+        "fmod_studio_common.h",
+        include_str!("../stub-sdk/studio/fmod_studio_common.h"),
+    ),
+];
+const FSBANK_HEADERS: &[(&str, &str)] = &[
+    ("fsbank.h", include_str!("../stub-sdk/fsbank/fsbank.h")),
+    (
+        "fsbank_errors.h",
+        include_str!("../stub-sdk/fsbank/fsbank_errors.h"),
+    ),
+];
+
+/// A stub FMOD SDK, to be written out with `StubSdk::write`, which needs the `stub-sdk`
+/// feature, or [`StubSdk::write_headers`] if only the headers are needed.
+#[derive(Debug, Clone)]
+pub struct StubSdk {
+    root: Utf8PathBuf,
+    version: FmodVersion,
+    #[cfg(feature = "stub-sdk")]
+    target: String,
+}
+
+impl StubSdk {
+    /// A stub SDK rooted at `root`, for the target being built for, or else the host.
+    pub fn new(root: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            version: FmodVersion {
+                product: 2,
+                major: 2,
+                minor: 0x22,
+            },
+            #[cfg(feature = "stub-sdk")]
+            target: std::env::var("TARGET").unwrap_or_else(|_| target_lexicon::HOST.to_string()),
+        }
+    }
+
+    /// Set the version written to `FMOD_VERSION`.
+    #[must_use]
+    pub fn version(mut self, version: FmodVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Set the target to build the stub libraries for.
+    #[cfg(feature = "stub-sdk")]
+    #[must_use]
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = target.into();
+        self
+    }
+
+    /// Write the headers of all three APIs, without building any libraries.
+    ///
+    /// # Panics
+    /// Panics if the headers can't be written.
+    pub fn write_headers(&self) {
+        let encoded = format!(
+            "0x{:04x}{:02x}{:02x}",
+            self.version.product, self.version.major, self.version.minor
+        );
+        for (api, headers) in [
+            (Api::Core, CORE_HEADERS),
+            (Api::Studio, STUDIO_HEADERS),
+            (Api::Fsbank, FSBANK_HEADERS),
+        ] {
+            let ApiPaths { include_dir, .. } = ApiPaths::in_sdk(&self.root, api);
+            fs::create_dir_all(&include_dir)
+                .unwrap_or_else(|err| panic!("couldn't create {include_dir}: {err}"));
+            for (name, contents) in headers {
+                let contents = contents.replace("@FMOD_VERSION@", &encoded);
+                fs::write(include_dir.join(name), contents)
+                    .unwrap_or_else(|err| panic!("couldn't write {name}: {err}"));
+            }
+        }
+    }
+
+    /// Write the headers, and build the libraries, of all three APIs.
+    ///
+    /// # Panics
+    /// Panics if the SDK can't be written, if the FMOD SDK has no shared libraries for the
+    /// target, or if the libraries fail to build.
+    #[cfg(feature = "stub-sdk")]
+    pub fn write(&self) {
+        self.write_headers();
+        for (api, headers) in [
+            (Api::Core, CORE_HEADERS),
+            (Api::Studio, STUDIO_HEADERS),
+            (Api::Fsbank, FSBANK_HEADERS),
+        ] {
+            let source = stub_source(headers);
+            for debug_logging in [false, true] {
+                self.build_lib(api, api.lib_name(), debug_logging, &source);
+            }
+        }
+        // The FSBank libraries link against FMOD's builds of these.
+        if self.target.contains("linux") || self.target.contains("apple") {
+            for lib_name in ["fsbvorbis", "opus"] {
+                self.build_lib(Api::Fsbank, lib_name, false, "");
+            }
+        }
+    }
+
+    /// Build one stub shared library from `source`, and put it where the SDK has it.
+    #[cfg(feature = "stub-sdk")]
+    fn build_lib(&self, api: Api, lib_name: &str, debug_logging: bool, source: &str) {
+        let target = &self.target;
+        let layout = lib_layout(target, lib_name, debug_logging)
+            .unwrap_or_else(|| panic!("unsupported target triple {target}"));
+        let (Some(dylib_file), None) = (layout.dylib_file, layout.dll_file) else {
+            panic!("stub SDKs can only be made for targets with shared objects, not {target}");
+        };
+
+        let ApiPaths { include_dir, .. } = ApiPaths::in_sdk(&self.root, api);
+        // The Studio headers include the Core headers, as in the real SDK.
+        let core_include_dir = ApiPaths::in_sdk(&self.root, Api::Core).include_dir;
+        let lib_dir = self
+            .root
+            .join("api")
+            .join(api.dir_name())
+            .join("lib")
+            .join(layout.arch_dir);
+        fs::create_dir_all(&lib_dir)
+            .unwrap_or_else(|err| panic!("couldn't create {lib_dir}: {err}"));
+        let source_path = lib_dir.join(format!("{lib_name}_stub.c"));
+        fs::write(&source_path, source)
+            .unwrap_or_else(|err| panic!("couldn't write {source_path}: {err}"));
+
+        let compiler = cc::Build::new()
+            .target(target)
+            .host(&target_lexicon::HOST.to_string())
+            .opt_level(0)
+            .debug(false)
+            .warnings(false)
+            .pic(true)
+            .cargo_metadata(false)
+            .get_compiler();
+        let status = compiler
+            .to_command()
+            .arg(if target.contains("apple") {
+                "-dynamiclib"
+            } else {
+                "-shared"
+            })
+            .arg("-DDLL_EXPORTS")
+            .arg(format!("-I{include_dir}"))
+            .arg(format!("-I{core_include_dir}"))
+            .arg("-o")
+            .arg(lib_dir.join(&dylib_file))
+            .arg(&source_path)
+            .status();
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => panic!("building {dylib_file} failed ({status})"),
+            Err(err) => panic!("couldn't run {:?}: {err}", compiler.path()),
+        }
+    }
+}
+
+/// C source defining every function declared in `headers`.
+#[cfg(feature = "stub-sdk")]
+fn stub_source(headers: &[(&str, &str)]) -> String {
+    let mut source = String::new();
+    for (name, _) in headers {
+        writeln!(source, "#include \"{name}\"").unwrap();
+    }
+    source.push_str("\nstatic char stub_handle;\n");
+
+    for declaration in headers
+        .iter()
+        .flat_map(|(_, contents)| contents.lines())
+        .filter_map(parse_declaration)
+    {
+        let Declaration {
+            return_type,
+            api_macro,
+            name,
+            params,
+        } = declaration;
+        writeln!(
+            source,
+            "\n{return_type} {api_macro} {name}({})\n{{",
+            params.join(", ")
+        )
+        .unwrap();
+        for param in &params {
+            if param.contains("**") {
+                let out = param.rsplit(['*', ' ']).next().unwrap();
+                writeln!(source, "    *{out} = (void *)&stub_handle;").unwrap();
+            }
+        }
+        // `FMOD_BOOL` functions are the `IsValid` checks, and stub handles are always valid.
+        let value = if return_type == "FMOD_BOOL" { 1 } else { 0 };
+        writeln!(source, "    return {value};\n}}").unwrap();
+    }
+    source
+}
+
+#[cfg(feature = "stub-sdk")]
+struct Declaration<'a> {
+    return_type: &'a str,
+    api_macro: &'a str,
+    name: &'a str,
+    params: Vec<&'a str>,
+}
+
+/// Parse a one-line function declaration, such as
+/// `FMOD_RESULT F_API FMOD_System_Close(FMOD_SYSTEM *system);`.
+#[cfg(feature = "stub-sdk")]
+fn parse_declaration(line: &str) -> Option<Declaration<'_>> {
+    let line = line.trim().strip_suffix(");")?;
+    let mut words = line.splitn(3, ' ');
+    let return_type = words.next()?;
+    let api_macro = words.next().filter(|it| ["F_API", "FB_API"].contains(it))?;
+    let (name, params) = words.next()?.split_once('(')?;
+    let params = params
+        .split(',')
+        .map(str::trim)
+        .filter(|it| !it.is_empty() && *it != "void")
+        .collect();
+    Some(Declaration {
+        return_type,
+        api_macro,
+        name: name.trim(),
+        params,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "stub-sdk")]
+    fn declarations() {
+        let declaration =
+            parse_declaration("FMOD_RESULT F_API FMOD_System_Create   (FMOD_SYSTEM **system, unsigned int headerversion);")
+                .unwrap();
+        assert_eq!(declaration.name, "FMOD_System_Create");
+        assert_eq!(
+            declaration.params,
+            ["FMOD_SYSTEM **system", "unsigned int headerversion"]
+        );
+        assert!(
+            parse_declaration("FSBANK_RESULT FB_API FSBank_Release(void);")
+                .unwrap()
+                .params
+                .is_empty()
+        );
+        assert!(parse_declaration(
+            "typedef FMOD_RESULT (F_CALL *FMOD_DSP_CREATE_CALLBACK)  (FMOD_DSP_STATE *dsp_state);"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn write_headers_only() {
+        let root = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fmod-stub-headers-{}", std::process::id()));
+        StubSdk::new(&root).write_headers();
+
+        for (api, header) in [
+            (Api::Core, "fmod.h"),
+            (Api::Studio, "fmod_studio.h"),
+            (Api::Fsbank, "fsbank.h"),
+        ] {
+            let header = ApiPaths::in_sdk(&root, api).include_dir.join(header);
+            assert!(header.is_file(), "{header} is missing");
+        }
+        assert!(!root.join("api").join("core").join("lib").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "stub-sdk")]
+    fn write_for_host() {
+        let root = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fmod-stub-sdk-{}", std::process::id()));
+        let target = target_lexicon::HOST.to_string();
+        StubSdk::new(&root).target(&target).write();
+
+        let version = FmodVersion::from_headers(&ApiPaths::in_sdk(&root, Api::Core).include_dir);
+        assert_eq!(version.map(|it| it.to_string()).as_deref(), Some("2.02.22"));
+        for api in [Api::Core, Api::Studio, Api::Fsbank] {
+            let layout = lib_layout(&target, api.lib_name(), false).unwrap();
+            let lib = root
+                .join("api")
+                .join(api.dir_name())
+                .join("lib")
+                .join(layout.arch_dir)
+                .join(layout.dylib_file.unwrap());
+            assert!(lib.is_file(), "{lib} is missing");
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
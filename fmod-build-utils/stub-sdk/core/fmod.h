@@ -0,0 +1,169 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD Core API's C header, with a representative subset of its
+ * functions, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_H
+#define _FMOD_H
+
+#include "fmod_common.h"
+#include "fmod_codec.h"
+#include "fmod_dsp.h"
+#include "fmod_output.h"
+
+#ifdef __cplusplus
+extern "C"
+{
+#endif
+
+FMOD_RESULT F_API FMOD_Debug_Initialize(FMOD_DEBUG_FLAGS flags, FMOD_DEBUG_MODE mode, FMOD_DEBUG_CALLBACK callback, const char *filename);
+
+FMOD_RESULT F_API FMOD_System_Create             (FMOD_SYSTEM **system, unsigned int headerversion);
+FMOD_RESULT F_API FMOD_System_Release            (FMOD_SYSTEM *system);
+FMOD_RESULT F_API FMOD_System_SetOutput          (FMOD_SYSTEM *system, FMOD_OUTPUTTYPE output);
+FMOD_RESULT F_API FMOD_System_GetOutput          (FMOD_SYSTEM *system, FMOD_OUTPUTTYPE *output);
+FMOD_RESULT F_API FMOD_System_SetSoftwareFormat  (FMOD_SYSTEM *system, int samplerate, FMOD_SPEAKERMODE speakermode, int numrawspeakers);
+FMOD_RESULT F_API FMOD_System_GetSoftwareFormat  (FMOD_SYSTEM *system, int *samplerate, FMOD_SPEAKERMODE *speakermode, int *numrawspeakers);
+FMOD_RESULT F_API FMOD_System_SetDSPBufferSize   (FMOD_SYSTEM *system, unsigned int bufferlength, int numbuffers);
+FMOD_RESULT F_API FMOD_System_GetDSPBufferSize   (FMOD_SYSTEM *system, unsigned int *bufferlength, int *numbuffers);
+FMOD_RESULT F_API FMOD_System_SetCallback        (FMOD_SYSTEM *system, FMOD_SYSTEM_CALLBACK callback, FMOD_SYSTEM_CALLBACK_TYPE callbackmask);
+FMOD_RESULT F_API FMOD_System_Init               (FMOD_SYSTEM *system, int maxchannels, FMOD_INITFLAGS flags, void *extradriverdata);
+FMOD_RESULT F_API FMOD_System_Close              (FMOD_SYSTEM *system);
+FMOD_RESULT F_API FMOD_System_Update             (FMOD_SYSTEM *system);
+FMOD_RESULT F_API FMOD_System_GetVersion         (FMOD_SYSTEM *system, unsigned int *version);
+FMOD_RESULT F_API FMOD_System_GetChannelsPlaying (FMOD_SYSTEM *system, int *channels, int *realchannels);
+FMOD_RESULT F_API FMOD_System_CreateSound        (FMOD_SYSTEM *system, const char *name_or_data, FMOD_MODE mode, FMOD_CREATESOUNDEXINFO *exinfo, FMOD_SOUND **sound);
+FMOD_RESULT F_API FMOD_System_CreateStream       (FMOD_SYSTEM *system, const char *name_or_data, FMOD_MODE mode, FMOD_CREATESOUNDEXINFO *exinfo, FMOD_SOUND **sound);
+FMOD_RESULT F_API FMOD_System_CreateDSPByType    (FMOD_SYSTEM *system, FMOD_DSP_TYPE type, FMOD_DSP **dsp);
+FMOD_RESULT F_API FMOD_System_CreateChannelGroup (FMOD_SYSTEM *system, const char *name, FMOD_CHANNELGROUP **channelgroup);
+FMOD_RESULT F_API FMOD_System_PlaySound          (FMOD_SYSTEM *system, FMOD_SOUND *sound, FMOD_CHANNELGROUP *channelgroup, FMOD_BOOL paused, FMOD_CHANNEL **channel);
+FMOD_RESULT F_API FMOD_System_GetChannel         (FMOD_SYSTEM *system, int channelid, FMOD_CHANNEL **channel);
+FMOD_RESULT F_API FMOD_System_GetMasterChannelGroup(FMOD_SYSTEM *system, FMOD_CHANNELGROUP **channelgroup);
+FMOD_RESULT F_API FMOD_System_SetUserData        (FMOD_SYSTEM *system, void *userdata);
+FMOD_RESULT F_API FMOD_System_GetUserData        (FMOD_SYSTEM *system, void **userdata);
+
+FMOD_RESULT F_API FMOD_Sound_Release             (FMOD_SOUND *sound);
+FMOD_RESULT F_API FMOD_Sound_GetSystemObject     (FMOD_SOUND *sound, FMOD_SYSTEM **system);
+FMOD_RESULT F_API FMOD_Sound_SetDefaults         (FMOD_SOUND *sound, float frequency, int priority);
+FMOD_RESULT F_API FMOD_Sound_GetDefaults         (FMOD_SOUND *sound, float *frequency, int *priority);
+FMOD_RESULT F_API FMOD_Sound_GetLength           (FMOD_SOUND *sound, unsigned int *length, FMOD_TIMEUNIT lengthtype);
+FMOD_RESULT F_API FMOD_Sound_GetName             (FMOD_SOUND *sound, char *name, int namelen);
+FMOD_RESULT F_API FMOD_Sound_SetMode             (FMOD_SOUND *sound, FMOD_MODE mode);
+FMOD_RESULT F_API FMOD_Sound_GetMode             (FMOD_SOUND *sound, FMOD_MODE *mode);
+FMOD_RESULT F_API FMOD_Sound_SetLoopCount        (FMOD_SOUND *sound, int loopcount);
+FMOD_RESULT F_API FMOD_Sound_GetLoopCount        (FMOD_SOUND *sound, int *loopcount);
+FMOD_RESULT F_API FMOD_Sound_SetUserData         (FMOD_SOUND *sound, void *userdata);
+FMOD_RESULT F_API FMOD_Sound_GetUserData         (FMOD_SOUND *sound, void **userdata);
+
+FMOD_RESULT F_API FMOD_Channel_GetSystemObject   (FMOD_CHANNEL *channel, FMOD_SYSTEM **system);
+FMOD_RESULT F_API FMOD_Channel_Stop              (FMOD_CHANNEL *channel);
+FMOD_RESULT F_API FMOD_Channel_SetPaused         (FMOD_CHANNEL *channel, FMOD_BOOL paused);
+FMOD_RESULT F_API FMOD_Channel_GetPaused         (FMOD_CHANNEL *channel, FMOD_BOOL *paused);
+FMOD_RESULT F_API FMOD_Channel_SetVolume         (FMOD_CHANNEL *channel, float volume);
+FMOD_RESULT F_API FMOD_Channel_GetVolume         (FMOD_CHANNEL *channel, float *volume);
+FMOD_RESULT F_API FMOD_Channel_SetPitch          (FMOD_CHANNEL *channel, float pitch);
+FMOD_RESULT F_API FMOD_Channel_GetPitch          (FMOD_CHANNEL *channel, float *pitch);
+FMOD_RESULT F_API FMOD_Channel_SetMute           (FMOD_CHANNEL *channel, FMOD_BOOL mute);
+FMOD_RESULT F_API FMOD_Channel_GetMute           (FMOD_CHANNEL *channel, FMOD_BOOL *mute);
+FMOD_RESULT F_API FMOD_Channel_SetReverbProperties(FMOD_CHANNEL *channel, int instance, float wet);
+FMOD_RESULT F_API FMOD_Channel_GetReverbProperties(FMOD_CHANNEL *channel, int instance, float *wet);
+FMOD_RESULT F_API FMOD_Channel_SetLowPassGain    (FMOD_CHANNEL *channel, float gain);
+FMOD_RESULT F_API FMOD_Channel_GetLowPassGain    (FMOD_CHANNEL *channel, float *gain);
+FMOD_RESULT F_API FMOD_Channel_SetMode           (FMOD_CHANNEL *channel, FMOD_MODE mode);
+FMOD_RESULT F_API FMOD_Channel_GetMode           (FMOD_CHANNEL *channel, FMOD_MODE *mode);
+FMOD_RESULT F_API FMOD_Channel_IsPlaying         (FMOD_CHANNEL *channel, FMOD_BOOL *isplaying);
+FMOD_RESULT F_API FMOD_Channel_SetPan            (FMOD_CHANNEL *channel, float pan);
+FMOD_RESULT F_API FMOD_Channel_AddDSP            (FMOD_CHANNEL *channel, int index, FMOD_DSP *dsp);
+FMOD_RESULT F_API FMOD_Channel_RemoveDSP         (FMOD_CHANNEL *channel, FMOD_DSP *dsp);
+FMOD_RESULT F_API FMOD_Channel_GetNumDSPs        (FMOD_CHANNEL *channel, int *numdsps);
+FMOD_RESULT F_API FMOD_Channel_GetDSP            (FMOD_CHANNEL *channel, int index, FMOD_DSP **dsp);
+FMOD_RESULT F_API FMOD_Channel_Set3DAttributes   (FMOD_CHANNEL *channel, const FMOD_VECTOR *pos, const FMOD_VECTOR *vel);
+FMOD_RESULT F_API FMOD_Channel_Get3DAttributes   (FMOD_CHANNEL *channel, FMOD_VECTOR *pos, FMOD_VECTOR *vel);
+FMOD_RESULT F_API FMOD_Channel_SetUserData       (FMOD_CHANNEL *channel, void *userdata);
+FMOD_RESULT F_API FMOD_Channel_GetUserData       (FMOD_CHANNEL *channel, void **userdata);
+FMOD_RESULT F_API FMOD_Channel_SetFrequency      (FMOD_CHANNEL *channel, float frequency);
+FMOD_RESULT F_API FMOD_Channel_GetFrequency      (FMOD_CHANNEL *channel, float *frequency);
+FMOD_RESULT F_API FMOD_Channel_SetPriority       (FMOD_CHANNEL *channel, int priority);
+FMOD_RESULT F_API FMOD_Channel_GetPriority       (FMOD_CHANNEL *channel, int *priority);
+FMOD_RESULT F_API FMOD_Channel_SetPosition       (FMOD_CHANNEL *channel, unsigned int position, FMOD_TIMEUNIT postype);
+FMOD_RESULT F_API FMOD_Channel_GetPosition       (FMOD_CHANNEL *channel, unsigned int *position, FMOD_TIMEUNIT postype);
+FMOD_RESULT F_API FMOD_Channel_SetChannelGroup   (FMOD_CHANNEL *channel, FMOD_CHANNELGROUP *channelgroup);
+FMOD_RESULT F_API FMOD_Channel_GetChannelGroup   (FMOD_CHANNEL *channel, FMOD_CHANNELGROUP **channelgroup);
+FMOD_RESULT F_API FMOD_Channel_SetLoopCount      (FMOD_CHANNEL *channel, int loopcount);
+FMOD_RESULT F_API FMOD_Channel_GetLoopCount      (FMOD_CHANNEL *channel, int *loopcount);
+FMOD_RESULT F_API FMOD_Channel_IsVirtual         (FMOD_CHANNEL *channel, FMOD_BOOL *isvirtual);
+FMOD_RESULT F_API FMOD_Channel_GetCurrentSound   (FMOD_CHANNEL *channel, FMOD_SOUND **sound);
+FMOD_RESULT F_API FMOD_Channel_GetIndex          (FMOD_CHANNEL *channel, int *index);
+
+FMOD_RESULT F_API FMOD_ChannelGroup_GetSystemObject(FMOD_CHANNELGROUP *channelgroup, FMOD_SYSTEM **system);
+FMOD_RESULT F_API FMOD_ChannelGroup_Stop         (FMOD_CHANNELGROUP *channelgroup);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetPaused    (FMOD_CHANNELGROUP *channelgroup, FMOD_BOOL paused);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetPaused    (FMOD_CHANNELGROUP *channelgroup, FMOD_BOOL *paused);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetVolume    (FMOD_CHANNELGROUP *channelgroup, float volume);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetVolume    (FMOD_CHANNELGROUP *channelgroup, float *volume);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetPitch     (FMOD_CHANNELGROUP *channelgroup, float pitch);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetPitch     (FMOD_CHANNELGROUP *channelgroup, float *pitch);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetMute      (FMOD_CHANNELGROUP *channelgroup, FMOD_BOOL mute);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetMute      (FMOD_CHANNELGROUP *channelgroup, FMOD_BOOL *mute);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetReverbProperties(FMOD_CHANNELGROUP *channelgroup, int instance, float wet);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetReverbProperties(FMOD_CHANNELGROUP *channelgroup, int instance, float *wet);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetLowPassGain(FMOD_CHANNELGROUP *channelgroup, float gain);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetLowPassGain(FMOD_CHANNELGROUP *channelgroup, float *gain);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetMode      (FMOD_CHANNELGROUP *channelgroup, FMOD_MODE mode);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetMode      (FMOD_CHANNELGROUP *channelgroup, FMOD_MODE *mode);
+FMOD_RESULT F_API FMOD_ChannelGroup_IsPlaying    (FMOD_CHANNELGROUP *channelgroup, FMOD_BOOL *isplaying);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetPan       (FMOD_CHANNELGROUP *channelgroup, float pan);
+FMOD_RESULT F_API FMOD_ChannelGroup_AddDSP       (FMOD_CHANNELGROUP *channelgroup, int index, FMOD_DSP *dsp);
+FMOD_RESULT F_API FMOD_ChannelGroup_RemoveDSP    (FMOD_CHANNELGROUP *channelgroup, FMOD_DSP *dsp);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetNumDSPs   (FMOD_CHANNELGROUP *channelgroup, int *numdsps);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetDSP       (FMOD_CHANNELGROUP *channelgroup, int index, FMOD_DSP **dsp);
+FMOD_RESULT F_API FMOD_ChannelGroup_Set3DAttributes(FMOD_CHANNELGROUP *channelgroup, const FMOD_VECTOR *pos, const FMOD_VECTOR *vel);
+FMOD_RESULT F_API FMOD_ChannelGroup_Get3DAttributes(FMOD_CHANNELGROUP *channelgroup, FMOD_VECTOR *pos, FMOD_VECTOR *vel);
+FMOD_RESULT F_API FMOD_ChannelGroup_SetUserData  (FMOD_CHANNELGROUP *channelgroup, void *userdata);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetUserData  (FMOD_CHANNELGROUP *channelgroup, void **userdata);
+FMOD_RESULT F_API FMOD_ChannelGroup_Release      (FMOD_CHANNELGROUP *channelgroup);
+FMOD_RESULT F_API FMOD_ChannelGroup_AddGroup     (FMOD_CHANNELGROUP *channelgroup, FMOD_CHANNELGROUP *group, FMOD_BOOL propagatedspclock, FMOD_DSPCONNECTION **connection);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetNumGroups (FMOD_CHANNELGROUP *channelgroup, int *numgroups);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetGroup     (FMOD_CHANNELGROUP *channelgroup, int index, FMOD_CHANNELGROUP **group);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetParentGroup(FMOD_CHANNELGROUP *channelgroup, FMOD_CHANNELGROUP **group);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetName      (FMOD_CHANNELGROUP *channelgroup, char *name, int namelen);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetNumChannels(FMOD_CHANNELGROUP *channelgroup, int *numchannels);
+FMOD_RESULT F_API FMOD_ChannelGroup_GetChannel   (FMOD_CHANNELGROUP *channelgroup, int index, FMOD_CHANNEL **channel);
+
+FMOD_RESULT F_API FMOD_DSP_Release               (FMOD_DSP *dsp);
+FMOD_RESULT F_API FMOD_DSP_GetSystemObject       (FMOD_DSP *dsp, FMOD_SYSTEM **system);
+FMOD_RESULT F_API FMOD_DSP_AddInput              (FMOD_DSP *dsp, FMOD_DSP *input, FMOD_DSPCONNECTION **connection, FMOD_DSPCONNECTION_TYPE type);
+FMOD_RESULT F_API FMOD_DSP_DisconnectFrom        (FMOD_DSP *dsp, FMOD_DSP *target, FMOD_DSPCONNECTION *connection);
+FMOD_RESULT F_API FMOD_DSP_GetNumInputs          (FMOD_DSP *dsp, int *numinputs);
+FMOD_RESULT F_API FMOD_DSP_SetActive             (FMOD_DSP *dsp, FMOD_BOOL active);
+FMOD_RESULT F_API FMOD_DSP_GetActive             (FMOD_DSP *dsp, FMOD_BOOL *active);
+FMOD_RESULT F_API FMOD_DSP_SetBypass             (FMOD_DSP *dsp, FMOD_BOOL bypass);
+FMOD_RESULT F_API FMOD_DSP_GetBypass             (FMOD_DSP *dsp, FMOD_BOOL *bypass);
+FMOD_RESULT F_API FMOD_DSP_SetParameterFloat     (FMOD_DSP *dsp, int index, float value);
+FMOD_RESULT F_API FMOD_DSP_SetParameterInt       (FMOD_DSP *dsp, int index, int value);
+FMOD_RESULT F_API FMOD_DSP_SetParameterBool      (FMOD_DSP *dsp, int index, FMOD_BOOL value);
+FMOD_RESULT F_API FMOD_DSP_GetParameterFloat     (FMOD_DSP *dsp, int index, float *value, char *valuestr, int valuestrlen);
+FMOD_RESULT F_API FMOD_DSP_GetParameterInt       (FMOD_DSP *dsp, int index, int *value, char *valuestr, int valuestrlen);
+FMOD_RESULT F_API FMOD_DSP_GetParameterBool      (FMOD_DSP *dsp, int index, FMOD_BOOL *value, char *valuestr, int valuestrlen);
+FMOD_RESULT F_API FMOD_DSP_GetNumParameters      (FMOD_DSP *dsp, int *numparams);
+FMOD_RESULT F_API FMOD_DSP_GetType               (FMOD_DSP *dsp, FMOD_DSP_TYPE *type);
+FMOD_RESULT F_API FMOD_DSP_SetUserData           (FMOD_DSP *dsp, void *userdata);
+FMOD_RESULT F_API FMOD_DSP_GetUserData           (FMOD_DSP *dsp, void **userdata);
+
+FMOD_RESULT F_API FMOD_DSPConnection_SetMix      (FMOD_DSPCONNECTION *dspconnection, float volume);
+FMOD_RESULT F_API FMOD_DSPConnection_GetMix      (FMOD_DSPCONNECTION *dspconnection, float *volume);
+FMOD_RESULT F_API FMOD_DSPConnection_SetMixMatrix(FMOD_DSPCONNECTION *dspconnection, float *matrix, int outchannels, int inchannels, int inchannel_hop);
+FMOD_RESULT F_API FMOD_DSPConnection_GetMixMatrix(FMOD_DSPCONNECTION *dspconnection, float *matrix, int *outchannels, int *inchannels, int inchannel_hop);
+FMOD_RESULT F_API FMOD_DSPConnection_GetType     (FMOD_DSPCONNECTION *dspconnection, FMOD_DSPCONNECTION_TYPE *type);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
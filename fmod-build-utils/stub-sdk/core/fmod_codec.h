@@ -0,0 +1,30 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD SDK's fmod_codec.h, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_CODEC_H
+#define _FMOD_CODEC_H
+
+typedef struct FMOD_CODEC_STATE FMOD_CODEC_STATE;
+
+typedef FMOD_RESULT (F_CALL *FMOD_CODEC_OPEN_CALLBACK)  (FMOD_CODEC_STATE *codec_state, FMOD_MODE usermode, FMOD_CREATESOUNDEXINFO *userexinfo);
+typedef FMOD_RESULT (F_CALL *FMOD_CODEC_CLOSE_CALLBACK) (FMOD_CODEC_STATE *codec_state);
+
+typedef struct FMOD_CODEC_DESCRIPTION
+{
+    unsigned int                apiversion;
+    const char                 *name;
+    unsigned int                version;
+    int                         defaultasstream;
+    FMOD_TIMEUNIT               timeunits;
+    FMOD_CODEC_OPEN_CALLBACK    open;
+    FMOD_CODEC_CLOSE_CALLBACK   close;
+} FMOD_CODEC_DESCRIPTION;
+
+#endif
@@ -0,0 +1,338 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD Core API's common header, with a representative subset of its
+ * declarations, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_COMMON_H
+#define _FMOD_COMMON_H
+
+#define FMOD_VERSION    @FMOD_VERSION@
+
+#if defined(_WIN32) || defined(__CYGWIN__)
+    #define F_CALL __stdcall
+#else
+    #define F_CALL
+#endif
+
+#if defined(_WIN32) || defined(__CYGWIN__) || defined(F_USE_DECLSPEC)
+    #define F_EXPORT __declspec(dllexport)
+#elif defined(__APPLE__) || defined(__ANDROID__) || defined(__linux__) || defined(F_USE_ATTRIBUTE)
+    #define F_EXPORT __attribute__((visibility("default")))
+#else
+    #define F_EXPORT
+#endif
+
+#ifdef DLL_EXPORTS
+    #define F_API F_EXPORT F_CALL
+#else
+    #define F_API F_CALL
+#endif
+
+#define F_CALLBACK F_CALL
+
+typedef int                         FMOD_BOOL;
+typedef struct FMOD_SYSTEM          FMOD_SYSTEM;
+typedef struct FMOD_SOUND           FMOD_SOUND;
+typedef struct FMOD_CHANNELCONTROL  FMOD_CHANNELCONTROL;
+typedef struct FMOD_CHANNEL         FMOD_CHANNEL;
+typedef struct FMOD_CHANNELGROUP    FMOD_CHANNELGROUP;
+typedef struct FMOD_DSP             FMOD_DSP;
+typedef struct FMOD_DSPCONNECTION   FMOD_DSPCONNECTION;
+
+typedef unsigned int FMOD_DEBUG_FLAGS;
+#define FMOD_DEBUG_LEVEL_NONE                       0x00000000
+#define FMOD_DEBUG_LEVEL_ERROR                      0x00000001
+#define FMOD_DEBUG_LEVEL_WARNING                    0x00000002
+#define FMOD_DEBUG_LEVEL_LOG                        0x00000004
+
+typedef unsigned int FMOD_INITFLAGS;
+#define FMOD_INIT_NORMAL                            0x00000000
+#define FMOD_INIT_STREAM_FROM_UPDATE                0x00000001
+#define FMOD_INIT_MIX_FROM_UPDATE                   0x00000002
+#define FMOD_INIT_3D_RIGHTHANDED                    0x00000004
+
+typedef unsigned int FMOD_SYSTEM_CALLBACK_TYPE;
+#define FMOD_SYSTEM_CALLBACK_DEVICELISTCHANGED      0x00000001
+#define FMOD_SYSTEM_CALLBACK_ERROR                  0x00000080
+#define FMOD_SYSTEM_CALLBACK_ALL                    0xFFFFFFFF
+
+typedef unsigned int FMOD_MODE;
+#define FMOD_DEFAULT                                0x00000000
+#define FMOD_LOOP_OFF                               0x00000001
+#define FMOD_LOOP_NORMAL                            0x00000002
+#define FMOD_LOOP_BIDI                              0x00000004
+#define FMOD_2D                                     0x00000008
+#define FMOD_3D                                     0x00000010
+#define FMOD_CREATESTREAM                           0x00000080
+#define FMOD_CREATESAMPLE                           0x00000100
+#define FMOD_OPENUSER                               0x00000400
+#define FMOD_OPENMEMORY                             0x00000800
+#define FMOD_OPENMEMORY_POINT                       0x10000000
+#define FMOD_OPENRAW                                0x00001000
+#define FMOD_3D_HEADRELATIVE                        0x00040000
+#define FMOD_3D_WORLDRELATIVE                       0x00080000
+#define FMOD_3D_INVERSEROLLOFF                      0x00100000
+#define FMOD_3D_LINEARROLLOFF                       0x00200000
+#define FMOD_3D_LINEARSQUAREROLLOFF                 0x00400000
+#define FMOD_3D_INVERSETAPEREDROLLOFF               0x00800000
+#define FMOD_3D_CUSTOMROLLOFF                       0x04000000
+
+typedef unsigned int FMOD_TIMEUNIT;
+#define FMOD_TIMEUNIT_MS                            0x00000001
+#define FMOD_TIMEUNIT_PCM                           0x00000002
+
+typedef enum FMOD_RESULT
+{
+    FMOD_OK,
+    FMOD_ERR_BADCOMMAND,
+    FMOD_ERR_CHANNEL_ALLOC,
+    FMOD_ERR_CHANNEL_STOLEN,
+    FMOD_ERR_DMA,
+    FMOD_ERR_DSP_CONNECTION,
+    FMOD_ERR_DSP_DONTPROCESS,
+    FMOD_ERR_DSP_FORMAT,
+    FMOD_ERR_DSP_INUSE,
+    FMOD_ERR_DSP_NOTFOUND,
+    FMOD_ERR_DSP_RESERVED,
+    FMOD_ERR_DSP_SILENCE,
+    FMOD_ERR_DSP_TYPE,
+    FMOD_ERR_FILE_BAD,
+    FMOD_ERR_FILE_COULDNOTSEEK,
+    FMOD_ERR_FILE_DISKEJECTED,
+    FMOD_ERR_FILE_EOF,
+    FMOD_ERR_FILE_ENDOFDATA,
+    FMOD_ERR_FILE_NOTFOUND,
+    FMOD_ERR_FORMAT,
+    FMOD_ERR_HEADER_MISMATCH,
+    FMOD_ERR_HTTP,
+    FMOD_ERR_HTTP_ACCESS,
+    FMOD_ERR_HTTP_PROXY_AUTH,
+    FMOD_ERR_HTTP_SERVER_ERROR,
+    FMOD_ERR_HTTP_TIMEOUT,
+    FMOD_ERR_INITIALIZATION,
+    FMOD_ERR_INITIALIZED,
+    FMOD_ERR_INTERNAL,
+    FMOD_ERR_INVALID_FLOAT,
+    FMOD_ERR_INVALID_HANDLE,
+    FMOD_ERR_INVALID_PARAM,
+    FMOD_ERR_INVALID_POSITION,
+    FMOD_ERR_INVALID_SPEAKER,
+    FMOD_ERR_INVALID_SYNCPOINT,
+    FMOD_ERR_INVALID_THREAD,
+    FMOD_ERR_INVALID_VECTOR,
+    FMOD_ERR_MAXAUDIBLE,
+    FMOD_ERR_MEMORY,
+    FMOD_ERR_MEMORY_CANTPOINT,
+    FMOD_ERR_NEEDS3D,
+    FMOD_ERR_NEEDSHARDWARE,
+    FMOD_ERR_NET_CONNECT,
+    FMOD_ERR_NET_SOCKET_ERROR,
+    FMOD_ERR_NET_URL,
+    FMOD_ERR_NET_WOULD_BLOCK,
+    FMOD_ERR_NOTREADY,
+    FMOD_ERR_OUTPUT_ALLOCATED,
+    FMOD_ERR_OUTPUT_CREATEBUFFER,
+    FMOD_ERR_OUTPUT_DRIVERCALL,
+    FMOD_ERR_OUTPUT_FORMAT,
+    FMOD_ERR_OUTPUT_INIT,
+    FMOD_ERR_OUTPUT_NODRIVERS,
+    FMOD_ERR_PLUGIN,
+    FMOD_ERR_PLUGIN_MISSING,
+    FMOD_ERR_PLUGIN_RESOURCE,
+    FMOD_ERR_PLUGIN_VERSION,
+    FMOD_ERR_RECORD,
+    FMOD_ERR_REVERB_CHANNELGROUP,
+    FMOD_ERR_REVERB_INSTANCE,
+    FMOD_ERR_SUBSOUNDS,
+    FMOD_ERR_SUBSOUND_ALLOCATED,
+    FMOD_ERR_SUBSOUND_CANTMOVE,
+    FMOD_ERR_TAGNOTFOUND,
+    FMOD_ERR_TOOMANYCHANNELS,
+    FMOD_ERR_TRUNCATED,
+    FMOD_ERR_UNIMPLEMENTED,
+    FMOD_ERR_UNINITIALIZED,
+    FMOD_ERR_UNSUPPORTED,
+    FMOD_ERR_VERSION,
+    FMOD_ERR_EVENT_ALREADY_LOADED,
+    FMOD_ERR_EVENT_LIVEUPDATE_BUSY,
+    FMOD_ERR_EVENT_LIVEUPDATE_MISMATCH,
+    FMOD_ERR_EVENT_LIVEUPDATE_TIMEOUT,
+    FMOD_ERR_EVENT_NOTFOUND,
+    FMOD_ERR_STUDIO_UNINITIALIZED,
+    FMOD_ERR_STUDIO_NOT_LOADED,
+    FMOD_ERR_INVALID_STRING,
+    FMOD_ERR_ALREADY_LOCKED,
+    FMOD_ERR_NOT_LOCKED,
+    FMOD_ERR_RECORD_DISCONNECTED,
+    FMOD_ERR_TOOMANYSAMPLES,
+
+    FMOD_RESULT_FORCEINT = 65536
+} FMOD_RESULT;
+
+typedef enum FMOD_DEBUG_MODE
+{
+    FMOD_DEBUG_MODE_TTY,
+    FMOD_DEBUG_MODE_FILE,
+    FMOD_DEBUG_MODE_CALLBACK,
+
+    FMOD_DEBUG_MODE_FORCEINT = 65536
+} FMOD_DEBUG_MODE;
+
+typedef enum FMOD_OUTPUTTYPE
+{
+    FMOD_OUTPUTTYPE_AUTODETECT,
+    FMOD_OUTPUTTYPE_UNKNOWN,
+    FMOD_OUTPUTTYPE_NOSOUND,
+    FMOD_OUTPUTTYPE_WAVWRITER,
+    FMOD_OUTPUTTYPE_NOSOUND_NRT,
+    FMOD_OUTPUTTYPE_WAVWRITER_NRT,
+
+    FMOD_OUTPUTTYPE_MAX,
+    FMOD_OUTPUTTYPE_FORCEINT = 65536
+} FMOD_OUTPUTTYPE;
+
+typedef enum FMOD_SPEAKERMODE
+{
+    FMOD_SPEAKERMODE_DEFAULT,
+    FMOD_SPEAKERMODE_RAW,
+    FMOD_SPEAKERMODE_MONO,
+    FMOD_SPEAKERMODE_STEREO,
+    FMOD_SPEAKERMODE_QUAD,
+    FMOD_SPEAKERMODE_SURROUND,
+    FMOD_SPEAKERMODE_5POINT1,
+    FMOD_SPEAKERMODE_7POINT1,
+    FMOD_SPEAKERMODE_7POINT1POINT4,
+
+    FMOD_SPEAKERMODE_MAX,
+    FMOD_SPEAKERMODE_FORCEINT = 65536
+} FMOD_SPEAKERMODE;
+
+typedef enum FMOD_SPEAKER
+{
+    FMOD_SPEAKER_NONE = -1,
+    FMOD_SPEAKER_FRONT_LEFT = 0,
+    FMOD_SPEAKER_FRONT_RIGHT,
+    FMOD_SPEAKER_FRONT_CENTER,
+    FMOD_SPEAKER_LOW_FREQUENCY,
+    FMOD_SPEAKER_SURROUND_LEFT,
+    FMOD_SPEAKER_SURROUND_RIGHT,
+    FMOD_SPEAKER_BACK_LEFT,
+    FMOD_SPEAKER_BACK_RIGHT,
+    FMOD_SPEAKER_TOP_FRONT_LEFT,
+    FMOD_SPEAKER_TOP_FRONT_RIGHT,
+    FMOD_SPEAKER_TOP_BACK_LEFT,
+    FMOD_SPEAKER_TOP_BACK_RIGHT,
+
+    FMOD_SPEAKER_MAX,
+    FMOD_SPEAKER_FORCEINT = 65536
+} FMOD_SPEAKER;
+
+typedef enum FMOD_CHANNELCONTROL_DSP_INDEX
+{
+    FMOD_CHANNELCONTROL_DSP_HEAD = -1,
+    FMOD_CHANNELCONTROL_DSP_FADER = -2,
+    FMOD_CHANNELCONTROL_DSP_TAIL = -3,
+
+    FMOD_CHANNELCONTROL_DSP_FORCEINT = 65536
+} FMOD_CHANNELCONTROL_DSP_INDEX;
+
+typedef enum FMOD_DSPCONNECTION_TYPE
+{
+    FMOD_DSPCONNECTION_TYPE_STANDARD,
+    FMOD_DSPCONNECTION_TYPE_SIDECHAIN,
+    FMOD_DSPCONNECTION_TYPE_SEND,
+    FMOD_DSPCONNECTION_TYPE_SEND_SIDECHAIN,
+
+    FMOD_DSPCONNECTION_TYPE_MAX,
+    FMOD_DSPCONNECTION_TYPE_FORCEINT = 65536
+} FMOD_DSPCONNECTION_TYPE;
+
+typedef enum FMOD_SOUND_FORMAT
+{
+    FMOD_SOUND_FORMAT_NONE,
+    FMOD_SOUND_FORMAT_PCM8,
+    FMOD_SOUND_FORMAT_PCM16,
+    FMOD_SOUND_FORMAT_PCM24,
+    FMOD_SOUND_FORMAT_PCM32,
+    FMOD_SOUND_FORMAT_PCMFLOAT,
+    FMOD_SOUND_FORMAT_BITSTREAM,
+
+    FMOD_SOUND_FORMAT_MAX,
+    FMOD_SOUND_FORMAT_FORCEINT = 65536
+} FMOD_SOUND_FORMAT;
+
+typedef enum FMOD_DSP_TYPE
+{
+    FMOD_DSP_TYPE_UNKNOWN,
+    FMOD_DSP_TYPE_MIXER,
+    FMOD_DSP_TYPE_OSCILLATOR,
+    FMOD_DSP_TYPE_LOWPASS,
+    FMOD_DSP_TYPE_ITLOWPASS,
+    FMOD_DSP_TYPE_HIGHPASS,
+    FMOD_DSP_TYPE_ECHO,
+    FMOD_DSP_TYPE_FADER,
+    FMOD_DSP_TYPE_FLANGE,
+    FMOD_DSP_TYPE_DISTORTION,
+    FMOD_DSP_TYPE_NORMALIZE,
+    FMOD_DSP_TYPE_LIMITER,
+    FMOD_DSP_TYPE_PARAMEQ,
+    FMOD_DSP_TYPE_PITCHSHIFT,
+    FMOD_DSP_TYPE_CHORUS,
+    FMOD_DSP_TYPE_VSTPLUGIN,
+    FMOD_DSP_TYPE_WINAMPPLUGIN,
+    FMOD_DSP_TYPE_ITECHO,
+    FMOD_DSP_TYPE_COMPRESSOR,
+    FMOD_DSP_TYPE_SFXREVERB,
+    FMOD_DSP_TYPE_LOWPASS_SIMPLE,
+    FMOD_DSP_TYPE_DELAY,
+    FMOD_DSP_TYPE_TREMOLO,
+    FMOD_DSP_TYPE_LADSPAPLUGIN,
+    FMOD_DSP_TYPE_SEND,
+    FMOD_DSP_TYPE_RETURN,
+    FMOD_DSP_TYPE_HIGHPASS_SIMPLE,
+    FMOD_DSP_TYPE_PAN,
+    FMOD_DSP_TYPE_THREE_EQ,
+    FMOD_DSP_TYPE_FFT,
+    FMOD_DSP_TYPE_LOUDNESS_METER,
+    FMOD_DSP_TYPE_ENVELOPEFOLLOWER,
+    FMOD_DSP_TYPE_CONVOLUTIONREVERB,
+    FMOD_DSP_TYPE_CHANNELMIX,
+    FMOD_DSP_TYPE_TRANSCEIVER,
+    FMOD_DSP_TYPE_OBJECTPAN,
+    FMOD_DSP_TYPE_MULTIBAND_EQ,
+
+    FMOD_DSP_TYPE_MAX,
+    FMOD_DSP_TYPE_FORCEINT = 65536
+} FMOD_DSP_TYPE;
+
+typedef struct FMOD_VECTOR
+{
+    float x;
+    float y;
+    float z;
+} FMOD_VECTOR;
+
+typedef FMOD_RESULT (F_CALL *FMOD_DEBUG_CALLBACK)  (FMOD_DEBUG_FLAGS flags, const char *file, int line, const char* func, const char* message);
+typedef FMOD_RESULT (F_CALL *FMOD_SYSTEM_CALLBACK) (FMOD_SYSTEM *system, FMOD_SYSTEM_CALLBACK_TYPE type, void *commanddata1, void* commanddata2, void *userdata);
+
+typedef struct FMOD_CREATESOUNDEXINFO
+{
+    int                 cbsize;
+    unsigned int        length;
+    unsigned int        fileoffset;
+    int                 numchannels;
+    int                 defaultfrequency;
+    FMOD_SOUND_FORMAT   format;
+    unsigned int        decodebuffersize;
+    int                 initialsubsound;
+    int                 numsubsounds;
+    void               *userdata;
+} FMOD_CREATESOUNDEXINFO;
+
+#endif
@@ -0,0 +1,48 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD SDK's fmod_dsp.h, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_DSP_H
+#define _FMOD_DSP_H
+
+#include "fmod_dsp_effects.h"
+
+typedef struct FMOD_DSP_STATE FMOD_DSP_STATE;
+
+typedef enum
+{
+    FMOD_DSP_PARAMETER_TYPE_FLOAT,
+    FMOD_DSP_PARAMETER_TYPE_INT,
+    FMOD_DSP_PARAMETER_TYPE_BOOL,
+    FMOD_DSP_PARAMETER_TYPE_DATA,
+
+    FMOD_DSP_PARAMETER_TYPE_MAX,
+    FMOD_DSP_PARAMETER_TYPE_FORCEINT = 65536
+} FMOD_DSP_PARAMETER_TYPE;
+
+typedef FMOD_RESULT (F_CALL *FMOD_DSP_CREATE_CALLBACK)  (FMOD_DSP_STATE *dsp_state);
+typedef FMOD_RESULT (F_CALL *FMOD_DSP_RELEASE_CALLBACK) (FMOD_DSP_STATE *dsp_state);
+typedef FMOD_RESULT (F_CALL *FMOD_DSP_READ_CALLBACK)    (FMOD_DSP_STATE *dsp_state, float *inbuffer, float *outbuffer, unsigned int length, int inchannels, int *outchannels);
+
+#define FMOD_PLUGIN_SDK_VERSION 110
+
+typedef struct FMOD_DSP_DESCRIPTION
+{
+    unsigned int                pluginsdkversion;
+    char                        name[32];
+    unsigned int                version;
+    int                         numinputbuffers;
+    int                         numoutputbuffers;
+    FMOD_DSP_CREATE_CALLBACK    create;
+    FMOD_DSP_RELEASE_CALLBACK   release;
+    FMOD_DSP_READ_CALLBACK      read;
+    void                       *userdata;
+} FMOD_DSP_DESCRIPTION;
+
+#endif
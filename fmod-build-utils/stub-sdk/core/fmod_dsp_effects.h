@@ -0,0 +1,247 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD SDK's fmod_dsp_effects.h, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_DSP_EFFECTS_H
+#define _FMOD_DSP_EFFECTS_H
+
+typedef enum
+{
+    FMOD_DSP_ECHO_DELAY,
+    FMOD_DSP_ECHO_FEEDBACK,
+    FMOD_DSP_ECHO_DRYLEVEL,
+    FMOD_DSP_ECHO_WETLEVEL
+} FMOD_DSP_ECHO;
+
+typedef enum
+{
+    FMOD_DSP_LOWPASS_CUTOFF,
+    FMOD_DSP_LOWPASS_RESONANCE
+} FMOD_DSP_LOWPASS;
+
+typedef enum
+{
+    FMOD_DSP_FADER_GAIN,
+    FMOD_DSP_FADER_OVERALL_GAIN
+} FMOD_DSP_FADER;
+
+typedef enum
+{
+    FMOD_DSP_PITCHSHIFT_PITCH,
+    FMOD_DSP_PITCHSHIFT_FFTSIZE,
+    FMOD_DSP_PITCHSHIFT_OVERLAP,
+    FMOD_DSP_PITCHSHIFT_MAXCHANNELS
+} FMOD_DSP_PITCHSHIFT;
+
+typedef enum
+{
+    FMOD_DSP_SFXREVERB_DECAYTIME,
+    FMOD_DSP_SFXREVERB_EARLYDELAY,
+    FMOD_DSP_SFXREVERB_LATEDELAY,
+    FMOD_DSP_SFXREVERB_HFREFERENCE,
+    FMOD_DSP_SFXREVERB_HFDECAYRATIO,
+    FMOD_DSP_SFXREVERB_DIFFUSION,
+    FMOD_DSP_SFXREVERB_DENSITY,
+    FMOD_DSP_SFXREVERB_LOWSHELFFREQUENCY,
+    FMOD_DSP_SFXREVERB_LOWSHELFGAIN,
+    FMOD_DSP_SFXREVERB_HIGHCUT,
+    FMOD_DSP_SFXREVERB_EARLYLATEMIX,
+    FMOD_DSP_SFXREVERB_WETLEVEL,
+    FMOD_DSP_SFXREVERB_DRYLEVEL
+} FMOD_DSP_SFXREVERB;
+
+typedef enum
+{
+    FMOD_DSP_COMPRESSOR_THRESHOLD,
+    FMOD_DSP_COMPRESSOR_RATIO,
+    FMOD_DSP_COMPRESSOR_ATTACK,
+    FMOD_DSP_COMPRESSOR_RELEASE,
+    FMOD_DSP_COMPRESSOR_GAINMAKEUP,
+    FMOD_DSP_COMPRESSOR_USESIDECHAIN,
+    FMOD_DSP_COMPRESSOR_LINKED
+} FMOD_DSP_COMPRESSOR;
+
+typedef enum
+{
+    FMOD_DSP_PAN_MODE_MONO,
+    FMOD_DSP_PAN_MODE_STEREO,
+    FMOD_DSP_PAN_MODE_SURROUND
+} FMOD_DSP_PAN_MODE_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_PAN_3D_ROLLOFF_LINEARSQUARED,
+    FMOD_DSP_PAN_3D_ROLLOFF_LINEAR,
+    FMOD_DSP_PAN_3D_ROLLOFF_INVERSE,
+    FMOD_DSP_PAN_3D_ROLLOFF_INVERSETAPERED,
+    FMOD_DSP_PAN_3D_ROLLOFF_CUSTOM
+} FMOD_DSP_PAN_3D_ROLLOFF_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_PAN_3D_EXTENT_MODE_AUTO,
+    FMOD_DSP_PAN_3D_EXTENT_MODE_USER,
+    FMOD_DSP_PAN_3D_EXTENT_MODE_OFF
+} FMOD_DSP_PAN_3D_EXTENT_MODE_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_PAN_2D_STEREO_MODE_DISTRIBUTED,
+    FMOD_DSP_PAN_2D_STEREO_MODE_DISCRETE
+} FMOD_DSP_PAN_2D_STEREO_MODE_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_PAN_MODE,
+    FMOD_DSP_PAN_2D_STEREO_POSITION,
+    FMOD_DSP_PAN_2D_DIRECTION,
+    FMOD_DSP_PAN_2D_EXTENT,
+    FMOD_DSP_PAN_2D_ROTATION,
+    FMOD_DSP_PAN_2D_LFE_LEVEL,
+    FMOD_DSP_PAN_2D_STEREO_MODE,
+    FMOD_DSP_PAN_2D_STEREO_SEPARATION,
+    FMOD_DSP_PAN_2D_STEREO_AXIS,
+    FMOD_DSP_PAN_ENABLED_SPEAKERS,
+    FMOD_DSP_PAN_3D_POSITION,
+    FMOD_DSP_PAN_3D_ROLLOFF,
+    FMOD_DSP_PAN_3D_MIN_DISTANCE,
+    FMOD_DSP_PAN_3D_MAX_DISTANCE,
+    FMOD_DSP_PAN_3D_EXTENT_MODE,
+    FMOD_DSP_PAN_3D_SOUND_SIZE,
+    FMOD_DSP_PAN_3D_MIN_EXTENT,
+    FMOD_DSP_PAN_3D_PAN_BLEND,
+    FMOD_DSP_PAN_LFE_UPMIX_ENABLED,
+    FMOD_DSP_PAN_OVERALL_GAIN,
+    FMOD_DSP_PAN_SURROUND_SPEAKER_MODE,
+    FMOD_DSP_PAN_2D_HEIGHT_BLEND
+} FMOD_DSP_PAN;
+
+typedef enum
+{
+    FMOD_DSP_MULTIBAND_EQ_FILTER_DISABLED,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_LOWPASS_12DB,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_LOWPASS_24DB,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_LOWPASS_48DB,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_HIGHPASS_12DB,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_HIGHPASS_24DB,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_HIGHPASS_48DB,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_LOWSHELF,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_HIGHSHELF,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_PEAKING,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_BANDPASS,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_NOTCH,
+    FMOD_DSP_MULTIBAND_EQ_FILTER_ALLPASS
+} FMOD_DSP_MULTIBAND_EQ_FILTER_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_MULTIBAND_EQ_A_FILTER,
+    FMOD_DSP_MULTIBAND_EQ_A_FREQUENCY,
+    FMOD_DSP_MULTIBAND_EQ_A_Q,
+    FMOD_DSP_MULTIBAND_EQ_A_GAIN,
+    FMOD_DSP_MULTIBAND_EQ_B_FILTER,
+    FMOD_DSP_MULTIBAND_EQ_B_FREQUENCY,
+    FMOD_DSP_MULTIBAND_EQ_B_Q,
+    FMOD_DSP_MULTIBAND_EQ_B_GAIN,
+    FMOD_DSP_MULTIBAND_EQ_C_FILTER,
+    FMOD_DSP_MULTIBAND_EQ_C_FREQUENCY,
+    FMOD_DSP_MULTIBAND_EQ_C_Q,
+    FMOD_DSP_MULTIBAND_EQ_C_GAIN,
+    FMOD_DSP_MULTIBAND_EQ_D_FILTER,
+    FMOD_DSP_MULTIBAND_EQ_D_FREQUENCY,
+    FMOD_DSP_MULTIBAND_EQ_D_Q,
+    FMOD_DSP_MULTIBAND_EQ_D_GAIN,
+    FMOD_DSP_MULTIBAND_EQ_E_FILTER,
+    FMOD_DSP_MULTIBAND_EQ_E_FREQUENCY,
+    FMOD_DSP_MULTIBAND_EQ_E_Q,
+    FMOD_DSP_MULTIBAND_EQ_E_GAIN
+} FMOD_DSP_MULTIBAND_EQ;
+
+typedef enum
+{
+    FMOD_DSP_CHANNELMIX_OUTPUT_DEFAULT,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALLMONO,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALLSTEREO,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALLQUAD,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALL5POINT1,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALL7POINT1,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALLLFE,
+    FMOD_DSP_CHANNELMIX_OUTPUT_ALL7POINT1POINT4
+} FMOD_DSP_CHANNELMIX_OUTPUT;
+
+typedef enum
+{
+    FMOD_DSP_CHANNELMIX_OUTPUTGROUPING,
+    FMOD_DSP_CHANNELMIX_GAIN_CH0,
+    FMOD_DSP_CHANNELMIX_GAIN_CH1,
+    FMOD_DSP_CHANNELMIX_GAIN_CH2,
+    FMOD_DSP_CHANNELMIX_GAIN_CH3,
+    FMOD_DSP_CHANNELMIX_GAIN_CH4,
+    FMOD_DSP_CHANNELMIX_GAIN_CH5,
+    FMOD_DSP_CHANNELMIX_GAIN_CH6,
+    FMOD_DSP_CHANNELMIX_GAIN_CH7,
+    FMOD_DSP_CHANNELMIX_GAIN_CH8,
+    FMOD_DSP_CHANNELMIX_GAIN_CH9,
+    FMOD_DSP_CHANNELMIX_GAIN_CH10,
+    FMOD_DSP_CHANNELMIX_GAIN_CH11,
+    FMOD_DSP_CHANNELMIX_GAIN_CH12,
+    FMOD_DSP_CHANNELMIX_GAIN_CH13,
+    FMOD_DSP_CHANNELMIX_GAIN_CH14,
+    FMOD_DSP_CHANNELMIX_GAIN_CH15,
+    FMOD_DSP_CHANNELMIX_GAIN_CH16,
+    FMOD_DSP_CHANNELMIX_GAIN_CH17,
+    FMOD_DSP_CHANNELMIX_GAIN_CH18,
+    FMOD_DSP_CHANNELMIX_GAIN_CH19,
+    FMOD_DSP_CHANNELMIX_GAIN_CH20,
+    FMOD_DSP_CHANNELMIX_GAIN_CH21,
+    FMOD_DSP_CHANNELMIX_GAIN_CH22,
+    FMOD_DSP_CHANNELMIX_GAIN_CH23,
+    FMOD_DSP_CHANNELMIX_GAIN_CH24,
+    FMOD_DSP_CHANNELMIX_GAIN_CH25,
+    FMOD_DSP_CHANNELMIX_GAIN_CH26,
+    FMOD_DSP_CHANNELMIX_GAIN_CH27,
+    FMOD_DSP_CHANNELMIX_GAIN_CH28,
+    FMOD_DSP_CHANNELMIX_GAIN_CH29,
+    FMOD_DSP_CHANNELMIX_GAIN_CH30,
+    FMOD_DSP_CHANNELMIX_GAIN_CH31,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH0,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH1,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH2,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH3,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH4,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH5,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH6,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH7,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH8,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH9,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH10,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH11,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH12,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH13,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH14,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH15,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH16,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH17,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH18,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH19,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH20,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH21,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH22,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH23,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH24,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH25,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH26,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH27,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH28,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH29,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH30,
+    FMOD_DSP_CHANNELMIX_OUTPUT_CH31
+} FMOD_DSP_CHANNELMIX;
+
+#endif
@@ -0,0 +1,31 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD SDK's fmod_errors.h, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_ERRORS_H
+#define _FMOD_ERRORS_H
+
+#include "fmod.h"
+
+#ifdef __GNUC__
+static const char *FMOD_ErrorString(FMOD_RESULT errcode) __attribute__((unused));
+#endif
+
+static const char *FMOD_ErrorString(FMOD_RESULT errcode)
+{
+    switch (errcode)
+    {
+        case FMOD_OK:                   return "No errors.";
+        case FMOD_ERR_INVALID_HANDLE:   return "An invalid object handle was used.";
+        case FMOD_ERR_INVALID_PARAM:    return "An invalid parameter was passed to this function.";
+        default:                        return "Unknown error.";
+    };
+}
+
+#endif
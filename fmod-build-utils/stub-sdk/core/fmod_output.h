@@ -0,0 +1,33 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD SDK's fmod_output.h, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FMOD_OUTPUT_H
+#define _FMOD_OUTPUT_H
+
+typedef struct FMOD_OUTPUT_STATE FMOD_OUTPUT_STATE;
+
+#define FMOD_OUTPUT_PLUGIN_VERSION 5
+
+typedef unsigned int FMOD_OUTPUT_METHOD;
+#define FMOD_OUTPUT_METHOD_MIX_DIRECT   0
+#define FMOD_OUTPUT_METHOD_MIX_BUFFERED 1
+
+typedef FMOD_RESULT (F_CALL *FMOD_OUTPUT_GETNUMDRIVERS_CALLBACK) (FMOD_OUTPUT_STATE *output_state, int *numdrivers);
+
+typedef struct FMOD_OUTPUT_DESCRIPTION
+{
+    unsigned int                        apiversion;
+    const char                         *name;
+    unsigned int                        version;
+    FMOD_OUTPUT_METHOD                  method;
+    FMOD_OUTPUT_GETNUMDRIVERS_CALLBACK  getnumdrivers;
+} FMOD_OUTPUT_DESCRIPTION;
+
+#endif
@@ -0,0 +1,111 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FSBank API's header, with a representative subset of its
+ * declarations, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef _FSBANK_H
+#define _FSBANK_H
+
+#if defined(_WIN32)
+    #define FB_EXPORT __declspec(dllexport)
+    #define FB_CALL __stdcall
+#else
+    #define FB_EXPORT __attribute__((visibility("default")))
+    #define FB_CALL
+#endif
+
+#if defined(DLL_EXPORTS)
+    #define FB_API FB_EXPORT FB_CALL
+#else
+    #define FB_API FB_CALL
+#endif
+
+typedef unsigned int FSBANK_INITFLAGS;
+#define FSBANK_INIT_NORMAL                  0x00000000
+#define FSBANK_INIT_IGNOREERRORS            0x00000001
+
+typedef unsigned int FSBANK_BUILDFLAGS;
+#define FSBANK_BUILD_DEFAULT                0x00000000
+#define FSBANK_BUILD_DISABLESYNCPOINTS      0x00000001
+
+typedef enum FSBANK_RESULT
+{
+    FSBANK_OK,
+    FSBANK_ERR_CACHE_CHUNKNOTFOUND,
+    FSBANK_ERR_CANCELLED,
+    FSBANK_ERR_CANNOT_CONTINUE,
+    FSBANK_ERR_ENCODER,
+    FSBANK_ERR_ENCODER_INIT,
+    FSBANK_ERR_ENCODER_NOTSUPPORTED,
+    FSBANK_ERR_FILE_OS,
+    FSBANK_ERR_FILE_NOTFOUND,
+    FSBANK_ERR_FMOD,
+    FSBANK_ERR_INITIALIZED,
+    FSBANK_ERR_INVALID_FORMAT,
+    FSBANK_ERR_INVALID_PARAM,
+    FSBANK_ERR_MEMORY,
+    FSBANK_ERR_UNINITIALIZED,
+    FSBANK_ERR_WRITER_FORMAT,
+    FSBANK_WARN_CANNOTLOOP,
+    FSBANK_WARN_IGNORED_FILTERHIGHFREQ,
+    FSBANK_WARN_IGNORED_DISABLESEEKING,
+    FSBANK_WARN_FORCED_DONTWRITENAMES,
+    FSBANK_ERR_ENCODER_FILE_NOTFOUND,
+    FSBANK_ERR_ENCODER_FILE_BAD
+} FSBANK_RESULT;
+
+typedef enum FSBANK_FSBVERSION
+{
+    FSBANK_FSBVERSION_FSB5,
+
+    FSBANK_FSBVERSION_MAX
+} FSBANK_FSBVERSION;
+
+typedef enum FSBANK_FORMAT
+{
+    FSBANK_FORMAT_PCM,
+    FSBANK_FORMAT_XMA,
+    FSBANK_FORMAT_AT9,
+    FSBANK_FORMAT_VORBIS,
+    FSBANK_FORMAT_FADPCM,
+    FSBANK_FORMAT_OPUS,
+
+    FSBANK_FORMAT_MAX
+} FSBANK_FORMAT;
+
+typedef struct FSBANK_SUBSOUND
+{
+    const char* const  *fileNames;
+    const void* const  *fileData;
+    const unsigned int *fileDataLengths;
+    unsigned int        numFiles;
+    FSBANK_BUILDFLAGS   overrideFlags;
+    unsigned int        overrideQuality;
+    float               desiredSampleRate;
+    float               percentOptimizedRate;
+    unsigned int        speakerMap;
+} FSBANK_SUBSOUND;
+
+typedef void* (FB_CALL *FSBANK_MEMORY_ALLOC_CALLBACK)(unsigned int size, unsigned int type, const char *sourceStr);
+typedef void  (FB_CALL *FSBANK_MEMORY_FREE_CALLBACK) (void *ptr, unsigned int type, const char *sourceStr);
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+FSBANK_RESULT FB_API FSBank_MemoryInit(FSBANK_MEMORY_ALLOC_CALLBACK userAlloc, FSBANK_MEMORY_FREE_CALLBACK userFree);
+FSBANK_RESULT FB_API FSBank_Init(FSBANK_FSBVERSION version, FSBANK_INITFLAGS flags, unsigned int numSimultaneousJobs, const char *cacheDirectory);
+FSBANK_RESULT FB_API FSBank_Release(void);
+FSBANK_RESULT FB_API FSBank_Build(const FSBANK_SUBSOUND *subSounds, unsigned int numSubSounds, FSBANK_FORMAT encodeFormat, FSBANK_BUILDFLAGS buildFlags, unsigned int quality, const char *encryptKey, const char *outputFileName);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,31 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FSBank API's error strings, for testing the build machinery without
+ * the FMOD SDK.
+ */
+
+#ifndef _FSBANK_ERRORS_H
+#define _FSBANK_ERRORS_H
+
+#include "fsbank.h"
+
+#ifdef __GNUC__
+static const char *FSBank_ErrorString(FSBANK_RESULT errcode) __attribute__((unused));
+#endif
+
+static const char *FSBank_ErrorString(FSBANK_RESULT errcode)
+{
+    switch (errcode)
+    {
+        case FSBANK_OK:                 return "No errors.";
+        case FSBANK_ERR_INVALID_PARAM:  return "An invalid parameter has been passed to this function.";
+        default:                        return "Unknown error.";
+    }
+}
+
+#endif
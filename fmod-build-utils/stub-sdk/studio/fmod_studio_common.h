@@ -0,0 +1,61 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+ *
+ * SPDX-License-Identifier: MPL-2.0
+ */
+
+/*
+ * A stand-in for the FMOD Studio API's common header, with a representative subset of its
+ * declarations, for testing the build machinery without the FMOD SDK.
+ */
+
+#ifndef FMOD_STUDIO_COMMON_H
+#define FMOD_STUDIO_COMMON_H
+
+#include "fmod.h"
+
+typedef struct FMOD_STUDIO_SYSTEM               FMOD_STUDIO_SYSTEM;
+typedef struct FMOD_STUDIO_EVENTDESCRIPTION     FMOD_STUDIO_EVENTDESCRIPTION;
+typedef struct FMOD_STUDIO_EVENTINSTANCE        FMOD_STUDIO_EVENTINSTANCE;
+typedef struct FMOD_STUDIO_BANK                 FMOD_STUDIO_BANK;
+
+typedef unsigned int FMOD_STUDIO_INITFLAGS;
+#define FMOD_STUDIO_INIT_NORMAL                     0x00000000
+#define FMOD_STUDIO_INIT_LIVEUPDATE                 0x00000001
+
+typedef unsigned int FMOD_STUDIO_LOAD_BANK_FLAGS;
+#define FMOD_STUDIO_LOAD_BANK_NORMAL                0x00000000
+#define FMOD_STUDIO_LOAD_BANK_NONBLOCKING           0x00000001
+
+typedef unsigned int FMOD_STUDIO_EVENT_CALLBACK_TYPE;
+#define FMOD_STUDIO_EVENT_CALLBACK_CREATED          0x00000001
+#define FMOD_STUDIO_EVENT_CALLBACK_STOPPED          0x00000080
+
+typedef enum FMOD_STUDIO_STOP_MODE
+{
+    FMOD_STUDIO_STOP_ALLOWFADEOUT,
+    FMOD_STUDIO_STOP_IMMEDIATE,
+
+    FMOD_STUDIO_STOP_FORCEINT = 65536
+} FMOD_STUDIO_STOP_MODE;
+
+typedef struct FMOD_STUDIO_PARAMETER_ID
+{
+    unsigned int data1;
+    unsigned int data2;
+} FMOD_STUDIO_PARAMETER_ID;
+
+typedef struct FMOD_STUDIO_ADVANCEDSETTINGS
+{
+    int             cbsize;
+    unsigned int    commandqueuesize;
+    unsigned int    handleinitialsize;
+    int             studioupdateperiod;
+    int             idlesampledatapoolsize;
+    unsigned int    streamingscheduledelay;
+    const char*     encryptionkey;
+} FMOD_STUDIO_ADVANCEDSETTINGS;
+
+typedef FMOD_RESULT (F_CALL *FMOD_STUDIO_EVENT_CALLBACK) (FMOD_STUDIO_EVENT_CALLBACK_TYPE type, FMOD_STUDIO_EVENTINSTANCE *event, void *parameters);
+
+#endif
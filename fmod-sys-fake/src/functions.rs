@@ -0,0 +1,1640 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! The faked functions, with the same signatures as in `fmod_sys::functions`.
+//!
+//! Each function has the safety requirements of the FMOD function it fakes, although the
+//! fake never dereferences handles, so invalid ones fail with `FMOD_ERR_INVALID_HANDLE`
+//! rather than crashing.
+
+// The unwraps are on objects that were just looked up.
+#![allow(
+    clippy::missing_safety_doc,
+    clippy::missing_panics_doc,
+    clippy::float_cmp
+)]
+
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_int, c_uint, c_void, CStr, CString},
+    path::Path,
+};
+
+use fmod_sys::{
+    FMOD_BOOL, FMOD_CHANNEL, FMOD_CHANNELCONTROL_DSP_INDEX, FMOD_CHANNELGROUP,
+    FMOD_CREATESOUNDEXINFO, FMOD_DSP, FMOD_DSPCONNECTION, FMOD_DSPCONNECTION_TYPE, FMOD_DSP_TYPE,
+    FMOD_INITFLAGS, FMOD_MODE, FMOD_OPENMEMORY, FMOD_OPENMEMORY_POINT, FMOD_OPENUSER,
+    FMOD_OUTPUTTYPE, FMOD_RESULT, FMOD_SOUND, FMOD_SPEAKERMODE, FMOD_SYSTEM, FMOD_TIMEUNIT,
+    FMOD_TIMEUNIT_MS, FMOD_TIMEUNIT_PCM, FMOD_VECTOR, FMOD_VERSION,
+};
+
+use crate::state::{
+    handle, run, ChannelGroup, Connection, Control, Dsp, MixMatrix, Parameter, Sound, State,
+    System, DEFAULT_SOUND_LENGTH_MS,
+};
+
+const INVALID_PARAM: FMOD_RESULT = FMOD_RESULT::FMOD_ERR_INVALID_PARAM;
+
+/// Write `value` through a required out-parameter.
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), FMOD_RESULT> {
+    if out.is_null() {
+        return Err(INVALID_PARAM);
+    }
+    out.write(value);
+    Ok(())
+}
+
+/// Write `value` through an optional out-parameter.
+unsafe fn write_optional<T>(out: *mut T, value: T) {
+    if !out.is_null() {
+        out.write(value);
+    }
+}
+
+/// Copy `name` into a C string buffer, truncating it as FMOD does.
+unsafe fn write_name(name: &CStr, out: *mut c_char, len: c_int) -> Result<(), FMOD_RESULT> {
+    let len = usize::try_from(len).map_err(|_| INVALID_PARAM)?;
+    if out.is_null() || len == 0 {
+        return Err(INVALID_PARAM);
+    }
+    let bytes = name.to_bytes();
+    let copied = bytes.len().min(len - 1);
+    out.copy_from_nonoverlapping(bytes.as_ptr().cast(), copied);
+    out.add(copied).write(0);
+    Ok(())
+}
+
+fn is_true(value: FMOD_BOOL) -> bool {
+    value != 0
+}
+
+fn fmod_bool(value: bool) -> FMOD_BOOL {
+    FMOD_BOOL::from(value)
+}
+
+fn finite(value: f32) -> Result<f32, FMOD_RESULT> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(FMOD_RESULT::FMOD_ERR_INVALID_FLOAT)
+    }
+}
+
+pub unsafe extern "C" fn FMOD_System_Create(
+    system: *mut *mut FMOD_SYSTEM,
+    headerversion: c_uint,
+) -> FMOD_RESULT {
+    run(|state| {
+        if system.is_null() {
+            return Err(INVALID_PARAM);
+        }
+        if headerversion != FMOD_VERSION.cast_unsigned() {
+            return Err(FMOD_RESULT::FMOD_ERR_HEADER_MISMATCH);
+        }
+        let id = state.insert_id();
+        state.systems.insert(id, System::default());
+        write(system, handle(id))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_Release(system: *mut FMOD_SYSTEM) -> FMOD_RESULT {
+    run(|state| {
+        state.system(system)?;
+        state.release_system(system.addr());
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_SetOutput(
+    system: *mut FMOD_SYSTEM,
+    output: FMOD_OUTPUTTYPE,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.system(system)?.output = output;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetOutput(
+    system: *mut FMOD_SYSTEM,
+    output: *mut FMOD_OUTPUTTYPE,
+) -> FMOD_RESULT {
+    run(|state| write(output, state.system(system)?.output))
+}
+
+pub unsafe extern "C" fn FMOD_System_SetSoftwareFormat(
+    system: *mut FMOD_SYSTEM,
+    samplerate: c_int,
+    speakermode: FMOD_SPEAKERMODE,
+    numrawspeakers: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.system(system)?;
+        if system.initialized {
+            return Err(FMOD_RESULT::FMOD_ERR_INITIALIZED);
+        }
+        if !(8000..=192_000).contains(&samplerate) || numrawspeakers < 0 {
+            return Err(INVALID_PARAM);
+        }
+        system.sample_rate = samplerate;
+        system.speaker_mode = speakermode;
+        system.raw_speakers = numrawspeakers;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetSoftwareFormat(
+    system: *mut FMOD_SYSTEM,
+    samplerate: *mut c_int,
+    speakermode: *mut FMOD_SPEAKERMODE,
+    numrawspeakers: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.system(system)?;
+        write_optional(samplerate, system.sample_rate);
+        write_optional(speakermode, system.speaker_mode);
+        write_optional(numrawspeakers, system.raw_speakers);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_SetDSPBufferSize(
+    system: *mut FMOD_SYSTEM,
+    bufferlength: c_uint,
+    numbuffers: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.system(system)?;
+        if system.initialized {
+            return Err(FMOD_RESULT::FMOD_ERR_INITIALIZED);
+        }
+        if bufferlength == 0 || numbuffers < 2 {
+            return Err(INVALID_PARAM);
+        }
+        system.dsp_buffer_length = bufferlength;
+        system.dsp_buffer_count = numbuffers;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetDSPBufferSize(
+    system: *mut FMOD_SYSTEM,
+    bufferlength: *mut c_uint,
+    numbuffers: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.system(system)?;
+        write_optional(bufferlength, system.dsp_buffer_length);
+        write_optional(numbuffers, system.dsp_buffer_count);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_Init(
+    system: *mut FMOD_SYSTEM,
+    maxchannels: c_int,
+    _flags: FMOD_INITFLAGS,
+    _extradriverdata: *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| {
+        let id = system.addr();
+        let system = state.system(system)?;
+        if system.initialized {
+            return Err(FMOD_RESULT::FMOD_ERR_INITIALIZED);
+        }
+        if !(0..=4095).contains(&maxchannels) {
+            return Err(INVALID_PARAM);
+        }
+        system.initialized = true;
+        system.max_channels = maxchannels;
+        let master = state.insert_id();
+        state.groups.insert(
+            master,
+            ChannelGroup {
+                control: Control::new(id),
+                name: c"ChannelGroup".to_owned(),
+                parent: None,
+            },
+        );
+        state.systems.get_mut(&id).unwrap().master_group = Some(master);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_Close(system: *mut FMOD_SYSTEM) -> FMOD_RESULT {
+    run(|state| {
+        let id = system.addr();
+        state.system(system)?;
+        state.channels.retain(|_, it| it.control.system != id);
+        state.groups.retain(|_, it| it.control.system != id);
+        let system = state.system(system)?;
+        system.initialized = false;
+        system.master_group = None;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_Update(system: *mut FMOD_SYSTEM) -> FMOD_RESULT {
+    run(|state| state.initialized(system).map(drop))
+}
+
+pub unsafe extern "C" fn FMOD_System_GetVersion(
+    system: *mut FMOD_SYSTEM,
+    version: *mut c_uint,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.system(system)?;
+        write(version, FMOD_VERSION.cast_unsigned())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetChannelsPlaying(
+    system: *mut FMOD_SYSTEM,
+    channels: *mut c_int,
+    realchannels: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.initialized(system)?;
+        let playing = state
+            .channels
+            .values()
+            .filter(|it| it.control.system == system.addr())
+            .count();
+        let playing = c_int::try_from(playing).unwrap_or(c_int::MAX);
+        write_optional(channels, playing);
+        write_optional(realchannels, playing);
+        Ok(())
+    })
+}
+
+unsafe fn create_sound(
+    system: *mut FMOD_SYSTEM,
+    name_or_data: *const c_char,
+    mode: FMOD_MODE,
+    exinfo: *mut FMOD_CREATESOUNDEXINFO,
+    sound: *mut *mut FMOD_SOUND,
+) -> FMOD_RESULT {
+    run(|state| {
+        let id = system.addr();
+        state.initialized(system)?;
+        if sound.is_null() {
+            return Err(INVALID_PARAM);
+        }
+        if !exinfo.is_null()
+            && usize::try_from((*exinfo).cbsize).ok() != Some(size_of::<FMOD_CREATESOUNDEXINFO>())
+        {
+            return Err(INVALID_PARAM);
+        }
+        let from_memory =
+            mode & (FMOD_OPENMEMORY | FMOD_OPENMEMORY_POINT | FMOD_OPENUSER).cast_unsigned() != 0;
+        let name = if from_memory {
+            if exinfo.is_null() || (*exinfo).length == 0 {
+                return Err(INVALID_PARAM);
+            }
+            CString::default()
+        } else {
+            if name_or_data.is_null() {
+                return Err(INVALID_PARAM);
+            }
+            let name = CStr::from_ptr(name_or_data);
+            let path = name
+                .to_str()
+                .map_err(|_| FMOD_RESULT::FMOD_ERR_FILE_NOTFOUND)?;
+            if !path.contains("://") && !Path::new(path).is_file() {
+                return Err(FMOD_RESULT::FMOD_ERR_FILE_NOTFOUND);
+            }
+            name.to_owned()
+        };
+        let sample_rate = state.systems[&id].sample_rate;
+        let new = state.insert_id();
+        state.sounds.insert(
+            new,
+            Sound {
+                system: id,
+                name,
+                mode,
+                length_ms: DEFAULT_SOUND_LENGTH_MS,
+                loop_count: -1,
+                #[allow(clippy::cast_precision_loss)]
+                default_frequency: sample_rate as f32,
+                default_priority: 128,
+                user_data: 0,
+            },
+        );
+        write(sound, handle(new))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_CreateSound(
+    system: *mut FMOD_SYSTEM,
+    name_or_data: *const c_char,
+    mode: FMOD_MODE,
+    exinfo: *mut FMOD_CREATESOUNDEXINFO,
+    sound: *mut *mut FMOD_SOUND,
+) -> FMOD_RESULT {
+    create_sound(system, name_or_data, mode, exinfo, sound)
+}
+
+pub unsafe extern "C" fn FMOD_System_CreateStream(
+    system: *mut FMOD_SYSTEM,
+    name_or_data: *const c_char,
+    mode: FMOD_MODE,
+    exinfo: *mut FMOD_CREATESOUNDEXINFO,
+    sound: *mut *mut FMOD_SOUND,
+) -> FMOD_RESULT {
+    create_sound(system, name_or_data, mode, exinfo, sound)
+}
+
+pub unsafe extern "C" fn FMOD_System_CreateDSPByType(
+    system: *mut FMOD_SYSTEM,
+    type_: FMOD_DSP_TYPE,
+    dsp: *mut *mut FMOD_DSP,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.initialized(system)?;
+        if type_ == FMOD_DSP_TYPE::FMOD_DSP_TYPE_UNKNOWN {
+            return Err(INVALID_PARAM);
+        }
+        let id = state.insert_id();
+        state.dsps.insert(
+            id,
+            Dsp {
+                system: system.addr(),
+                kind: type_,
+                bypass: false,
+                active: false,
+                parameters: HashMap::new(),
+                user_data: 0,
+            },
+        );
+        write(dsp, handle(id))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_CreateChannelGroup(
+    system: *mut FMOD_SYSTEM,
+    name: *const c_char,
+    channelgroup: *mut *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        let master = state.initialized(system)?.master_group;
+        let name = if name.is_null() {
+            CString::default()
+        } else {
+            CStr::from_ptr(name).to_owned()
+        };
+        let id = state.insert_id();
+        state.groups.insert(
+            id,
+            ChannelGroup {
+                control: Control::new(system.addr()),
+                name,
+                parent: master,
+            },
+        );
+        write(channelgroup, handle(id))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_PlaySound(
+    system: *mut FMOD_SYSTEM,
+    sound: *mut FMOD_SOUND,
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    paused: FMOD_BOOL,
+    channel: *mut *mut FMOD_CHANNEL,
+) -> FMOD_RESULT {
+    run(|state| {
+        let master = state.initialized(system)?.master_group;
+        if state.sound(sound)?.system != system.addr() {
+            return Err(INVALID_PARAM);
+        }
+        let group = if channelgroup.is_null() {
+            master.ok_or(FMOD_RESULT::FMOD_ERR_UNINITIALIZED)?
+        } else {
+            state.group(channelgroup)?;
+            channelgroup.addr()
+        };
+        let id = state.play(system.addr(), sound.addr(), group, is_true(paused))?;
+        write_optional(channel, handle(id));
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetChannel(
+    system: *mut FMOD_SYSTEM,
+    channelid: c_int,
+    channel: *mut *mut FMOD_CHANNEL,
+) -> FMOD_RESULT {
+    run(|state| {
+        let max_channels = state.initialized(system)?.max_channels;
+        if !(0..max_channels).contains(&channelid) {
+            return Err(INVALID_PARAM);
+        }
+        let id = state
+            .channels
+            .iter()
+            .find(|(_, it)| it.control.system == system.addr() && it.index == channelid)
+            .map_or(0, |(&id, _)| id);
+        write(channel, handle(id))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetMasterChannelGroup(
+    system: *mut FMOD_SYSTEM,
+    channelgroup: *mut *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        let master = state
+            .initialized(system)?
+            .master_group
+            .ok_or(FMOD_RESULT::FMOD_ERR_UNINITIALIZED)?;
+        write(channelgroup, handle(master))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_SetUserData(
+    system: *mut FMOD_SYSTEM,
+    userdata: *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.system(system)?.user_data = userdata.addr();
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_System_GetUserData(
+    system: *mut FMOD_SYSTEM,
+    userdata: *mut *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| write(userdata, handle(state.system(system)?.user_data)))
+}
+
+pub unsafe extern "C" fn FMOD_Sound_Release(sound: *mut FMOD_SOUND) -> FMOD_RESULT {
+    run(|state| {
+        state.sound(sound)?;
+        state.sounds.remove(&sound.addr());
+        state.stop_channels(|it| it.sound == sound.addr());
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetSystemObject(
+    sound: *mut FMOD_SOUND,
+    system: *mut *mut FMOD_SYSTEM,
+) -> FMOD_RESULT {
+    run(|state| write(system, handle(state.sound(sound)?.system)))
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetName(
+    sound: *mut FMOD_SOUND,
+    name: *mut c_char,
+    namelen: c_int,
+) -> FMOD_RESULT {
+    run(|state| write_name(&state.sound(sound)?.name, name, namelen))
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetLength(
+    sound: *mut FMOD_SOUND,
+    length: *mut c_uint,
+    lengthtype: FMOD_TIMEUNIT,
+) -> FMOD_RESULT {
+    run(|state| {
+        let sound = state.sound(sound)?;
+        let value = from_ms(sound.length_ms, sound.default_frequency, lengthtype)?;
+        write(length, value)
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_SetMode(
+    sound: *mut FMOD_SOUND,
+    mode: FMOD_MODE,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.sound(sound)?.mode = mode;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetMode(
+    sound: *mut FMOD_SOUND,
+    mode: *mut FMOD_MODE,
+) -> FMOD_RESULT {
+    run(|state| write(mode, state.sound(sound)?.mode))
+}
+
+pub unsafe extern "C" fn FMOD_Sound_SetLoopCount(
+    sound: *mut FMOD_SOUND,
+    loopcount: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        if loopcount < -1 {
+            return Err(INVALID_PARAM);
+        }
+        state.sound(sound)?.loop_count = loopcount;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetLoopCount(
+    sound: *mut FMOD_SOUND,
+    loopcount: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| write(loopcount, state.sound(sound)?.loop_count))
+}
+
+pub unsafe extern "C" fn FMOD_Sound_SetDefaults(
+    sound: *mut FMOD_SOUND,
+    frequency: f32,
+    priority: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let frequency = finite(frequency)?;
+        if !(0..=256).contains(&priority) {
+            return Err(INVALID_PARAM);
+        }
+        let sound = state.sound(sound)?;
+        sound.default_frequency = frequency;
+        sound.default_priority = priority;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetDefaults(
+    sound: *mut FMOD_SOUND,
+    frequency: *mut f32,
+    priority: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let sound = state.sound(sound)?;
+        write_optional(frequency, sound.default_frequency);
+        write_optional(priority, sound.default_priority);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_SetUserData(
+    sound: *mut FMOD_SOUND,
+    userdata: *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.sound(sound)?.user_data = userdata.addr();
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Sound_GetUserData(
+    sound: *mut FMOD_SOUND,
+    userdata: *mut *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| write(userdata, handle(state.sound(sound)?.user_data)))
+}
+
+/// Convert milliseconds to `unit`, at `frequency`.
+fn from_ms(ms: u32, frequency: f32, unit: FMOD_TIMEUNIT) -> Result<u32, FMOD_RESULT> {
+    if unit == FMOD_TIMEUNIT_MS.cast_unsigned() {
+        Ok(ms)
+    } else if unit == FMOD_TIMEUNIT_PCM.cast_unsigned() {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok((f64::from(ms) * f64::from(frequency) / 1000.0) as u32)
+    } else {
+        Err(INVALID_PARAM)
+    }
+}
+
+/// Convert `value`, in `unit`, to milliseconds, at `frequency`.
+fn to_ms(value: u32, frequency: f32, unit: FMOD_TIMEUNIT) -> Result<u32, FMOD_RESULT> {
+    if unit == FMOD_TIMEUNIT_MS.cast_unsigned() {
+        Ok(value)
+    } else if unit == FMOD_TIMEUNIT_PCM.cast_unsigned() && frequency > 0.0 {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok((f64::from(value) * 1000.0 / f64::from(frequency)) as u32)
+    } else {
+        Err(INVALID_PARAM)
+    }
+}
+
+/// A channel or channel group, for the functions they share.
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Channel(*mut FMOD_CHANNEL),
+    Group(*mut FMOD_CHANNELGROUP),
+}
+
+impl State {
+    fn control(&mut self, target: Target) -> Result<&mut Control, FMOD_RESULT> {
+        match target {
+            Target::Channel(channel) => Ok(&mut self.channel(channel)?.control),
+            Target::Group(group) => Ok(&mut self.group(group)?.control),
+        }
+    }
+}
+
+unsafe fn get_system_object(
+    state: &mut State,
+    target: Target,
+    system: *mut *mut FMOD_SYSTEM,
+) -> Result<(), FMOD_RESULT> {
+    write(system, handle(state.control(target)?.system))
+}
+
+unsafe fn set_paused(
+    state: &mut State,
+    target: Target,
+    paused: FMOD_BOOL,
+) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.paused = is_true(paused);
+    Ok(())
+}
+
+unsafe fn get_paused(
+    state: &mut State,
+    target: Target,
+    paused: *mut FMOD_BOOL,
+) -> Result<(), FMOD_RESULT> {
+    write(paused, fmod_bool(state.control(target)?.paused))
+}
+
+unsafe fn set_volume(state: &mut State, target: Target, volume: f32) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.volume = finite(volume)?;
+    Ok(())
+}
+
+unsafe fn get_volume(
+    state: &mut State,
+    target: Target,
+    volume: *mut f32,
+) -> Result<(), FMOD_RESULT> {
+    write(volume, state.control(target)?.volume)
+}
+
+unsafe fn set_pitch(state: &mut State, target: Target, pitch: f32) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.pitch = finite(pitch)?.max(0.0);
+    Ok(())
+}
+
+unsafe fn get_pitch(state: &mut State, target: Target, pitch: *mut f32) -> Result<(), FMOD_RESULT> {
+    write(pitch, state.control(target)?.pitch)
+}
+
+unsafe fn set_mute(state: &mut State, target: Target, mute: FMOD_BOOL) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.mute = is_true(mute);
+    Ok(())
+}
+
+unsafe fn get_mute(
+    state: &mut State,
+    target: Target,
+    mute: *mut FMOD_BOOL,
+) -> Result<(), FMOD_RESULT> {
+    write(mute, fmod_bool(state.control(target)?.mute))
+}
+
+unsafe fn set_pan(state: &mut State, target: Target, pan: f32) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.pan = finite(pan)?.clamp(-1.0, 1.0);
+    Ok(())
+}
+
+unsafe fn set_mode(state: &mut State, target: Target, mode: FMOD_MODE) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.mode = mode;
+    Ok(())
+}
+
+unsafe fn get_mode(
+    state: &mut State,
+    target: Target,
+    mode: *mut FMOD_MODE,
+) -> Result<(), FMOD_RESULT> {
+    write(mode, state.control(target)?.mode)
+}
+
+unsafe fn set_low_pass_gain(
+    state: &mut State,
+    target: Target,
+    gain: f32,
+) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.low_pass_gain = finite(gain)?.clamp(0.0, 1.0);
+    Ok(())
+}
+
+unsafe fn get_low_pass_gain(
+    state: &mut State,
+    target: Target,
+    gain: *mut f32,
+) -> Result<(), FMOD_RESULT> {
+    write(gain, state.control(target)?.low_pass_gain)
+}
+
+unsafe fn set_reverb_properties(
+    state: &mut State,
+    target: Target,
+    instance: c_int,
+    wet: f32,
+) -> Result<(), FMOD_RESULT> {
+    let wet = finite(wet)?;
+    let control = state.control(target)?;
+    let instance = usize::try_from(instance).map_err(|_| INVALID_PARAM)?;
+    *control.reverb_wet.get_mut(instance).ok_or(INVALID_PARAM)? = wet;
+    Ok(())
+}
+
+unsafe fn get_reverb_properties(
+    state: &mut State,
+    target: Target,
+    instance: c_int,
+    wet: *mut f32,
+) -> Result<(), FMOD_RESULT> {
+    let control = state.control(target)?;
+    let instance = usize::try_from(instance).map_err(|_| INVALID_PARAM)?;
+    write(wet, *control.reverb_wet.get(instance).ok_or(INVALID_PARAM)?)
+}
+
+unsafe fn set_3d_attributes(
+    state: &mut State,
+    target: Target,
+    pos: *const FMOD_VECTOR,
+    vel: *const FMOD_VECTOR,
+) -> Result<(), FMOD_RESULT> {
+    let control = state.control(target)?;
+    if control.mode & fmod_sys::FMOD_3D.cast_unsigned() == 0 {
+        return Err(FMOD_RESULT::FMOD_ERR_NEEDS3D);
+    }
+    if let Some(pos) = pos.as_ref() {
+        control.position_3d = *pos;
+    }
+    if let Some(vel) = vel.as_ref() {
+        control.velocity_3d = *vel;
+    }
+    Ok(())
+}
+
+unsafe fn get_3d_attributes(
+    state: &mut State,
+    target: Target,
+    pos: *mut FMOD_VECTOR,
+    vel: *mut FMOD_VECTOR,
+) -> Result<(), FMOD_RESULT> {
+    let control = state.control(target)?;
+    write_optional(pos, control.position_3d);
+    write_optional(vel, control.velocity_3d);
+    Ok(())
+}
+
+unsafe fn add_dsp(
+    state: &mut State,
+    target: Target,
+    index: c_int,
+    dsp: *mut FMOD_DSP,
+) -> Result<(), FMOD_RESULT> {
+    state.dsp(dsp)?;
+    if state
+        .channels
+        .values()
+        .any(|it| it.control.dsps.contains(&dsp.addr()))
+        || state
+            .groups
+            .values()
+            .any(|it| it.control.dsps.contains(&dsp.addr()))
+    {
+        return Err(FMOD_RESULT::FMOD_ERR_DSP_INUSE);
+    }
+    let dsps = &mut state.control(target)?.dsps;
+    // The fake has no fader, so both the head and the fader are at index 0.
+    let index = match FMOD_CHANNELCONTROL_DSP_INDEX(index) {
+        FMOD_CHANNELCONTROL_DSP_INDEX::FMOD_CHANNELCONTROL_DSP_HEAD
+        | FMOD_CHANNELCONTROL_DSP_INDEX::FMOD_CHANNELCONTROL_DSP_FADER => 0,
+        FMOD_CHANNELCONTROL_DSP_INDEX::FMOD_CHANNELCONTROL_DSP_TAIL => dsps.len(),
+        _ => usize::try_from(index)
+            .ok()
+            .filter(|it| *it <= dsps.len())
+            .ok_or(INVALID_PARAM)?,
+    };
+    dsps.insert(index, dsp.addr());
+    state.dsps.get_mut(&dsp.addr()).unwrap().active = true;
+    Ok(())
+}
+
+unsafe fn remove_dsp(
+    state: &mut State,
+    target: Target,
+    dsp: *mut FMOD_DSP,
+) -> Result<(), FMOD_RESULT> {
+    state.dsp(dsp)?;
+    let dsps = &mut state.control(target)?.dsps;
+    let index = dsps
+        .iter()
+        .position(|it| *it == dsp.addr())
+        .ok_or(FMOD_RESULT::FMOD_ERR_DSP_NOTFOUND)?;
+    dsps.remove(index);
+    Ok(())
+}
+
+unsafe fn get_num_dsps(
+    state: &mut State,
+    target: Target,
+    numdsps: *mut c_int,
+) -> Result<(), FMOD_RESULT> {
+    let count = state.control(target)?.dsps.len();
+    write(numdsps, c_int::try_from(count).unwrap_or(c_int::MAX))
+}
+
+unsafe fn get_dsp(
+    state: &mut State,
+    target: Target,
+    index: c_int,
+    dsp: *mut *mut FMOD_DSP,
+) -> Result<(), FMOD_RESULT> {
+    let dsps = &state.control(target)?.dsps;
+    let id = usize::try_from(index)
+        .ok()
+        .and_then(|it| dsps.get(it))
+        .ok_or(INVALID_PARAM)?;
+    write(dsp, handle(*id))
+}
+
+unsafe fn set_user_data(
+    state: &mut State,
+    target: Target,
+    userdata: *mut c_void,
+) -> Result<(), FMOD_RESULT> {
+    state.control(target)?.user_data = userdata.addr();
+    Ok(())
+}
+
+unsafe fn get_user_data(
+    state: &mut State,
+    target: Target,
+    userdata: *mut *mut c_void,
+) -> Result<(), FMOD_RESULT> {
+    write(userdata, handle(state.control(target)?.user_data))
+}
+
+/// Define the `FMOD_Channel_*` and `FMOD_ChannelGroup_*` versions of functions they share.
+macro_rules! control_functions {
+    ($($shared:ident => $channel:ident, $group:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        pub unsafe extern "C" fn $channel(channel: *mut FMOD_CHANNEL, $($arg: $ty),*) -> FMOD_RESULT {
+            run(|state| $shared(state, Target::Channel(channel), $($arg),*))
+        }
+
+        pub unsafe extern "C" fn $group(
+            channelgroup: *mut FMOD_CHANNELGROUP,
+            $($arg: $ty),*
+        ) -> FMOD_RESULT {
+            run(|state| $shared(state, Target::Group(channelgroup), $($arg),*))
+        }
+    )*};
+}
+
+control_functions! {
+    get_system_object => FMOD_Channel_GetSystemObject, FMOD_ChannelGroup_GetSystemObject(system: *mut *mut FMOD_SYSTEM);
+    set_paused => FMOD_Channel_SetPaused, FMOD_ChannelGroup_SetPaused(paused: FMOD_BOOL);
+    get_paused => FMOD_Channel_GetPaused, FMOD_ChannelGroup_GetPaused(paused: *mut FMOD_BOOL);
+    set_volume => FMOD_Channel_SetVolume, FMOD_ChannelGroup_SetVolume(volume: f32);
+    get_volume => FMOD_Channel_GetVolume, FMOD_ChannelGroup_GetVolume(volume: *mut f32);
+    set_pitch => FMOD_Channel_SetPitch, FMOD_ChannelGroup_SetPitch(pitch: f32);
+    get_pitch => FMOD_Channel_GetPitch, FMOD_ChannelGroup_GetPitch(pitch: *mut f32);
+    set_mute => FMOD_Channel_SetMute, FMOD_ChannelGroup_SetMute(mute: FMOD_BOOL);
+    get_mute => FMOD_Channel_GetMute, FMOD_ChannelGroup_GetMute(mute: *mut FMOD_BOOL);
+    set_pan => FMOD_Channel_SetPan, FMOD_ChannelGroup_SetPan(pan: f32);
+    set_mode => FMOD_Channel_SetMode, FMOD_ChannelGroup_SetMode(mode: FMOD_MODE);
+    get_mode => FMOD_Channel_GetMode, FMOD_ChannelGroup_GetMode(mode: *mut FMOD_MODE);
+    set_low_pass_gain => FMOD_Channel_SetLowPassGain, FMOD_ChannelGroup_SetLowPassGain(gain: f32);
+    get_low_pass_gain => FMOD_Channel_GetLowPassGain, FMOD_ChannelGroup_GetLowPassGain(gain: *mut f32);
+    set_reverb_properties => FMOD_Channel_SetReverbProperties, FMOD_ChannelGroup_SetReverbProperties(instance: c_int, wet: f32);
+    get_reverb_properties => FMOD_Channel_GetReverbProperties, FMOD_ChannelGroup_GetReverbProperties(instance: c_int, wet: *mut f32);
+    set_3d_attributes => FMOD_Channel_Set3DAttributes, FMOD_ChannelGroup_Set3DAttributes(pos: *const FMOD_VECTOR, vel: *const FMOD_VECTOR);
+    get_3d_attributes => FMOD_Channel_Get3DAttributes, FMOD_ChannelGroup_Get3DAttributes(pos: *mut FMOD_VECTOR, vel: *mut FMOD_VECTOR);
+    add_dsp => FMOD_Channel_AddDSP, FMOD_ChannelGroup_AddDSP(index: c_int, dsp: *mut FMOD_DSP);
+    remove_dsp => FMOD_Channel_RemoveDSP, FMOD_ChannelGroup_RemoveDSP(dsp: *mut FMOD_DSP);
+    get_num_dsps => FMOD_Channel_GetNumDSPs, FMOD_ChannelGroup_GetNumDSPs(numdsps: *mut c_int);
+    get_dsp => FMOD_Channel_GetDSP, FMOD_ChannelGroup_GetDSP(index: c_int, dsp: *mut *mut FMOD_DSP);
+    set_user_data => FMOD_Channel_SetUserData, FMOD_ChannelGroup_SetUserData(userdata: *mut c_void);
+    get_user_data => FMOD_Channel_GetUserData, FMOD_ChannelGroup_GetUserData(userdata: *mut *mut c_void);
+}
+
+/// The group and the groups under it.
+fn subtree(state: &State, group: usize) -> Vec<usize> {
+    let mut groups = vec![group];
+    let mut i = 0;
+    while let Some(&parent) = groups.get(i) {
+        groups.extend(
+            state
+                .groups
+                .iter()
+                .filter(|(_, it)| it.parent == Some(parent))
+                .map(|(&id, _)| id),
+        );
+        i += 1;
+    }
+    groups
+}
+
+pub unsafe extern "C" fn FMOD_Channel_Stop(channel: *mut FMOD_CHANNEL) -> FMOD_RESULT {
+    run(|state| {
+        state.channel(channel)?;
+        state.channels.remove(&channel.addr());
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_IsPlaying(
+    channel: *mut FMOD_CHANNEL,
+    isplaying: *mut FMOD_BOOL,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.channel(channel)?;
+        write(isplaying, fmod_bool(true))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_IsVirtual(
+    channel: *mut FMOD_CHANNEL,
+    isvirtual: *mut FMOD_BOOL,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.channel(channel)?;
+        write(isvirtual, fmod_bool(false))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_SetFrequency(
+    channel: *mut FMOD_CHANNEL,
+    frequency: f32,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.channel(channel)?.frequency = finite(frequency)?;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetFrequency(
+    channel: *mut FMOD_CHANNEL,
+    frequency: *mut f32,
+) -> FMOD_RESULT {
+    run(|state| write(frequency, state.channel(channel)?.frequency))
+}
+
+pub unsafe extern "C" fn FMOD_Channel_SetPriority(
+    channel: *mut FMOD_CHANNEL,
+    priority: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        if !(0..=256).contains(&priority) {
+            return Err(INVALID_PARAM);
+        }
+        state.channel(channel)?.priority = priority;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetPriority(
+    channel: *mut FMOD_CHANNEL,
+    priority: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| write(priority, state.channel(channel)?.priority))
+}
+
+pub unsafe extern "C" fn FMOD_Channel_SetPosition(
+    channel: *mut FMOD_CHANNEL,
+    position: c_uint,
+    postype: FMOD_TIMEUNIT,
+) -> FMOD_RESULT {
+    run(|state| {
+        let (frequency, sound) = {
+            let channel = state.channel(channel)?;
+            (channel.frequency, channel.sound)
+        };
+        let position = to_ms(position, frequency, postype)?;
+        if position >= state.sounds[&sound].length_ms {
+            return Err(FMOD_RESULT::FMOD_ERR_INVALID_POSITION);
+        }
+        state.channel(channel)?.position_ms = position;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetPosition(
+    channel: *mut FMOD_CHANNEL,
+    position: *mut c_uint,
+    postype: FMOD_TIMEUNIT,
+) -> FMOD_RESULT {
+    run(|state| {
+        let channel = state.channel(channel)?;
+        write(
+            position,
+            from_ms(channel.position_ms, channel.frequency, postype)?,
+        )
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_SetLoopCount(
+    channel: *mut FMOD_CHANNEL,
+    loopcount: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        if loopcount < -1 {
+            return Err(INVALID_PARAM);
+        }
+        state.channel(channel)?.loop_count = loopcount;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetLoopCount(
+    channel: *mut FMOD_CHANNEL,
+    loopcount: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| write(loopcount, state.channel(channel)?.loop_count))
+}
+
+pub unsafe extern "C" fn FMOD_Channel_SetChannelGroup(
+    channel: *mut FMOD_CHANNEL,
+    channelgroup: *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.channel(channel)?.control.system;
+        if state.group(channelgroup)?.control.system != system {
+            return Err(INVALID_PARAM);
+        }
+        state.channel(channel)?.group = channelgroup.addr();
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetChannelGroup(
+    channel: *mut FMOD_CHANNEL,
+    channelgroup: *mut *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| write(channelgroup, handle(state.channel(channel)?.group)))
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetCurrentSound(
+    channel: *mut FMOD_CHANNEL,
+    sound: *mut *mut FMOD_SOUND,
+) -> FMOD_RESULT {
+    run(|state| write(sound, handle(state.channel(channel)?.sound)))
+}
+
+pub unsafe extern "C" fn FMOD_Channel_GetIndex(
+    channel: *mut FMOD_CHANNEL,
+    index: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| write(index, state.channel(channel)?.index))
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_Stop(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        let groups = subtree(state, channelgroup.addr());
+        state.stop_channels(|it| groups.contains(&it.group));
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_IsPlaying(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    isplaying: *mut FMOD_BOOL,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        let groups = subtree(state, channelgroup.addr());
+        let playing = state.channels.values().any(|it| groups.contains(&it.group));
+        write(isplaying, fmod_bool(playing))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_Release(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.group(channelgroup)?.control.system;
+        let master = state.systems.get(&system).and_then(|it| it.master_group);
+        if master == Some(channelgroup.addr()) {
+            return Err(INVALID_PARAM);
+        }
+        let parent = state.groups.remove(&channelgroup.addr()).unwrap().parent;
+        // What was in the group moves up to its parent.
+        for channel in state.channels.values_mut() {
+            if channel.group == channelgroup.addr() {
+                channel.group = parent.or(master).unwrap_or_default();
+            }
+        }
+        for group in state.groups.values_mut() {
+            if group.parent == Some(channelgroup.addr()) {
+                group.parent = parent.or(master);
+            }
+        }
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_AddGroup(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    group: *mut FMOD_CHANNELGROUP,
+    _propagatedspclock: FMOD_BOOL,
+    connection: *mut *mut FMOD_DSPCONNECTION,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        state.group(group)?;
+        if subtree(state, group.addr()).contains(&channelgroup.addr()) {
+            return Err(INVALID_PARAM);
+        }
+        state.group(group)?.parent = Some(channelgroup.addr());
+        // The fake doesn't model the DSP graph's connections.
+        write_optional(connection, std::ptr::null_mut());
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_GetNumGroups(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    numgroups: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        let count = children(state, channelgroup.addr()).len();
+        write(numgroups, c_int::try_from(count).unwrap_or(c_int::MAX))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_GetGroup(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    index: c_int,
+    group: *mut *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        let children = children(state, channelgroup.addr());
+        let id = usize::try_from(index)
+            .ok()
+            .and_then(|it| children.get(it))
+            .ok_or(INVALID_PARAM)?;
+        write(group, handle(*id))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_GetParentGroup(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    group: *mut *mut FMOD_CHANNELGROUP,
+) -> FMOD_RESULT {
+    run(|state| {
+        let parent = state.group(channelgroup)?.parent.unwrap_or_default();
+        write(group, handle(parent))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_GetName(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    name: *mut c_char,
+    namelen: c_int,
+) -> FMOD_RESULT {
+    run(|state| write_name(&state.group(channelgroup)?.name, name, namelen))
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_GetNumChannels(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    numchannels: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        let count = channels_in(state, channelgroup.addr()).len();
+        write(numchannels, c_int::try_from(count).unwrap_or(c_int::MAX))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_ChannelGroup_GetChannel(
+    channelgroup: *mut FMOD_CHANNELGROUP,
+    index: c_int,
+    channel: *mut *mut FMOD_CHANNEL,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.group(channelgroup)?;
+        let channels = channels_in(state, channelgroup.addr());
+        let id = usize::try_from(index)
+            .ok()
+            .and_then(|it| channels.get(it))
+            .ok_or(INVALID_PARAM)?;
+        write(channel, handle(*id))
+    })
+}
+
+/// The groups directly under `group`, oldest first.
+fn children(state: &State, group: usize) -> Vec<usize> {
+    let mut children = state
+        .groups
+        .iter()
+        .filter(|(_, it)| it.parent == Some(group))
+        .map(|(&id, _)| id)
+        .collect::<Vec<_>>();
+    children.sort_unstable();
+    children
+}
+
+/// The channels directly in `group`, oldest first.
+fn channels_in(state: &State, group: usize) -> Vec<usize> {
+    let mut channels = state
+        .channels
+        .iter()
+        .filter(|(_, it)| it.group == group)
+        .map(|(&id, _)| id)
+        .collect::<Vec<_>>();
+    channels.sort_unstable();
+    channels
+}
+
+pub unsafe extern "C" fn FMOD_DSP_Release(dsp: *mut FMOD_DSP) -> FMOD_RESULT {
+    run(|state| {
+        state.dsp(dsp)?;
+        let in_use = state
+            .channels
+            .values()
+            .map(|it| &it.control)
+            .chain(state.groups.values().map(|it| &it.control))
+            .any(|it| it.dsps.contains(&dsp.addr()));
+        if in_use {
+            return Err(FMOD_RESULT::FMOD_ERR_DSP_INUSE);
+        }
+        state.dsps.remove(&dsp.addr());
+        state.disconnect(dsp.addr());
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetSystemObject(
+    dsp: *mut FMOD_DSP,
+    system: *mut *mut FMOD_SYSTEM,
+) -> FMOD_RESULT {
+    run(|state| write(system, handle(state.dsp(dsp)?.system)))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetType(
+    dsp: *mut FMOD_DSP,
+    type_: *mut FMOD_DSP_TYPE,
+) -> FMOD_RESULT {
+    run(|state| write(type_, state.dsp(dsp)?.kind))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_SetBypass(dsp: *mut FMOD_DSP, bypass: FMOD_BOOL) -> FMOD_RESULT {
+    run(|state| {
+        state.dsp(dsp)?.bypass = is_true(bypass);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetBypass(
+    dsp: *mut FMOD_DSP,
+    bypass: *mut FMOD_BOOL,
+) -> FMOD_RESULT {
+    run(|state| write(bypass, fmod_bool(state.dsp(dsp)?.bypass)))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_SetActive(dsp: *mut FMOD_DSP, active: FMOD_BOOL) -> FMOD_RESULT {
+    run(|state| {
+        state.dsp(dsp)?.active = is_true(active);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetActive(
+    dsp: *mut FMOD_DSP,
+    active: *mut FMOD_BOOL,
+) -> FMOD_RESULT {
+    run(|state| write(active, fmod_bool(state.dsp(dsp)?.active)))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetNumParameters(
+    dsp: *mut FMOD_DSP,
+    numparams: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let count = state
+            .dsp(dsp)?
+            .parameters
+            .keys()
+            .max()
+            .map_or(0, |it| it + 1);
+        write(numparams, count)
+    })
+}
+
+/// Set a parameter, which must keep the type it was first set with.
+fn set_parameter(
+    state: &mut State,
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: Parameter,
+) -> Result<(), FMOD_RESULT> {
+    if index < 0 {
+        return Err(INVALID_PARAM);
+    }
+    let parameters = &mut state.dsp(dsp)?.parameters;
+    match parameters.get(&index) {
+        Some(old) if std::mem::discriminant(old) != std::mem::discriminant(&value) => {
+            Err(INVALID_PARAM)
+        }
+        _ => {
+            parameters.insert(index, value);
+            Ok(())
+        }
+    }
+}
+
+/// Get a parameter, which is zero until it's set.
+fn get_parameter(
+    state: &mut State,
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    default: Parameter,
+) -> Result<Parameter, FMOD_RESULT> {
+    if index < 0 {
+        return Err(INVALID_PARAM);
+    }
+    match state.dsp(dsp)?.parameters.get(&index) {
+        Some(value) if std::mem::discriminant(value) != std::mem::discriminant(&default) => {
+            Err(INVALID_PARAM)
+        }
+        Some(value) => Ok(*value),
+        None => Ok(default),
+    }
+}
+
+pub unsafe extern "C" fn FMOD_DSP_SetParameterFloat(
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: f32,
+) -> FMOD_RESULT {
+    run(|state| set_parameter(state, dsp, index, Parameter::Float(finite(value)?)))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetParameterFloat(
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: *mut f32,
+    valuestr: *mut c_char,
+    valuestrlen: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let Parameter::Float(it) = get_parameter(state, dsp, index, Parameter::Float(0.0))? else {
+            unreachable!()
+        };
+        write_optional(value, it);
+        if !valuestr.is_null() {
+            write_name(
+                &CString::new(format!("{it:.2}")).unwrap(),
+                valuestr,
+                valuestrlen,
+            )?;
+        }
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_SetParameterInt(
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: c_int,
+) -> FMOD_RESULT {
+    run(|state| set_parameter(state, dsp, index, Parameter::Int(value)))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetParameterInt(
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: *mut c_int,
+    valuestr: *mut c_char,
+    valuestrlen: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let Parameter::Int(it) = get_parameter(state, dsp, index, Parameter::Int(0))? else {
+            unreachable!()
+        };
+        write_optional(value, it);
+        if !valuestr.is_null() {
+            write_name(
+                &CString::new(it.to_string()).unwrap(),
+                valuestr,
+                valuestrlen,
+            )?;
+        }
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_SetParameterBool(
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: FMOD_BOOL,
+) -> FMOD_RESULT {
+    run(|state| set_parameter(state, dsp, index, Parameter::Bool(is_true(value))))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetParameterBool(
+    dsp: *mut FMOD_DSP,
+    index: c_int,
+    value: *mut FMOD_BOOL,
+    valuestr: *mut c_char,
+    valuestrlen: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let Parameter::Bool(it) = get_parameter(state, dsp, index, Parameter::Bool(false))? else {
+            unreachable!()
+        };
+        write_optional(value, fmod_bool(it));
+        if !valuestr.is_null() {
+            let text = if it { c"On" } else { c"Off" };
+            write_name(text, valuestr, valuestrlen)?;
+        }
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_SetUserData(
+    dsp: *mut FMOD_DSP,
+    userdata: *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.dsp(dsp)?.user_data = userdata.addr();
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetUserData(
+    dsp: *mut FMOD_DSP,
+    userdata: *mut *mut c_void,
+) -> FMOD_RESULT {
+    run(|state| write(userdata, handle(state.dsp(dsp)?.user_data)))
+}
+
+pub unsafe extern "C" fn FMOD_DSP_AddInput(
+    dsp: *mut FMOD_DSP,
+    input: *mut FMOD_DSP,
+    connection: *mut *mut FMOD_DSPCONNECTION,
+    type_: FMOD_DSPCONNECTION_TYPE,
+) -> FMOD_RESULT {
+    run(|state| {
+        let system = state.dsp(dsp)?.system;
+        if state.dsp(input)?.system != system || dsp == input {
+            return Err(INVALID_PARAM);
+        }
+        let id = state.insert_id();
+        state.connections.insert(
+            id,
+            Connection {
+                output: dsp.addr(),
+                input: input.addr(),
+                kind: type_,
+                mix: 1.0,
+                matrix: None,
+            },
+        );
+        write_optional(connection, handle(id));
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_DisconnectFrom(
+    dsp: *mut FMOD_DSP,
+    target: *mut FMOD_DSP,
+    connection: *mut FMOD_DSPCONNECTION,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.dsp(dsp)?;
+        if target.is_null() {
+            state.disconnect(dsp.addr());
+            return Ok(());
+        }
+        state.dsp(target)?;
+        if !connection.is_null() {
+            state.connection(connection)?;
+        }
+        let between = [dsp.addr(), target.addr()];
+        state.connections.retain(|&id, it| {
+            let matches = between.contains(&it.output) && between.contains(&it.input);
+            !matches || (!connection.is_null() && id != connection.addr())
+        });
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSP_GetNumInputs(
+    dsp: *mut FMOD_DSP,
+    numinputs: *mut c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.dsp(dsp)?;
+        let count = state
+            .connections
+            .values()
+            .filter(|it| it.output == dsp.addr())
+            .count();
+        write(numinputs, c_int::try_from(count).unwrap_or(c_int::MAX))
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSPConnection_GetType(
+    dspconnection: *mut FMOD_DSPCONNECTION,
+    type_: *mut FMOD_DSPCONNECTION_TYPE,
+) -> FMOD_RESULT {
+    run(|state| write(type_, state.connection(dspconnection)?.kind))
+}
+
+pub unsafe extern "C" fn FMOD_DSPConnection_SetMix(
+    dspconnection: *mut FMOD_DSPCONNECTION,
+    volume: f32,
+) -> FMOD_RESULT {
+    run(|state| {
+        state.connection(dspconnection)?.mix = finite(volume)?;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn FMOD_DSPConnection_GetMix(
+    dspconnection: *mut FMOD_DSPCONNECTION,
+    volume: *mut f32,
+) -> FMOD_RESULT {
+    run(|state| write(volume, state.connection(dspconnection)?.mix))
+}
+
+/// Set the mix matrix, with rows `inchannel_hop` apart, or clear it if `matrix` is null.
+pub unsafe extern "C" fn FMOD_DSPConnection_SetMixMatrix(
+    dspconnection: *mut FMOD_DSPCONNECTION,
+    matrix: *mut f32,
+    outchannels: c_int,
+    inchannels: c_int,
+    inchannel_hop: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let connection = state.connection(dspconnection)?;
+        if matrix.is_null() {
+            connection.matrix = None;
+            return Ok(());
+        }
+        let hop = if inchannel_hop == 0 {
+            inchannels
+        } else {
+            inchannel_hop
+        };
+        let channels = 1..=32;
+        if !channels.contains(&outchannels) || !channels.contains(&inchannels) || hop < inchannels {
+            return Err(INVALID_PARAM);
+        }
+        let (out_len, in_len, hop) = (
+            usize::try_from(outchannels).unwrap(),
+            usize::try_from(inchannels).unwrap(),
+            usize::try_from(hop).unwrap(),
+        );
+        let mut levels = Vec::with_capacity(out_len * in_len);
+        for row in 0..out_len {
+            for column in 0..in_len {
+                levels.push(finite(*matrix.add(row * hop + column))?);
+            }
+        }
+        connection.matrix = Some(MixMatrix {
+            out_channels: outchannels,
+            in_channels: inchannels,
+            levels,
+        });
+        Ok(())
+    })
+}
+
+/// Get the mix matrix, with rows `inchannel_hop` apart. Connections without one have no
+/// channels, as the fake doesn't know how many a DSP has.
+pub unsafe extern "C" fn FMOD_DSPConnection_GetMixMatrix(
+    dspconnection: *mut FMOD_DSPCONNECTION,
+    matrix: *mut f32,
+    outchannels: *mut c_int,
+    inchannels: *mut c_int,
+    inchannel_hop: c_int,
+) -> FMOD_RESULT {
+    run(|state| {
+        let connection = state.connection(dspconnection)?;
+        let Some(set) = &connection.matrix else {
+            write_optional(outchannels, 0);
+            write_optional(inchannels, 0);
+            return Ok(());
+        };
+        write_optional(outchannels, set.out_channels);
+        write_optional(inchannels, set.in_channels);
+        if !matrix.is_null() {
+            let in_len = usize::try_from(set.in_channels).unwrap();
+            let hop = if inchannel_hop == 0 {
+                in_len
+            } else {
+                usize::try_from(inchannel_hop)
+                    .ok()
+                    .filter(|it| *it >= in_len)
+                    .ok_or(INVALID_PARAM)?
+            };
+            for (index, level) in set.levels.iter().enumerate() {
+                matrix
+                    .add(index / in_len * hop + index % in_len)
+                    .write(*level);
+            }
+        }
+        Ok(())
+    })
+}
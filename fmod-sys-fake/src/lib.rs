@@ -0,0 +1,418 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! An in-memory stand-in for part of the FMOD Core API, for testing code that uses
+//! `fmod-sys` without FMOD.
+//!
+//! [`functions`] has the same signatures as `fmod_sys::functions`, so it can be swapped in
+//! for tests:
+//!
+//! ``` rust
+//! #[cfg(not(test))]
+//! use fmod_sys::functions as fmod_sys_fns;
+//! #[cfg(test)]
+//! use fmod_sys_fake::functions as fmod_sys_fns;
+//! ```
+//!
+//! Systems, sounds, channels, channel groups, DSPs and the connections between DSPs are
+//! tracked with their properties, and calls fail the way FMOD's do: released or unknown
+//! handles give `FMOD_ERR_INVALID_HANDLE`, channels whose voice was stolen give
+//! `FMOD_ERR_CHANNEL_STOLEN`, and so on. Nothing is decoded or mixed. Every sound is one
+//! second long, unless [`set_sound_length`] says otherwise, and time only passes when
+//! [`advance`] is called.
+
+#![allow(non_snake_case)]
+
+use fmod_sys::{FMOD_LOOP_BIDI, FMOD_LOOP_NORMAL, FMOD_RESULT, FMOD_SOUND, FMOD_SYSTEM};
+
+pub mod functions;
+mod state;
+
+use state::run;
+
+/// Set how long a sound is, in milliseconds.
+pub fn set_sound_length(sound: *mut FMOD_SOUND, length_ms: u32) -> FMOD_RESULT {
+    run(|state| {
+        state.sound(sound)?.length_ms = length_ms.max(1);
+        Ok(())
+    })
+}
+
+/// Let `ms` milliseconds pass for the channels of `system`. Channels that aren't paused,
+/// and aren't in a paused group, move on, looping if their mode and loop count say to, and
+/// stop when they reach the end of their sound.
+pub fn advance(system: *mut FMOD_SYSTEM, ms: u32) -> FMOD_RESULT {
+    run(|state| {
+        state.initialized(system)?;
+        let looping = (FMOD_LOOP_NORMAL | FMOD_LOOP_BIDI).cast_unsigned();
+        let ids = state
+            .channels
+            .iter()
+            .filter(|(_, it)| it.control.system == system.addr())
+            .map(|(&id, _)| id)
+            .collect::<Vec<_>>();
+        for id in ids {
+            let channel = &state.channels[&id];
+            if channel.control.paused || state.group_paused(channel.group) {
+                continue;
+            }
+            let length = state.sounds[&channel.sound].length_ms;
+            let Some(channel) = state.channels.get_mut(&id) else {
+                continue;
+            };
+            let mut position = channel.position_ms.saturating_add(ms);
+            let mut finished = false;
+            while position >= length {
+                if length == 0 || channel.control.mode & looping == 0 || channel.loop_count == 0 {
+                    finished = true;
+                    break;
+                }
+                position -= length;
+                if channel.loop_count > 0 {
+                    channel.loop_count -= 1;
+                }
+            }
+            channel.position_ms = position;
+            if finished {
+                state.channels.remove(&id);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::null_mut;
+
+    use fmod_sys::{
+        FMOD_CHANNEL, FMOD_CHANNELGROUP, FMOD_CREATESOUNDEXINFO, FMOD_DSPCONNECTION_TYPE,
+        FMOD_DSP_TYPE, FMOD_OPENUSER, FMOD_TIMEUNIT_MS, FMOD_VERSION,
+    };
+
+    use super::*;
+    use crate::functions::*;
+
+    /// Create and initialize a system with room for `max_channels` channels.
+    unsafe fn system(max_channels: i32) -> *mut FMOD_SYSTEM {
+        let mut system = null_mut();
+        assert_eq!(
+            FMOD_System_Create(&raw mut system, FMOD_VERSION.cast_unsigned()),
+            FMOD_RESULT::FMOD_OK
+        );
+        assert_eq!(
+            FMOD_System_Init(system, max_channels, 0, null_mut()),
+            FMOD_RESULT::FMOD_OK
+        );
+        system
+    }
+
+    /// Create a user sound with `system`.
+    unsafe fn sound(system: *mut FMOD_SYSTEM) -> *mut FMOD_SOUND {
+        let mut exinfo = std::mem::zeroed::<FMOD_CREATESOUNDEXINFO>();
+        exinfo.cbsize = size_of::<FMOD_CREATESOUNDEXINFO>().try_into().unwrap();
+        exinfo.length = 16;
+        let mut sound = null_mut();
+        let mode = FMOD_OPENUSER.cast_unsigned();
+        assert_eq!(
+            FMOD_System_CreateSound(
+                system,
+                std::ptr::null(),
+                mode,
+                &raw mut exinfo,
+                &raw mut sound,
+            ),
+            FMOD_RESULT::FMOD_OK
+        );
+        sound
+    }
+
+    unsafe fn play(
+        system: *mut FMOD_SYSTEM,
+        sound: *mut FMOD_SOUND,
+        group: *mut FMOD_CHANNELGROUP,
+    ) -> *mut FMOD_CHANNEL {
+        let mut channel = null_mut();
+        assert_eq!(
+            FMOD_System_PlaySound(system, sound, group, 0, &raw mut channel),
+            FMOD_RESULT::FMOD_OK
+        );
+        channel
+    }
+
+    #[test]
+    fn channels_finish() {
+        unsafe {
+            let system = system(1);
+            let sound = sound(system);
+            assert_eq!(set_sound_length(sound, 500), FMOD_RESULT::FMOD_OK);
+
+            let mut channel = null_mut();
+            FMOD_System_PlaySound(system, sound, null_mut(), 0, &raw mut channel);
+            assert_eq!(advance(system, 200), FMOD_RESULT::FMOD_OK);
+            let mut position = 0;
+            let unit = FMOD_TIMEUNIT_MS.cast_unsigned();
+            FMOD_Channel_GetPosition(channel, &raw mut position, unit);
+            assert_eq!(position, 200);
+
+            assert_eq!(advance(system, 400), FMOD_RESULT::FMOD_OK);
+            let mut playing = 0;
+            assert_eq!(
+                FMOD_Channel_IsPlaying(channel, &raw mut playing),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+
+            let mut stolen = null_mut();
+            FMOD_System_PlaySound(system, sound, null_mut(), 0, &raw mut stolen);
+            FMOD_System_PlaySound(system, sound, null_mut(), 0, &raw mut channel);
+            assert_eq!(
+                FMOD_Channel_IsPlaying(stolen, &raw mut playing),
+                FMOD_RESULT::FMOD_ERR_CHANNEL_STOLEN
+            );
+            assert_eq!(FMOD_System_Release(system), FMOD_RESULT::FMOD_OK);
+        }
+    }
+
+    #[test]
+    fn invalid_handles() {
+        unsafe {
+            let system = system(4);
+            let sound = sound(system);
+            let mut length = 0;
+            let unit = FMOD_TIMEUNIT_MS.cast_unsigned();
+            assert_eq!(
+                FMOD_Sound_GetLength(null_mut(), &raw mut length, unit),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            // A handle that was never given out.
+            assert_eq!(
+                FMOD_Sound_GetLength(sound.wrapping_byte_add(1), &raw mut length, unit),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            assert_eq!(FMOD_Sound_Release(sound), FMOD_RESULT::FMOD_OK);
+            assert_eq!(
+                FMOD_Sound_GetLength(sound, &raw mut length, unit),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            assert_eq!(
+                FMOD_Sound_Release(sound),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            assert_eq!(FMOD_System_Release(system), FMOD_RESULT::FMOD_OK);
+            assert_eq!(
+                FMOD_System_Release(system),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+        }
+    }
+
+    #[test]
+    fn release_cascades() {
+        unsafe {
+            let other = system(4);
+            let system = system(4);
+            let kept = sound(other);
+            let sound = sound(system);
+            let channel = play(system, sound, null_mut());
+            let mut group = null_mut();
+            FMOD_System_CreateChannelGroup(system, c"group".as_ptr(), &raw mut group);
+
+            assert_eq!(FMOD_System_Release(system), FMOD_RESULT::FMOD_OK);
+            let mut length = 0;
+            let unit = FMOD_TIMEUNIT_MS.cast_unsigned();
+            assert_eq!(
+                FMOD_Sound_GetLength(sound, &raw mut length, unit),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            let mut playing = 0;
+            assert_eq!(
+                FMOD_Channel_IsPlaying(channel, &raw mut playing),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            assert_eq!(
+                FMOD_ChannelGroup_IsPlaying(group, &raw mut playing),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            // Another system's sounds are left alone.
+            assert_eq!(
+                FMOD_Sound_GetLength(kept, &raw mut length, unit),
+                FMOD_RESULT::FMOD_OK
+            );
+            assert_eq!(FMOD_System_Release(other), FMOD_RESULT::FMOD_OK);
+        }
+    }
+
+    #[test]
+    fn channels_are_stolen() {
+        unsafe {
+            let system = system(2);
+            let sound = sound(system);
+            let oldest = play(system, sound, null_mut());
+            let older = play(system, sound, null_mut());
+            let newest = play(system, sound, null_mut());
+            let mut playing = 0;
+            assert_eq!(
+                FMOD_Channel_IsPlaying(oldest, &raw mut playing),
+                FMOD_RESULT::FMOD_ERR_CHANNEL_STOLEN
+            );
+            for channel in [older, newest] {
+                assert_eq!(
+                    FMOD_Channel_IsPlaying(channel, &raw mut playing),
+                    FMOD_RESULT::FMOD_OK
+                );
+                assert_eq!(playing, 1);
+            }
+            // Only two channels are ever playing.
+            let mut master = null_mut();
+            FMOD_System_GetMasterChannelGroup(system, &raw mut master);
+            let mut count = 0;
+            FMOD_ChannelGroup_GetNumChannels(master, &raw mut count);
+            assert_eq!(count, 2);
+            assert_eq!(FMOD_System_Release(system), FMOD_RESULT::FMOD_OK);
+        }
+    }
+
+    #[test]
+    fn channel_groups() {
+        unsafe {
+            let system = system(4);
+            let sound = sound(system);
+            let mut master = null_mut();
+            FMOD_System_GetMasterChannelGroup(system, &raw mut master);
+            let mut parent = null_mut();
+            FMOD_System_CreateChannelGroup(system, c"parent".as_ptr(), &raw mut parent);
+            let mut child = null_mut();
+            FMOD_System_CreateChannelGroup(system, c"child".as_ptr(), &raw mut child);
+
+            // New groups go under the master group until they're added to another.
+            let mut above = null_mut();
+            FMOD_ChannelGroup_GetParentGroup(child, &raw mut above);
+            assert_eq!(above, master);
+            assert_eq!(
+                FMOD_ChannelGroup_AddGroup(parent, child, 1, null_mut()),
+                FMOD_RESULT::FMOD_OK
+            );
+            FMOD_ChannelGroup_GetParentGroup(child, &raw mut above);
+            assert_eq!(above, parent);
+            let mut count = 0;
+            FMOD_ChannelGroup_GetNumGroups(parent, &raw mut count);
+            assert_eq!(count, 1);
+            let mut group = null_mut();
+            FMOD_ChannelGroup_GetGroup(parent, 0, &raw mut group);
+            assert_eq!(group, child);
+
+            let channel = play(system, sound, child);
+            let mut its_group = null_mut();
+            FMOD_Channel_GetChannelGroup(channel, &raw mut its_group);
+            assert_eq!(its_group, child);
+            FMOD_ChannelGroup_GetNumChannels(child, &raw mut count);
+            assert_eq!(count, 1);
+            let mut member = null_mut();
+            FMOD_ChannelGroup_GetChannel(child, 0, &raw mut member);
+            assert_eq!(member, channel);
+            FMOD_ChannelGroup_GetNumChannels(master, &raw mut count);
+            assert_eq!(count, 0);
+
+            assert_eq!(
+                FMOD_Channel_SetChannelGroup(channel, parent),
+                FMOD_RESULT::FMOD_OK
+            );
+            FMOD_ChannelGroup_GetNumChannels(child, &raw mut count);
+            assert_eq!(count, 0);
+            assert_eq!(
+                FMOD_Channel_SetChannelGroup(channel, child),
+                FMOD_RESULT::FMOD_OK
+            );
+
+            // Releasing a group moves what was in it up to its parent.
+            assert_eq!(FMOD_ChannelGroup_Release(child), FMOD_RESULT::FMOD_OK);
+            FMOD_Channel_GetChannelGroup(channel, &raw mut its_group);
+            assert_eq!(its_group, parent);
+            FMOD_ChannelGroup_GetNumGroups(parent, &raw mut count);
+            assert_eq!(count, 0);
+            assert_eq!(
+                FMOD_ChannelGroup_GetNumChannels(child, &raw mut count),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+            // The master group can't be released.
+            assert_eq!(
+                FMOD_ChannelGroup_Release(master),
+                FMOD_RESULT::FMOD_ERR_INVALID_PARAM
+            );
+            assert_eq!(FMOD_System_Release(system), FMOD_RESULT::FMOD_OK);
+        }
+    }
+
+    #[test]
+    fn dsp_connections() {
+        unsafe {
+            let system = system(4);
+            let dsp = |kind| {
+                let mut dsp = null_mut();
+                assert_eq!(
+                    FMOD_System_CreateDSPByType(system, kind, &raw mut dsp),
+                    FMOD_RESULT::FMOD_OK
+                );
+                dsp
+            };
+            let mixer = dsp(FMOD_DSP_TYPE::FMOD_DSP_TYPE_MIXER);
+            let echo = dsp(FMOD_DSP_TYPE::FMOD_DSP_TYPE_ECHO);
+            let lowpass = dsp(FMOD_DSP_TYPE::FMOD_DSP_TYPE_LOWPASS);
+            let standard = FMOD_DSPCONNECTION_TYPE::FMOD_DSPCONNECTION_TYPE_STANDARD;
+
+            let mut connection = null_mut();
+            assert_eq!(
+                FMOD_DSP_AddInput(mixer, echo, &raw mut connection, standard),
+                FMOD_RESULT::FMOD_OK
+            );
+            assert_eq!(
+                FMOD_DSP_AddInput(mixer, lowpass, null_mut(), standard),
+                FMOD_RESULT::FMOD_OK
+            );
+            assert_eq!(
+                FMOD_DSP_AddInput(mixer, mixer, null_mut(), standard),
+                FMOD_RESULT::FMOD_ERR_INVALID_PARAM
+            );
+            let mut inputs = 0;
+            FMOD_DSP_GetNumInputs(mixer, &raw mut inputs);
+            assert_eq!(inputs, 2);
+            let mut mix = 0.0;
+            assert_eq!(
+                FMOD_DSPConnection_GetMix(connection, &raw mut mix),
+                FMOD_RESULT::FMOD_OK
+            );
+            assert!((mix - 1.0).abs() < f32::EPSILON);
+
+            // Disconnecting works from either end.
+            assert_eq!(
+                FMOD_DSP_DisconnectFrom(echo, mixer, connection),
+                FMOD_RESULT::FMOD_OK
+            );
+            FMOD_DSP_GetNumInputs(mixer, &raw mut inputs);
+            assert_eq!(inputs, 1);
+            assert_eq!(
+                FMOD_DSPConnection_GetMix(connection, &raw mut mix),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+
+            // Releasing a DSP disconnects it from everything.
+            assert_eq!(FMOD_DSP_Release(lowpass), FMOD_RESULT::FMOD_OK);
+            FMOD_DSP_GetNumInputs(mixer, &raw mut inputs);
+            assert_eq!(inputs, 0);
+            assert_eq!(
+                FMOD_DSP_GetNumInputs(lowpass, &raw mut inputs),
+                FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+            );
+
+            FMOD_DSP_AddInput(mixer, echo, null_mut(), standard);
+            assert_eq!(
+                FMOD_DSP_DisconnectFrom(mixer, null_mut(), null_mut()),
+                FMOD_RESULT::FMOD_OK
+            );
+            FMOD_DSP_GetNumInputs(mixer, &raw mut inputs);
+            assert_eq!(inputs, 0);
+            assert_eq!(FMOD_System_Release(system), FMOD_RESULT::FMOD_OK);
+        }
+    }
+}
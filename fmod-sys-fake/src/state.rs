@@ -0,0 +1,346 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! The objects the fake hands out handles to, and the tables they live in.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::CString,
+    sync::{LazyLock, Mutex, MutexGuard},
+};
+
+use fmod_sys::{
+    FMOD_CHANNEL, FMOD_CHANNELGROUP, FMOD_DSP, FMOD_DSPCONNECTION, FMOD_DSPCONNECTION_TYPE,
+    FMOD_DSP_TYPE, FMOD_MODE, FMOD_OUTPUTTYPE, FMOD_RESULT, FMOD_SOUND, FMOD_SPEAKERMODE,
+    FMOD_SYSTEM, FMOD_VECTOR,
+};
+
+/// The length given to sounds, since the fake doesn't decode anything.
+pub(crate) const DEFAULT_SOUND_LENGTH_MS: u32 = 1000;
+
+/// Everything the fake knows about, shared by all systems.
+static STATE: LazyLock<Mutex<State>> = LazyLock::new(Mutex::default);
+
+/// Lock the state, carrying on if a panic poisoned it, since a failed test shouldn't fail
+/// the tests running alongside it.
+pub(crate) fn state() -> MutexGuard<'static, State> {
+    STATE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Run `f` on the state, turning its error into the returned `FMOD_RESULT`.
+pub(crate) fn run(f: impl FnOnce(&mut State) -> Result<(), FMOD_RESULT>) -> FMOD_RESULT {
+    match f(&mut state()) {
+        Ok(()) => FMOD_RESULT::FMOD_OK,
+        Err(err) => err,
+    }
+}
+
+/// A handle of type `T` for the object `id`. Handles are never dereferenced, so they only
+/// need to be unique and non-null.
+pub(crate) fn handle<T>(id: usize) -> *mut T {
+    std::ptr::without_provenance_mut(id)
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    next_id: usize,
+    pub(crate) systems: HashMap<usize, System>,
+    pub(crate) sounds: HashMap<usize, Sound>,
+    pub(crate) channels: HashMap<usize, Channel>,
+    /// Channels whose voices were given to newer channels.
+    pub(crate) stolen: HashSet<usize>,
+    pub(crate) groups: HashMap<usize, ChannelGroup>,
+    pub(crate) dsps: HashMap<usize, Dsp>,
+    pub(crate) connections: HashMap<usize, Connection>,
+}
+
+#[derive(Debug)]
+pub(crate) struct System {
+    pub(crate) initialized: bool,
+    pub(crate) max_channels: i32,
+    pub(crate) output: FMOD_OUTPUTTYPE,
+    pub(crate) sample_rate: i32,
+    pub(crate) speaker_mode: FMOD_SPEAKERMODE,
+    pub(crate) raw_speakers: i32,
+    pub(crate) dsp_buffer_length: u32,
+    pub(crate) dsp_buffer_count: i32,
+    pub(crate) master_group: Option<usize>,
+    pub(crate) user_data: usize,
+}
+
+impl Default for System {
+    fn default() -> Self {
+        Self {
+            initialized: false,
+            max_channels: 0,
+            output: FMOD_OUTPUTTYPE::FMOD_OUTPUTTYPE_AUTODETECT,
+            sample_rate: 48000,
+            speaker_mode: FMOD_SPEAKERMODE::FMOD_SPEAKERMODE_STEREO,
+            raw_speakers: 0,
+            dsp_buffer_length: 1024,
+            dsp_buffer_count: 4,
+            master_group: None,
+            user_data: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Sound {
+    pub(crate) system: usize,
+    pub(crate) name: CString,
+    pub(crate) mode: FMOD_MODE,
+    pub(crate) length_ms: u32,
+    pub(crate) loop_count: i32,
+    pub(crate) default_frequency: f32,
+    pub(crate) default_priority: i32,
+    pub(crate) user_data: usize,
+}
+
+/// What channels and channel groups have in common, as `FMOD_CHANNELCONTROL`.
+#[derive(Debug)]
+pub(crate) struct Control {
+    pub(crate) system: usize,
+    pub(crate) paused: bool,
+    pub(crate) volume: f32,
+    pub(crate) pitch: f32,
+    pub(crate) pan: f32,
+    pub(crate) mute: bool,
+    pub(crate) mode: FMOD_MODE,
+    pub(crate) low_pass_gain: f32,
+    pub(crate) reverb_wet: [f32; 4],
+    pub(crate) position_3d: FMOD_VECTOR,
+    pub(crate) velocity_3d: FMOD_VECTOR,
+    pub(crate) dsps: Vec<usize>,
+    pub(crate) user_data: usize,
+}
+
+impl Control {
+    pub(crate) fn new(system: usize) -> Self {
+        let zero = FMOD_VECTOR {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        Self {
+            system,
+            paused: false,
+            volume: 1.0,
+            pitch: 1.0,
+            pan: 0.0,
+            mute: false,
+            mode: 0,
+            low_pass_gain: 1.0,
+            reverb_wet: [0.0; 4],
+            position_3d: zero,
+            velocity_3d: zero,
+            dsps: Vec::new(),
+            user_data: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Channel {
+    pub(crate) control: Control,
+    pub(crate) sound: usize,
+    pub(crate) group: usize,
+    pub(crate) index: i32,
+    pub(crate) position_ms: u32,
+    pub(crate) frequency: f32,
+    pub(crate) priority: i32,
+    pub(crate) loop_count: i32,
+}
+
+#[derive(Debug)]
+pub(crate) struct ChannelGroup {
+    pub(crate) control: Control,
+    pub(crate) name: CString,
+    pub(crate) parent: Option<usize>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Dsp {
+    pub(crate) system: usize,
+    pub(crate) kind: FMOD_DSP_TYPE,
+    pub(crate) bypass: bool,
+    pub(crate) active: bool,
+    pub(crate) parameters: HashMap<i32, Parameter>,
+    pub(crate) user_data: usize,
+}
+
+/// A connection from an input DSP to the DSP it feeds.
+#[derive(Debug)]
+pub(crate) struct Connection {
+    pub(crate) output: usize,
+    pub(crate) input: usize,
+    pub(crate) kind: FMOD_DSPCONNECTION_TYPE,
+    pub(crate) mix: f32,
+    /// The mix matrix, as rows of `in_channels` for each output channel, if one was set.
+    pub(crate) matrix: Option<MixMatrix>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MixMatrix {
+    pub(crate) out_channels: i32,
+    pub(crate) in_channels: i32,
+    pub(crate) levels: Vec<f32>,
+}
+
+/// A DSP parameter value. Each index keeps the type it was first set with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Parameter {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
+}
+
+/// Look up an object by handle, or fail with `FMOD_ERR_INVALID_HANDLE`.
+fn lookup<T, U>(table: &mut HashMap<usize, U>, handle: *mut T) -> Result<&mut U, FMOD_RESULT> {
+    table
+        .get_mut(&handle.addr())
+        .ok_or(FMOD_RESULT::FMOD_ERR_INVALID_HANDLE)
+}
+
+impl State {
+    pub(crate) fn insert_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub(crate) fn system(&mut self, system: *mut FMOD_SYSTEM) -> Result<&mut System, FMOD_RESULT> {
+        lookup(&mut self.systems, system)
+    }
+
+    /// The system, if it's been initialized.
+    pub(crate) fn initialized(
+        &mut self,
+        system: *mut FMOD_SYSTEM,
+    ) -> Result<&mut System, FMOD_RESULT> {
+        let system = self.system(system)?;
+        if system.initialized {
+            Ok(system)
+        } else {
+            Err(FMOD_RESULT::FMOD_ERR_UNINITIALIZED)
+        }
+    }
+
+    pub(crate) fn sound(&mut self, sound: *mut FMOD_SOUND) -> Result<&mut Sound, FMOD_RESULT> {
+        lookup(&mut self.sounds, sound)
+    }
+
+    pub(crate) fn channel(
+        &mut self,
+        channel: *mut FMOD_CHANNEL,
+    ) -> Result<&mut Channel, FMOD_RESULT> {
+        if self.stolen.contains(&channel.addr()) {
+            return Err(FMOD_RESULT::FMOD_ERR_CHANNEL_STOLEN);
+        }
+        lookup(&mut self.channels, channel)
+    }
+
+    pub(crate) fn group(
+        &mut self,
+        group: *mut FMOD_CHANNELGROUP,
+    ) -> Result<&mut ChannelGroup, FMOD_RESULT> {
+        lookup(&mut self.groups, group)
+    }
+
+    pub(crate) fn dsp(&mut self, dsp: *mut FMOD_DSP) -> Result<&mut Dsp, FMOD_RESULT> {
+        lookup(&mut self.dsps, dsp)
+    }
+
+    pub(crate) fn connection(
+        &mut self,
+        connection: *mut FMOD_DSPCONNECTION,
+    ) -> Result<&mut Connection, FMOD_RESULT> {
+        lookup(&mut self.connections, connection)
+    }
+
+    /// Disconnect a DSP from everything it's connected to.
+    pub(crate) fn disconnect(&mut self, dsp: usize) {
+        self.connections
+            .retain(|_, it| it.output != dsp && it.input != dsp);
+    }
+
+    /// Whether the group, or any group above it, is paused.
+    pub(crate) fn group_paused(&self, mut group: usize) -> bool {
+        while let Some(it) = self.groups.get(&group) {
+            if it.control.paused {
+                return true;
+            }
+            match it.parent {
+                Some(parent) => group = parent,
+                None => break,
+            }
+        }
+        false
+    }
+
+    /// Make a channel playing `sound` in `group`, stealing the oldest channel if all of the
+    /// system's channels are in use.
+    pub(crate) fn play(
+        &mut self,
+        system: usize,
+        sound: usize,
+        group: usize,
+        paused: bool,
+    ) -> Result<usize, FMOD_RESULT> {
+        let max_channels = self.systems[&system].max_channels;
+        let mut in_use = self
+            .channels
+            .iter()
+            .filter(|(_, it)| it.control.system == system)
+            .map(|(&id, it)| (id, it.index))
+            .collect::<Vec<_>>();
+        in_use.sort_unstable();
+        let index = if in_use.len() < usize::try_from(max_channels).unwrap_or(0) {
+            (0..max_channels)
+                .find(|index| in_use.iter().all(|(_, it)| it != index))
+                .ok_or(FMOD_RESULT::FMOD_ERR_CHANNEL_ALLOC)?
+        } else {
+            let &(oldest, index) = in_use.first().ok_or(FMOD_RESULT::FMOD_ERR_CHANNEL_ALLOC)?;
+            self.channels.remove(&oldest);
+            self.stolen.insert(oldest);
+            index
+        };
+
+        let played = &self.sounds[&sound];
+        let mut control = Control::new(system);
+        control.paused = paused;
+        control.mode = played.mode;
+        let channel = Channel {
+            control,
+            sound,
+            group,
+            index,
+            position_ms: 0,
+            frequency: played.default_frequency,
+            priority: played.default_priority,
+            loop_count: played.loop_count,
+        };
+        let id = self.insert_id();
+        self.channels.insert(id, channel);
+        Ok(id)
+    }
+
+    /// Stop the channels that match `filter`.
+    pub(crate) fn stop_channels(&mut self, filter: impl Fn(&Channel) -> bool) {
+        self.channels.retain(|_, it| !filter(it));
+    }
+
+    /// Release a system, and everything it made.
+    pub(crate) fn release_system(&mut self, system: usize) {
+        self.systems.remove(&system);
+        self.sounds.retain(|_, it| it.system != system);
+        self.channels.retain(|_, it| it.control.system != system);
+        self.groups.retain(|_, it| it.control.system != system);
+        self.dsps.retain(|_, it| it.system != system);
+        let dsps = &self.dsps;
+        self.connections
+            .retain(|_, it| dsps.contains_key(&it.output) && dsps.contains_key(&it.input));
+    }
+}
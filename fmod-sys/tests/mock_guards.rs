@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Tests mocking the same function through the guards in `mock_guards`.
+
+#![cfg(feature = "mockall")]
+
+use std::{
+    ffi::c_uint,
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use fmod_sys::{functions, mock_guards, FMOD_RESULT, FMOD_SYSTEM, FMOD_VERSION};
+
+/// How many threads hold the guard of `FMOD_System_GetVersion`.
+static HOLDERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Expect `FMOD_System_GetVersion` to give `version`, and check it does, a few times over.
+fn get_version(version: c_uint) {
+    for _ in 0..20 {
+        let mock = mock_guards::FMOD_System_GetVersion();
+        assert_eq!(HOLDERS.fetch_add(1, Ordering::SeqCst), 0);
+        mock.expect().returning(move |_, out| {
+            unsafe { out.write(version) };
+            FMOD_RESULT::FMOD_OK
+        });
+        // Give any other thread the chance to set its own expectation in the meantime.
+        thread::sleep(Duration::from_millis(1));
+        let mut got = 0;
+        let result = unsafe { functions::FMOD_System_GetVersion(ptr::null_mut(), &raw mut got) };
+        assert_eq!(result, FMOD_RESULT::FMOD_OK);
+        assert_eq!(got, version);
+        HOLDERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn guards_serialise() {
+    thread::scope(|scope| {
+        for version in 1..=4 {
+            scope.spawn(move || get_version(version));
+        }
+    });
+}
+
+#[test]
+fn guards_serialise_tests() {
+    // Alongside `guards_serialise`, when the tests run in parallel.
+    get_version(5);
+}
+
+#[test]
+fn default_ok() {
+    let _mock = mock_guards::FMOD_System_Create().default_ok();
+    let mut system: *mut FMOD_SYSTEM = ptr::null_mut();
+    let result =
+        unsafe { functions::FMOD_System_Create(&raw mut system, FMOD_VERSION.cast_unsigned()) };
+    assert_eq!(result, FMOD_RESULT::FMOD_OK);
+    assert!(!system.is_null());
+    // Null out-parameters are left alone.
+    let result =
+        unsafe { functions::FMOD_System_Create(ptr::null_mut(), FMOD_VERSION.cast_unsigned()) };
+    assert_eq!(result, FMOD_RESULT::FMOD_OK);
+}
@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Recording calls to the mocked functions, and replaying them against a mock of the API.
+
+#![cfg(all(feature = "record-calls", feature = "mockall"))]
+
+use std::{
+    ffi::{c_char, c_uint},
+    io::{self, Write},
+    ptr,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use fmod_sys::{
+    functions, mock_guards, record, MockFmodApi, FMOD_CREATESOUNDEXINFO, FMOD_DEFAULT, FMOD_MODE,
+    FMOD_RESULT, FMOD_SOUND, FMOD_SYSTEM, FMOD_TIMEUNIT, FMOD_TIMEUNIT_MS, FMOD_VERSION,
+};
+
+const SYSTEM: usize = 0x1000;
+const SOUND: usize = 0x2000;
+/// The length set in the `FMOD_CREATESOUNDEXINFO` a sound is created with.
+const EXINFO_LENGTH: c_uint = 0x1234_5678;
+
+/// A log that can be read once the recorder is done with it.
+#[derive(Clone, Default)]
+struct Log(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Log {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// What FMOD does, as far as these tests go: it gives out `SYSTEM` and `SOUND`, only
+// creates sounds with `EXINFO_LENGTH` set, and only takes the handles it gave out.
+
+fn create_system(system: *mut *mut FMOD_SYSTEM, _: c_uint) -> FMOD_RESULT {
+    unsafe { system.write(ptr::without_provenance_mut(SYSTEM)) };
+    FMOD_RESULT::FMOD_OK
+}
+
+fn create_sound(
+    system: *mut FMOD_SYSTEM,
+    _: *const c_char,
+    _: FMOD_MODE,
+    exinfo: *mut FMOD_CREATESOUNDEXINFO,
+    sound: *mut *mut FMOD_SOUND,
+) -> FMOD_RESULT {
+    unsafe {
+        if system.addr() != SYSTEM {
+            FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+        } else if exinfo.is_null() || (*exinfo).length != EXINFO_LENGTH {
+            FMOD_RESULT::FMOD_ERR_FILE_BAD
+        } else {
+            sound.write(ptr::without_provenance_mut(SOUND));
+            FMOD_RESULT::FMOD_OK
+        }
+    }
+}
+
+fn sound_length(sound: *mut FMOD_SOUND, length: *mut c_uint, _: FMOD_TIMEUNIT) -> FMOD_RESULT {
+    if sound.addr() != SOUND || length.is_null() {
+        return FMOD_RESULT::FMOD_ERR_INVALID_HANDLE;
+    }
+    unsafe { length.write(1000) };
+    FMOD_RESULT::FMOD_OK
+}
+
+fn release_sound(sound: *mut FMOD_SOUND) -> FMOD_RESULT {
+    if sound.addr() == SOUND {
+        FMOD_RESULT::FMOD_OK
+    } else {
+        FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+    }
+}
+
+fn release_system(system: *mut FMOD_SYSTEM) -> FMOD_RESULT {
+    if system.addr() == SYSTEM {
+        FMOD_RESULT::FMOD_OK
+    } else {
+        FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+    }
+}
+
+/// Record creating a system, creating a sound with it, getting the sound's length, and
+/// releasing both, with the mocked functions standing in for FMOD.
+fn record() -> Vec<u8> {
+    // There's only one recorder.
+    static RECORDING: Mutex<()> = Mutex::new(());
+    let _recording = RECORDING.lock().unwrap_or_else(PoisonError::into_inner);
+    let create_system_mock = mock_guards::FMOD_System_Create();
+    create_system_mock.expect().returning(create_system);
+    let create_sound_mock = mock_guards::FMOD_System_CreateSound();
+    create_sound_mock.expect().returning(create_sound);
+    let sound_length_mock = mock_guards::FMOD_Sound_GetLength();
+    sound_length_mock.expect().returning(sound_length);
+    let release_sound_mock = mock_guards::FMOD_Sound_Release();
+    release_sound_mock.expect().returning(release_sound);
+    let release_system_mock = mock_guards::FMOD_System_Release();
+    release_system_mock.expect().returning(release_system);
+
+    let log = Log::default();
+    record::start(log.clone()).unwrap();
+    unsafe {
+        let mut system = ptr::null_mut();
+        let result = functions::FMOD_System_Create(&raw mut system, FMOD_VERSION.cast_unsigned());
+        assert_eq!(result, FMOD_RESULT::FMOD_OK);
+        let mut exinfo: FMOD_CREATESOUNDEXINFO = std::mem::zeroed();
+        exinfo.cbsize = size_of::<FMOD_CREATESOUNDEXINFO>().try_into().unwrap();
+        exinfo.length = EXINFO_LENGTH;
+        let mut sound = ptr::null_mut();
+        let result = functions::FMOD_System_CreateSound(
+            system,
+            c"first.ogg".as_ptr(),
+            FMOD_DEFAULT.cast_unsigned(),
+            &raw mut exinfo,
+            &raw mut sound,
+        );
+        assert_eq!(result, FMOD_RESULT::FMOD_OK);
+        let mut length = 0;
+        functions::FMOD_Sound_GetLength(sound, &raw mut length, FMOD_TIMEUNIT_MS.cast_unsigned());
+        assert_eq!(length, 1000);
+        functions::FMOD_Sound_Release(sound);
+        functions::FMOD_System_Release(system);
+    }
+    record::stop().unwrap();
+    let log = log.0.lock().unwrap_or_else(PoisonError::into_inner);
+    log.clone()
+}
+
+/// A mock of the API behaving as the mocked functions did when recording.
+fn api() -> MockFmodApi {
+    let mut api = MockFmodApi::new();
+    api.expect_FMOD_System_Create()
+        .times(1)
+        .returning(create_system);
+    api.expect_FMOD_System_CreateSound()
+        .times(1)
+        .returning(create_sound);
+    api.expect_FMOD_Sound_GetLength()
+        .times(1)
+        .returning(sound_length);
+    api.expect_FMOD_Sound_Release()
+        .times(1)
+        .returning(release_sound);
+    api.expect_FMOD_System_Release()
+        .times(1)
+        .returning(release_system);
+    api
+}
+
+#[test]
+fn round_trip() {
+    let log = record();
+    // The mock only takes the handles it gave out, so nothing diverges if the recorded
+    // ones were mapped to them.
+    let divergences = unsafe { record::replay(&log[..], &api()) }.unwrap();
+    assert_eq!(divergences, []);
+}
+
+#[test]
+fn changed_argument_diverges() {
+    let mut log = record();
+    let length = EXINFO_LENGTH.to_le_bytes();
+    let at = log
+        .windows(length.len())
+        .position(|it| it == length)
+        .expect("the exinfo should be recorded");
+    log[at..at + length.len()].copy_from_slice(&1_u32.to_le_bytes());
+
+    let divergences = unsafe { record::replay(&log[..], &api()) }.unwrap();
+    let divergence = &divergences[0];
+    assert_eq!(divergence.call, 1);
+    assert_eq!(divergence.function, "FMOD_System_CreateSound");
+    assert_eq!(divergence.recorded, format!("{:?}", FMOD_RESULT::FMOD_OK));
+    assert_eq!(
+        divergence.replayed,
+        format!("{:?}", FMOD_RESULT::FMOD_ERR_FILE_BAD)
+    );
+    // Without the sound, the calls on it fail too.
+    assert_eq!(divergences.len(), 3);
+}
+
+#[test]
+fn header_is_checked() {
+    let log = record();
+    let replay = |log: &[u8]| unsafe { record::replay(log, &MockFmodApi::new()) };
+
+    let mut not_a_log = log.clone();
+    not_a_log[0] ^= 0xff;
+    assert_eq!(
+        replay(&not_a_log).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    // The hash of the functions the log was recorded against follows the magic and format.
+    let mut other_functions = log.clone();
+    other_functions[10] ^= 0xff;
+    let err = replay(&other_functions).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("other functions"), "{err}");
+}
@@ -0,0 +1,126 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Logging calls to the mocked functions, with a subscriber capturing the events.
+
+#![cfg(all(feature = "trace-calls", feature = "mockall"))]
+
+use std::{
+    collections::HashMap,
+    fmt, ptr,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use fmod_sys::{functions, mock_guards, trace, trace::Level, FMOD_RESULT, FMOD_SYSTEM};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+const SYSTEM: usize = 0x1000;
+
+/// An event that was logged, with its fields formatted.
+#[derive(Debug)]
+struct Logged {
+    target: String,
+    level: Level,
+    fields: HashMap<&'static str, String>,
+}
+
+/// A subscriber keeping every event it's given.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<Logged>>>);
+
+impl Capture {
+    fn take(&self) -> Vec<Logged> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl Visit for Logged {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut logged = Logged {
+            target: event.metadata().target().to_owned(),
+            level: *event.metadata().level(),
+            fields: HashMap::new(),
+        };
+        event.record(&mut logged);
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(logged);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Release `SYSTEM` with the mock refusing it, and return what was logged.
+fn release(capture: &Capture) -> Vec<Logged> {
+    tracing::subscriber::with_default(capture.clone(), || {
+        let result = unsafe { functions::FMOD_System_Release(ptr::without_provenance_mut(SYSTEM)) };
+        assert_eq!(result, FMOD_RESULT::FMOD_ERR_INVALID_HANDLE);
+    });
+    capture.take()
+}
+
+#[test]
+fn calls_are_logged() {
+    let mock = mock_guards::FMOD_System_Release();
+    mock.expect()
+        .returning(|_: *mut FMOD_SYSTEM| FMOD_RESULT::FMOD_ERR_INVALID_HANDLE);
+    let capture = Capture::default();
+
+    let logged = release(&capture);
+    let [event] = &logged[..] else {
+        panic!("expected one event, got {logged:?}");
+    };
+    assert_eq!(event.target, "fmod_calls");
+    assert_eq!(event.level, Level::DEBUG);
+    assert_eq!(event.fields["function"], "FMOD_System_Release");
+    assert_eq!(event.fields["args"], format!("system={SYSTEM:#x}"));
+    assert_eq!(
+        event.fields["result"],
+        format!("{:?}", FMOD_RESULT::FMOD_ERR_INVALID_HANDLE)
+    );
+    assert!(event.fields.contains_key("elapsed"));
+
+    assert!(trace::set_level("System", Some(Level::WARN)));
+    assert_eq!(release(&capture)[0].level, Level::WARN);
+    // Other families are left alone.
+    assert!(trace::set_level("Sound", None));
+    assert_eq!(release(&capture).len(), 1);
+    assert!(trace::set_level("System", None));
+    assert_eq!(release(&capture).len(), 0);
+    assert!(!trace::set_level("NotAFamily", None));
+
+    trace::set_all_levels(Some(Level::TRACE));
+    assert_eq!(release(&capture)[0].level, Level::TRACE);
+    trace::set_all_levels(None);
+    assert_eq!(release(&capture).len(), 0);
+    trace::set_all_levels(Some(Level::DEBUG));
+}
@@ -0,0 +1,808 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Channels, which play sounds, and the groups they're mixed in.
+//!
+//! A channel's handle stops being valid when its sound finishes, or its voice is stolen to
+//! play another sound, and FMOD then fails calls on it with `FMOD_ERR_INVALID_HANDLE` or
+//! `FMOD_ERR_CHANNEL_STOLEN`. Both are returned as [`Error::Stale`], with what FMOD
+//! returned, and a channel that's been found stale fails every later call the same way,
+//! without calling FMOD. Clones of a channel share what's been found. Groups are tracked
+//! the same way, for when their handles outlive the groups.
+
+use std::{
+    cell::Cell,
+    ffi::{c_char, c_int, c_uint, CStr, CString},
+    marker::PhantomData,
+    ptr::{null, null_mut, NonNull},
+    rc::Rc,
+};
+
+use fmod_sys::{
+    FMOD_BOOL, FMOD_CHANNEL, FMOD_CHANNELGROUP, FMOD_RESULT, FMOD_TIMEUNIT, FMOD_VECTOR,
+};
+
+use crate::{
+    error::{call, check_handle, Result},
+    Dsp, Error, Sound, System,
+};
+
+/// The handle behind a [`ChannelControl`].
+#[derive(Debug, Clone, Copy)]
+pub enum Control {
+    Channel(*mut FMOD_CHANNEL),
+    Group(*mut FMOD_CHANNELGROUP),
+    /// A channel that's already been found stale, with what FMOD returned then.
+    StaleChannel(FMOD_RESULT),
+    /// A group that's already been found stale, with what FMOD returned then.
+    StaleGroup(FMOD_RESULT),
+}
+
+mod private {
+    use fmod_sys::FMOD_RESULT;
+
+    use super::Control;
+    use crate::Result;
+
+    pub trait Sealed {
+        /// The handle to call FMOD with.
+        fn control(&self) -> Control;
+
+        /// Turn what `function` returned into a `Result`.
+        fn check(&self, function: &'static str, result: FMOD_RESULT) -> Result<()>;
+    }
+}
+
+/// Call the `FMOD_Channel_*` or `FMOD_ChannelGroup_*` function for what `$this` is.
+macro_rules! control {
+    ($this:expr, $channel:ident | $group:ident($($arg:expr),* $(,)?)) => {{
+        let this = $this;
+        match private::Sealed::control(this) {
+            Control::Channel(raw) => private::Sealed::check(
+                this,
+                stringify!($channel),
+                unsafe { crate::ffi::$channel(raw, $($arg),*) },
+            ),
+            Control::Group(raw) => private::Sealed::check(
+                this,
+                stringify!($group),
+                unsafe { crate::ffi::$group(raw, $($arg),*) },
+            ),
+            Control::StaleChannel(result) => Err(Error::Stale {
+                function: stringify!($channel),
+                result,
+            }),
+            Control::StaleGroup(result) => Err(Error::Stale {
+                function: stringify!($group),
+                result,
+            }),
+        }
+    }};
+}
+
+/// What channels and groups have in common, as FMOD's `ChannelControl`.
+pub trait ChannelControl: private::Sealed {
+    /// Whether anything is playing.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `IsPlaying` fails.
+    fn is_playing(&self) -> Result<bool> {
+        let mut playing: FMOD_BOOL = 0;
+        control!(
+            self,
+            FMOD_Channel_IsPlaying | FMOD_ChannelGroup_IsPlaying(&raw mut playing)
+        )?;
+        Ok(playing != 0)
+    }
+
+    /// Stop playing. A stopped channel's handle is stale from then on.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `Stop` fails.
+    fn stop(&self) -> Result<()> {
+        control!(self, FMOD_Channel_Stop | FMOD_ChannelGroup_Stop())
+    }
+
+    /// Whether playback is paused.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetPaused` fails.
+    fn paused(&self) -> Result<bool> {
+        let mut paused: FMOD_BOOL = 0;
+        control!(
+            self,
+            FMOD_Channel_GetPaused | FMOD_ChannelGroup_GetPaused(&raw mut paused)
+        )?;
+        Ok(paused != 0)
+    }
+
+    /// Pause or resume playback.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetPaused` fails.
+    fn set_paused(&self, paused: bool) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_SetPaused | FMOD_ChannelGroup_SetPaused(paused.into())
+        )
+    }
+
+    /// The volume, where 0 is silent and 1 is full volume.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetVolume` fails.
+    fn volume(&self) -> Result<f32> {
+        let mut volume = 0.0;
+        control!(
+            self,
+            FMOD_Channel_GetVolume | FMOD_ChannelGroup_GetVolume(&raw mut volume)
+        )?;
+        Ok(volume)
+    }
+
+    /// Set the volume, where 0 is silent and 1 is full volume. Higher volumes amplify.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetVolume` fails.
+    fn set_volume(&self, volume: f32) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_SetVolume | FMOD_ChannelGroup_SetVolume(volume)
+        )
+    }
+
+    /// The pitch, as a multiple of the normal pitch.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetPitch` fails.
+    fn pitch(&self) -> Result<f32> {
+        let mut pitch = 0.0;
+        control!(
+            self,
+            FMOD_Channel_GetPitch | FMOD_ChannelGroup_GetPitch(&raw mut pitch)
+        )?;
+        Ok(pitch)
+    }
+
+    /// Set the pitch, as a multiple of the normal pitch.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetPitch` fails.
+    fn set_pitch(&self, pitch: f32) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_SetPitch | FMOD_ChannelGroup_SetPitch(pitch)
+        )
+    }
+
+    /// Pan from -1, fully left, to 1, fully right.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetPan` fails.
+    fn set_pan(&self, pan: f32) -> Result<()> {
+        control!(self, FMOD_Channel_SetPan | FMOD_ChannelGroup_SetPan(pan))
+    }
+
+    /// Whether the output is muted.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetMute` fails.
+    fn mute(&self) -> Result<bool> {
+        let mut mute: FMOD_BOOL = 0;
+        control!(
+            self,
+            FMOD_Channel_GetMute | FMOD_ChannelGroup_GetMute(&raw mut mute)
+        )?;
+        Ok(mute != 0)
+    }
+
+    /// Mute or unmute the output, keeping the volume.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetMute` fails.
+    fn set_mute(&self, mute: bool) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_SetMute | FMOD_ChannelGroup_SetMute(mute.into())
+        )
+    }
+
+    /// The position and velocity in 3D space.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `Get3DAttributes` fails.
+    fn attributes_3d(&self) -> Result<(FMOD_VECTOR, FMOD_VECTOR)> {
+        let zero = FMOD_VECTOR {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let (mut position, mut velocity) = (zero, zero);
+        control!(
+            self,
+            FMOD_Channel_Get3DAttributes
+                | FMOD_ChannelGroup_Get3DAttributes(&raw mut position, &raw mut velocity)
+        )?;
+        Ok((position, velocity))
+    }
+
+    /// Set the position and velocity in 3D space, leaving either as it is if it's `None`.
+    /// This is for sounds loaded as 3D.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `Set3DAttributes` fails.
+    fn set_3d_attributes(
+        &self,
+        position: Option<&FMOD_VECTOR>,
+        velocity: Option<&FMOD_VECTOR>,
+    ) -> Result<()> {
+        let position = position.map_or(null(), std::ptr::from_ref);
+        let velocity = velocity.map_or(null(), std::ptr::from_ref);
+        control!(
+            self,
+            FMOD_Channel_Set3DAttributes | FMOD_ChannelGroup_Set3DAttributes(position, velocity)
+        )
+    }
+
+    /// The gain of the low-pass filter, where 1 leaves the sound as it is.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetLowPassGain` fails.
+    fn low_pass_gain(&self) -> Result<f32> {
+        let mut gain = 0.0;
+        control!(
+            self,
+            FMOD_Channel_GetLowPassGain | FMOD_ChannelGroup_GetLowPassGain(&raw mut gain)
+        )?;
+        Ok(gain)
+    }
+
+    /// Set the gain of the low-pass filter, from 0, fully filtered, to 1, unfiltered.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetLowPassGain` fails.
+    fn set_low_pass_gain(&self, gain: f32) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_SetLowPassGain | FMOD_ChannelGroup_SetLowPassGain(gain)
+        )
+    }
+
+    /// How much is sent to reverb instance `instance`, from 0 to 1.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetReverbProperties` fails.
+    fn reverb_wet(&self, instance: c_int) -> Result<f32> {
+        let mut wet = 0.0;
+        control!(
+            self,
+            FMOD_Channel_GetReverbProperties
+                | FMOD_ChannelGroup_GetReverbProperties(instance, &raw mut wet)
+        )?;
+        Ok(wet)
+    }
+
+    /// Set how much is sent to reverb instance `instance`, from 0 to 1.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `SetReverbProperties` fails.
+    fn set_reverb_wet(&self, instance: c_int, wet: f32) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_SetReverbProperties | FMOD_ChannelGroup_SetReverbProperties(instance, wet)
+        )
+    }
+
+    /// Add `dsp` to the DSP chain at `index`, which may be `FMOD_CHANNELCONTROL_DSP_HEAD` or
+    /// `FMOD_CHANNELCONTROL_DSP_TAIL`.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `AddDSP` fails, as when `dsp` is already in a chain.
+    fn add_dsp(&self, index: c_int, dsp: &Dsp<'_>) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_AddDSP | FMOD_ChannelGroup_AddDSP(index, dsp.as_raw())
+        )
+    }
+
+    /// Take `dsp` out of the DSP chain.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `RemoveDSP` fails, as when `dsp` isn't in the chain.
+    fn remove_dsp(&self, dsp: &Dsp<'_>) -> Result<()> {
+        control!(
+            self,
+            FMOD_Channel_RemoveDSP | FMOD_ChannelGroup_RemoveDSP(dsp.as_raw())
+        )
+    }
+
+    /// The number of DSP units in the DSP chain.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `GetNumDSPs` fails.
+    fn num_dsps(&self) -> Result<c_int> {
+        let mut count = 0;
+        control!(
+            self,
+            FMOD_Channel_GetNumDSPs | FMOD_ChannelGroup_GetNumDSPs(&raw mut count)
+        )?;
+        Ok(count)
+    }
+}
+
+/// A channel playing a sound. It isn't owned, as FMOD frees it when the sound finishes, and
+/// it can't outlive the [`System`] that played it.
+#[derive(Debug, Clone)]
+pub struct Channel<'s> {
+    raw: *mut FMOD_CHANNEL,
+    /// What FMOD returned when the handle was found stale, shared with every clone.
+    stale: Rc<Cell<Option<FMOD_RESULT>>>,
+    _system: PhantomData<&'s System>,
+}
+
+impl Channel<'_> {
+    /// The channel's handle, which may be stale.
+    #[must_use]
+    pub fn as_raw(&self) -> *mut FMOD_CHANNEL {
+        self.raw
+    }
+
+    /// Whether the channel's handle is still valid.
+    ///
+    /// A channel already known to be stale is answered for without calling FMOD. Otherwise
+    /// this asks FMOD through `FMOD_Channel_GetIndex`, which takes the system's lock, so
+    /// [`is_known_stale`](Self::is_known_stale) is cheaper where a channel that has only
+    /// just gone stale can be missed.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if self.is_known_stale() {
+            return false;
+        }
+        self.index().is_ok()
+    }
+
+    /// Whether a call has already found the channel's handle stale. This doesn't call
+    /// FMOD, so a channel that isn't known to be stale may still be.
+    #[must_use]
+    pub fn is_known_stale(&self) -> bool {
+        self.stale.get().is_some()
+    }
+
+    /// The channel's index among the system's channels.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_GetIndex` fails.
+    pub fn index(&self) -> Result<c_int> {
+        let mut index = 0;
+        self.call("FMOD_Channel_GetIndex", |raw| unsafe {
+            crate::ffi::FMOD_Channel_GetIndex(raw, &raw mut index)
+        })?;
+        Ok(index)
+    }
+
+    /// Whether the channel is virtual, with FMOD keeping track of it without mixing it.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_IsVirtual` fails.
+    pub fn is_virtual(&self) -> Result<bool> {
+        let mut is_virtual: FMOD_BOOL = 0;
+        self.call("FMOD_Channel_IsVirtual", |raw| unsafe {
+            crate::ffi::FMOD_Channel_IsVirtual(raw, &raw mut is_virtual)
+        })?;
+        Ok(is_virtual != 0)
+    }
+
+    /// The playback frequency, in Hz.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_GetFrequency` fails.
+    pub fn frequency(&self) -> Result<f32> {
+        let mut frequency = 0.0;
+        self.call("FMOD_Channel_GetFrequency", |raw| unsafe {
+            crate::ffi::FMOD_Channel_GetFrequency(raw, &raw mut frequency)
+        })?;
+        Ok(frequency)
+    }
+
+    /// Set the playback frequency, in Hz.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_SetFrequency` fails.
+    pub fn set_frequency(&self, frequency: f32) -> Result<()> {
+        self.call("FMOD_Channel_SetFrequency", |raw| unsafe {
+            crate::ffi::FMOD_Channel_SetFrequency(raw, frequency)
+        })
+    }
+
+    /// The playback position, in `unit`, such as `FMOD_TIMEUNIT_MS`.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_GetPosition` fails.
+    pub fn position(&self, unit: FMOD_TIMEUNIT) -> Result<c_uint> {
+        let mut position = 0;
+        self.call("FMOD_Channel_GetPosition", |raw| unsafe {
+            crate::ffi::FMOD_Channel_GetPosition(raw, &raw mut position, unit)
+        })?;
+        Ok(position)
+    }
+
+    /// Seek to `position`, in `unit`, such as `FMOD_TIMEUNIT_MS`.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_SetPosition` fails.
+    pub fn set_position(&self, position: c_uint, unit: FMOD_TIMEUNIT) -> Result<()> {
+        self.call("FMOD_Channel_SetPosition", |raw| unsafe {
+            crate::ffi::FMOD_Channel_SetPosition(raw, position, unit)
+        })
+    }
+
+    /// How many more times the channel loops, where -1 is forever.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_GetLoopCount` fails.
+    pub fn loop_count(&self) -> Result<c_int> {
+        let mut count = 0;
+        self.call("FMOD_Channel_GetLoopCount", |raw| unsafe {
+            crate::ffi::FMOD_Channel_GetLoopCount(raw, &raw mut count)
+        })?;
+        Ok(count)
+    }
+
+    /// Set how many more times the channel loops, where -1 is forever.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_Channel_SetLoopCount` fails.
+    pub fn set_loop_count(&self, count: c_int) -> Result<()> {
+        self.call("FMOD_Channel_SetLoopCount", |raw| unsafe {
+            crate::ffi::FMOD_Channel_SetLoopCount(raw, count)
+        })
+    }
+
+    /// Move the channel to `group`.
+    ///
+    /// # Errors
+    /// Fails if either handle is stale, or `FMOD_Channel_SetChannelGroup` fails.
+    pub fn set_channel_group(&self, group: &ChannelGroup<'_>) -> Result<()> {
+        self.call("FMOD_Channel_SetChannelGroup", |raw| unsafe {
+            crate::ffi::FMOD_Channel_SetChannelGroup(raw, group.as_raw())
+        })
+    }
+
+    /// Call `function` with the handle, unless it's known to be stale.
+    fn call(
+        &self,
+        function: &'static str,
+        call: impl FnOnce(*mut FMOD_CHANNEL) -> FMOD_RESULT,
+    ) -> Result<()> {
+        if let Some(result) = self.stale.get() {
+            return Err(Error::Stale { function, result });
+        }
+        private::Sealed::check(self, function, call(self.raw))
+    }
+}
+
+impl private::Sealed for Channel<'_> {
+    fn control(&self) -> Control {
+        match self.stale.get() {
+            Some(result) => Control::StaleChannel(result),
+            None => Control::Channel(self.raw),
+        }
+    }
+
+    fn check(&self, function: &'static str, result: FMOD_RESULT) -> Result<()> {
+        let checked = check_handle(function, result);
+        if let Err(Error::Stale { result, .. }) = checked {
+            self.stale.set(Some(result));
+        }
+        checked
+    }
+}
+
+impl ChannelControl for Channel<'_> {}
+
+/// A group that channels and other groups are mixed into. Groups made with
+/// [`System::create_channel_group`] are released when they're dropped, which moves what
+/// was in them to their parent. None can outlive the [`System`] they belong to.
+#[derive(Debug)]
+pub struct ChannelGroup<'s> {
+    raw: NonNull<FMOD_CHANNELGROUP>,
+    owned: bool,
+    /// What FMOD returned when the handle was found stale.
+    stale: Cell<Option<FMOD_RESULT>>,
+    _system: PhantomData<&'s System>,
+}
+
+impl ChannelGroup<'_> {
+    /// The group's handle, which may be stale.
+    #[must_use]
+    pub fn as_raw(&self) -> *mut FMOD_CHANNELGROUP {
+        self.raw.as_ptr()
+    }
+
+    /// Whether the group's handle is still valid.
+    ///
+    /// A group already known to be stale is answered for without calling FMOD. Otherwise
+    /// this asks FMOD through `FMOD_ChannelGroup_GetNumChannels`, which takes the system's
+    /// lock, so [`is_known_stale`](Self::is_known_stale) is cheaper where a group that has
+    /// only just gone stale can be missed.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if self.is_known_stale() {
+            return false;
+        }
+        self.num_channels().is_ok()
+    }
+
+    /// Whether a call has already found the group's handle stale. This doesn't call FMOD,
+    /// so a group that isn't known to be stale may still be.
+    #[must_use]
+    pub fn is_known_stale(&self) -> bool {
+        self.stale.get().is_some()
+    }
+
+    /// The group's name.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_ChannelGroup_GetName` fails.
+    pub fn name(&self) -> Result<String> {
+        let mut name = [0 as c_char; 256];
+        self.call("FMOD_ChannelGroup_GetName", |raw| unsafe {
+            crate::ffi::FMOD_ChannelGroup_GetName(
+                raw,
+                name.as_mut_ptr(),
+                name.len().try_into().unwrap_or(c_int::MAX),
+            )
+        })?;
+        Ok(unsafe { CStr::from_ptr(name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Mix `group` into this group, instead of its parent.
+    ///
+    /// # Errors
+    /// Fails if either handle is stale, or `FMOD_ChannelGroup_AddGroup` fails, as when
+    /// `group` contains this group.
+    pub fn add_group(&self, group: &ChannelGroup<'_>) -> Result<()> {
+        let function = "FMOD_ChannelGroup_AddGroup";
+        if let Some(result) = self.stale.get().or(group.stale.get()) {
+            return Err(Error::Stale { function, result });
+        }
+        // Either handle could be the stale one, so neither is marked.
+        check_handle(function, unsafe {
+            crate::ffi::FMOD_ChannelGroup_AddGroup(
+                self.as_raw(),
+                group.as_raw(),
+                FMOD_BOOL::from(true),
+                null_mut(),
+            )
+        })
+    }
+
+    /// The number of channels playing directly in this group.
+    ///
+    /// # Errors
+    /// Fails if the handle is stale, or `FMOD_ChannelGroup_GetNumChannels` fails.
+    pub fn num_channels(&self) -> Result<c_int> {
+        let mut count = 0;
+        self.call("FMOD_ChannelGroup_GetNumChannels", |raw| unsafe {
+            crate::ffi::FMOD_ChannelGroup_GetNumChannels(raw, &raw mut count)
+        })?;
+        Ok(count)
+    }
+
+    /// Call `function` with the handle, unless it's known to be stale.
+    fn call(
+        &self,
+        function: &'static str,
+        call: impl FnOnce(*mut FMOD_CHANNELGROUP) -> FMOD_RESULT,
+    ) -> Result<()> {
+        if let Some(result) = self.stale.get() {
+            return Err(Error::Stale { function, result });
+        }
+        private::Sealed::check(self, function, call(self.as_raw()))
+    }
+}
+
+impl private::Sealed for ChannelGroup<'_> {
+    fn control(&self) -> Control {
+        match self.stale.get() {
+            Some(result) => Control::StaleGroup(result),
+            None => Control::Group(self.as_raw()),
+        }
+    }
+
+    fn check(&self, function: &'static str, result: FMOD_RESULT) -> Result<()> {
+        let checked = check_handle(function, result);
+        if let Err(Error::Stale { result, .. }) = checked {
+            self.stale.set(Some(result));
+        }
+        checked
+    }
+}
+
+impl ChannelControl for ChannelGroup<'_> {}
+
+impl Drop for ChannelGroup<'_> {
+    fn drop(&mut self) {
+        if self.owned {
+            // There's nothing to do if this fails.
+            let _ = unsafe { call!(FMOD_ChannelGroup_Release(self.as_raw())) };
+        }
+    }
+}
+
+impl System {
+    /// Play `sound`, in `group`, or the master group if it's `None`, starting paused or
+    /// not.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_PlaySound` does, or `group` is stale.
+    pub fn play_sound(
+        &self,
+        sound: &Sound<'_>,
+        group: Option<&ChannelGroup<'_>>,
+        paused: bool,
+    ) -> Result<Channel<'_>> {
+        let group = group.map_or(null_mut(), ChannelGroup::as_raw);
+        let mut raw = null_mut();
+        unsafe {
+            call!(FMOD_System_PlaySound(
+                self.as_raw(),
+                sound.as_raw(),
+                group,
+                paused.into(),
+                &raw mut raw,
+            ))?;
+        }
+        Ok(Channel {
+            raw,
+            stale: Rc::default(),
+            _system: PhantomData,
+        })
+    }
+
+    /// The group everything is mixed into in the end, which is owned by the system.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_GetMasterChannelGroup` does.
+    ///
+    /// # Panics
+    /// Panics if FMOD reports success without giving a handle to the group.
+    pub fn master_channel_group(&self) -> Result<ChannelGroup<'_>> {
+        let mut raw = null_mut();
+        unsafe {
+            call!(FMOD_System_GetMasterChannelGroup(
+                self.as_raw(),
+                &raw mut raw
+            ))?;
+        }
+        Ok(ChannelGroup {
+            raw: NonNull::new(raw).expect("FMOD should give the master group when it succeeds"),
+            owned: false,
+            stale: Cell::new(None),
+            _system: PhantomData,
+        })
+    }
+
+    /// Create a group, mixed into the master group, and released when it's dropped.
+    ///
+    /// # Errors
+    /// Fails if `name` has a nul byte in it, or `FMOD_System_CreateChannelGroup` fails.
+    ///
+    /// # Panics
+    /// Panics if FMOD reports creating the group without giving a handle to it.
+    pub fn create_channel_group(&self, name: &str) -> Result<ChannelGroup<'_>> {
+        let name = CString::new(name).map_err(|_| Error::InvalidName(name.to_owned()))?;
+        let mut raw = null_mut();
+        unsafe {
+            call!(FMOD_System_CreateChannelGroup(
+                self.as_raw(),
+                name.as_ptr(),
+                &raw mut raw
+            ))?;
+        }
+        Ok(ChannelGroup {
+            raw: NonNull::new(raw).expect("FMOD should create a group when it succeeds"),
+            owned: true,
+            stale: Cell::new(None),
+            _system: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fmod_sys::{FMOD_DSP_TYPE, FMOD_TIMEUNIT_MS};
+
+    use super::*;
+    use crate::SoundBuilder;
+
+    #[test]
+    fn channels() {
+        let system = System::builder().max_channels(1).build().unwrap();
+        let data = [0; 16];
+        let sound = SoundBuilder::default().open_memory(&system, &data).unwrap();
+        let group = system.create_channel_group("music").unwrap();
+        assert_eq!(group.name().unwrap(), "music");
+        let channel = system.play_sound(&sound, Some(&group), true).unwrap();
+        assert!(channel.is_valid());
+        assert!(!channel.is_known_stale());
+        let copy = channel.clone();
+        assert_eq!(group.num_channels().unwrap(), 1);
+
+        channel.set_volume(0.5).unwrap();
+        assert!((channel.volume().unwrap() - 0.5).abs() < f32::EPSILON);
+        group.set_paused(true).unwrap();
+        assert!(group.paused().unwrap());
+        channel.set_mute(true).unwrap();
+        assert!(channel.mute().unwrap());
+        let dsp = system
+            .create_dsp(FMOD_DSP_TYPE::FMOD_DSP_TYPE_ECHO)
+            .unwrap();
+        group.add_dsp(0, &dsp).unwrap();
+        assert_eq!(group.num_dsps().unwrap(), 1);
+        group.remove_dsp(&dsp).unwrap();
+
+        // The only voice is stolen for the next sound.
+        let next = system.play_sound(&sound, None, false).unwrap();
+        assert_eq!(
+            channel.position(FMOD_TIMEUNIT_MS.cast_unsigned()),
+            Err(Error::Stale {
+                function: "FMOD_Channel_GetPosition",
+                result: FMOD_RESULT::FMOD_ERR_CHANNEL_STOLEN,
+            })
+        );
+        assert!(channel.is_known_stale());
+        assert!(!channel.is_valid());
+        // Clones know what the channel found.
+        assert!(copy.is_known_stale());
+        assert_eq!(
+            channel.set_volume(1.0),
+            Err(Error::Stale {
+                function: "FMOD_Channel_SetVolume",
+                result: FMOD_RESULT::FMOD_ERR_CHANNEL_STOLEN,
+            })
+        );
+
+        next.stop().unwrap();
+        assert!(!next.is_known_stale());
+        assert!(!next.is_valid());
+        assert!(next.is_known_stale());
+        assert_eq!(
+            next.set_volume(1.0).unwrap_err().result(),
+            Some(FMOD_RESULT::FMOD_ERR_INVALID_HANDLE)
+        );
+        assert!(system.master_channel_group().unwrap().is_playing().is_ok());
+    }
+
+    #[test]
+    fn stale_groups() {
+        let system = System::builder().build().unwrap();
+        let group = system.create_channel_group("gone").unwrap();
+        assert!(group.is_valid());
+        let alias = ChannelGroup {
+            raw: group.raw,
+            owned: false,
+            stale: Cell::new(None),
+            _system: PhantomData,
+        };
+        drop(group);
+
+        assert!(!alias.is_known_stale());
+        assert!(!alias.is_valid());
+        assert!(alias.is_known_stale());
+        assert_eq!(
+            alias.set_volume(1.0),
+            Err(Error::Stale {
+                function: "FMOD_ChannelGroup_SetVolume",
+                result: FMOD_RESULT::FMOD_ERR_INVALID_HANDLE,
+            })
+        );
+        assert!(matches!(alias.name(), Err(Error::Stale { .. })));
+        let master = system.master_channel_group().unwrap();
+        assert!(matches!(master.add_group(&alias), Err(Error::Stale { .. })));
+        assert!(!master.is_known_stale());
+    }
+}
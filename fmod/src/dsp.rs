@@ -0,0 +1,297 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! DSP units, which process audio as it passes through channels and groups.
+
+use std::{
+    ffi::c_int,
+    marker::PhantomData,
+    ptr::{null_mut, NonNull},
+};
+
+use fmod_sys::{FMOD_BOOL, FMOD_DSP, FMOD_DSPCONNECTION, FMOD_DSPCONNECTION_TYPE, FMOD_DSP_TYPE};
+
+use crate::{
+    error::{call, Result},
+    Error, System,
+};
+
+/// A DSP unit, released when it's dropped. It can't outlive the [`System`] that created it.
+///
+/// FMOD won't release a unit that's still in a DSP chain, so remove it with
+/// [`ChannelControl::remove_dsp`](crate::ChannelControl::remove_dsp) before dropping it.
+#[derive(Debug)]
+pub struct Dsp<'s> {
+    raw: NonNull<FMOD_DSP>,
+    _system: PhantomData<&'s System>,
+}
+
+impl System {
+    /// Create one of FMOD's built-in DSP units.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_CreateDSPByType` does.
+    ///
+    /// # Panics
+    /// Panics if FMOD reports creating the unit without giving a handle to it.
+    pub fn create_dsp(&self, ty: FMOD_DSP_TYPE) -> Result<Dsp<'_>> {
+        let mut raw = null_mut();
+        unsafe { call!(FMOD_System_CreateDSPByType(self.as_raw(), ty, &raw mut raw))? };
+        Ok(Dsp {
+            raw: NonNull::new(raw).expect("FMOD should create a DSP when it succeeds"),
+            _system: PhantomData,
+        })
+    }
+}
+
+impl Dsp<'_> {
+    /// The unit's handle, which stays owned by it.
+    #[must_use]
+    pub fn as_raw(&self) -> *mut FMOD_DSP {
+        self.raw.as_ptr()
+    }
+
+    /// Which of FMOD's DSP units this is.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_GetType` does.
+    pub fn dsp_type(&self) -> Result<FMOD_DSP_TYPE> {
+        let mut ty = FMOD_DSP_TYPE::FMOD_DSP_TYPE_UNKNOWN;
+        unsafe { call!(FMOD_DSP_GetType(self.as_raw(), &raw mut ty))? };
+        Ok(ty)
+    }
+
+    /// Whether the unit passes audio through without processing it.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_GetBypass` does.
+    pub fn bypass(&self) -> Result<bool> {
+        let mut bypass: FMOD_BOOL = 0;
+        unsafe { call!(FMOD_DSP_GetBypass(self.as_raw(), &raw mut bypass))? };
+        Ok(bypass != 0)
+    }
+
+    /// Pass audio through without processing it, or not.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_SetBypass` does.
+    pub fn set_bypass(&self, bypass: bool) -> Result<()> {
+        unsafe { call!(FMOD_DSP_SetBypass(self.as_raw(), bypass.into())) }
+    }
+
+    /// Whether the unit is processing, or silent.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_GetActive` does.
+    pub fn active(&self) -> Result<bool> {
+        let mut active: FMOD_BOOL = 0;
+        unsafe { call!(FMOD_DSP_GetActive(self.as_raw(), &raw mut active))? };
+        Ok(active != 0)
+    }
+
+    /// Process audio, or go silent.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_SetActive` does.
+    pub fn set_active(&self, active: bool) -> Result<()> {
+        unsafe { call!(FMOD_DSP_SetActive(self.as_raw(), active.into())) }
+    }
+
+    /// The float parameter at `index`. The typed effects, such as [`Echo`](crate::Echo),
+    /// have a method for each of their parameters instead.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_GetParameterFloat` does, as for a parameter of another type.
+    pub fn parameter_float(&self, index: c_int) -> Result<f32> {
+        let mut value = 0.0;
+        unsafe {
+            call!(FMOD_DSP_GetParameterFloat(
+                self.as_raw(),
+                index,
+                &raw mut value,
+                null_mut(),
+                0
+            ))?;
+        }
+        Ok(value)
+    }
+
+    /// Set the float parameter at `index`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_SetParameterFloat` does, as for a parameter of another type.
+    pub fn set_parameter_float(&self, index: c_int, value: f32) -> Result<()> {
+        unsafe { call!(FMOD_DSP_SetParameterFloat(self.as_raw(), index, value)) }
+    }
+
+    /// The int parameter at `index`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_GetParameterInt` does, as for a parameter of another type.
+    pub fn parameter_int(&self, index: c_int) -> Result<c_int> {
+        let mut value = 0;
+        unsafe {
+            call!(FMOD_DSP_GetParameterInt(
+                self.as_raw(),
+                index,
+                &raw mut value,
+                null_mut(),
+                0
+            ))?;
+        }
+        Ok(value)
+    }
+
+    /// Set the int parameter at `index`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_SetParameterInt` does, as for a parameter of another type.
+    pub fn set_parameter_int(&self, index: c_int, value: c_int) -> Result<()> {
+        unsafe { call!(FMOD_DSP_SetParameterInt(self.as_raw(), index, value)) }
+    }
+
+    /// The bool parameter at `index`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_GetParameterBool` does, as for a parameter of another type.
+    pub fn parameter_bool(&self, index: c_int) -> Result<bool> {
+        let mut value: FMOD_BOOL = 0;
+        unsafe {
+            call!(FMOD_DSP_GetParameterBool(
+                self.as_raw(),
+                index,
+                &raw mut value,
+                null_mut(),
+                0
+            ))?;
+        }
+        Ok(value != 0)
+    }
+
+    /// Set the bool parameter at `index`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_SetParameterBool` does, as for a parameter of another type.
+    pub fn set_parameter_bool(&self, index: c_int, value: bool) -> Result<()> {
+        unsafe {
+            call!(FMOD_DSP_SetParameterBool(
+                self.as_raw(),
+                index,
+                value.into()
+            ))
+        }
+    }
+
+    /// Feed `input` into this unit, returning the connection between them, which borrows
+    /// both.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSP_AddInput` does.
+    ///
+    /// # Panics
+    /// Panics if FMOD reports connecting the units without giving a handle to the
+    /// connection.
+    pub fn add_input<'a>(
+        &'a self,
+        input: &'a Dsp<'_>,
+        ty: FMOD_DSPCONNECTION_TYPE,
+    ) -> Result<DspConnection<'a>> {
+        let mut raw = null_mut();
+        unsafe {
+            call!(FMOD_DSP_AddInput(
+                self.as_raw(),
+                input.as_raw(),
+                &raw mut raw,
+                ty
+            ))?;
+        }
+        Ok(DspConnection {
+            raw: NonNull::new(raw).expect("FMOD should give a connection when it succeeds"),
+            _dsps: PhantomData,
+        })
+    }
+}
+
+impl Drop for Dsp<'_> {
+    fn drop(&mut self) {
+        // This fails for a unit still in a DSP chain, which is then left to the system.
+        let _ = unsafe { call!(FMOD_DSP_Release(self.as_raw())) };
+    }
+}
+
+/// A connection from one DSP unit into another, made with [`Dsp::add_input`]. It can't
+/// outlive either unit.
+#[derive(Debug)]
+pub struct DspConnection<'a> {
+    raw: NonNull<FMOD_DSPCONNECTION>,
+    _dsps: PhantomData<&'a Dsp<'a>>,
+}
+
+impl DspConnection<'_> {
+    /// The connection's handle, which is owned by FMOD.
+    #[must_use]
+    pub fn as_raw(&self) -> *mut FMOD_DSPCONNECTION {
+        self.raw.as_ptr()
+    }
+
+    /// What kind of connection this is.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSPConnection_GetType` does.
+    pub fn connection_type(&self) -> Result<FMOD_DSPCONNECTION_TYPE> {
+        let mut ty = FMOD_DSPCONNECTION_TYPE::FMOD_DSPCONNECTION_TYPE_STANDARD;
+        unsafe { call!(FMOD_DSPConnection_GetType(self.as_raw(), &raw mut ty))? };
+        Ok(ty)
+    }
+
+    /// The volume the input is mixed in at, where 1 is full volume.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSPConnection_GetMix` does.
+    pub fn mix(&self) -> Result<f32> {
+        let mut volume = 0.0;
+        unsafe { call!(FMOD_DSPConnection_GetMix(self.as_raw(), &raw mut volume))? };
+        Ok(volume)
+    }
+
+    /// Set the volume the input is mixed in at, where 1 is full volume.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSPConnection_SetMix` does.
+    pub fn set_mix(&self, volume: f32) -> Result<()> {
+        unsafe { call!(FMOD_DSPConnection_SetMix(self.as_raw(), volume)) }
+    }
+
+    /// Set the level each input channel is mixed into each output channel at. `matrix`
+    /// has a row of `in_channels` levels for each output channel.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_DSPConnection_SetMixMatrix` does, `in_channels` is 0, `matrix` isn't
+    /// made of whole rows, or the matrix has more channels than FMOD can take.
+    pub fn set_mix_matrix(&self, matrix: &[f32], in_channels: usize) -> Result<()> {
+        if in_channels == 0 {
+            return Err(Error::InvalidParameter {
+                parameter: "in_channels",
+            });
+        }
+        if !matrix.len().is_multiple_of(in_channels) {
+            return Err(Error::InvalidParameter {
+                parameter: "matrix",
+            });
+        }
+        let too_large = || Error::BufferTooLarge { len: matrix.len() };
+        let out_channels = c_int::try_from(matrix.len() / in_channels).map_err(|_| too_large())?;
+        let in_channels = c_int::try_from(in_channels).map_err(|_| too_large())?;
+        // FMOD only reads the matrix.
+        unsafe {
+            call!(FMOD_DSPConnection_SetMixMatrix(
+                self.as_raw(),
+                matrix.as_ptr().cast_mut(),
+                out_channels,
+                in_channels,
+                in_channels
+            ))
+        }
+    }
+}
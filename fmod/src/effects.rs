@@ -0,0 +1,699 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! FMOD's built-in effects, with a typed getter and setter for each of their parameters.
+//!
+//! Each effect is a [`Dsp`] it derefs to, so it can be added to channels and connected
+//! like any other unit. The parameters that hold data, rather than a number, are left to
+//! the raw API.
+
+use std::{ffi::c_int, fmt::Debug, ops::Deref};
+
+use fmod_sys::{
+    FMOD_DSP_CHANNELMIX, FMOD_DSP_CHANNELMIX_OUTPUT, FMOD_DSP_COMPRESSOR, FMOD_DSP_ECHO,
+    FMOD_DSP_FADER, FMOD_DSP_MULTIBAND_EQ, FMOD_DSP_MULTIBAND_EQ_FILTER_TYPE, FMOD_DSP_PAN,
+    FMOD_DSP_PAN_2D_STEREO_MODE_TYPE, FMOD_DSP_PAN_3D_EXTENT_MODE_TYPE,
+    FMOD_DSP_PAN_3D_ROLLOFF_TYPE, FMOD_DSP_PAN_MODE_TYPE, FMOD_DSP_PITCHSHIFT, FMOD_DSP_SFXREVERB,
+    FMOD_DSP_TYPE, FMOD_SPEAKER, FMOD_SPEAKERMODE,
+};
+
+use crate::{Dsp, Error, Result, System};
+
+/// The number of channels [`ChannelMix`] has a gain and output for.
+const CHANNELMIX_CHANNELS: usize = 32;
+
+/// A type a DSP parameter holds.
+pub(crate) trait Parameter: Sized {
+    fn get(dsp: &Dsp<'_>, index: c_int) -> Result<Self>;
+
+    fn set(dsp: &Dsp<'_>, index: c_int, value: Self) -> Result<()>;
+}
+
+impl Parameter for f32 {
+    fn get(dsp: &Dsp<'_>, index: c_int) -> Result<Self> {
+        dsp.parameter_float(index)
+    }
+
+    fn set(dsp: &Dsp<'_>, index: c_int, value: Self) -> Result<()> {
+        dsp.set_parameter_float(index, value)
+    }
+}
+
+impl Parameter for c_int {
+    fn get(dsp: &Dsp<'_>, index: c_int) -> Result<Self> {
+        dsp.parameter_int(index)
+    }
+
+    fn set(dsp: &Dsp<'_>, index: c_int, value: Self) -> Result<()> {
+        dsp.set_parameter_int(index, value)
+    }
+}
+
+impl Parameter for bool {
+    fn get(dsp: &Dsp<'_>, index: c_int) -> Result<Self> {
+        dsp.parameter_bool(index)
+    }
+
+    fn set(dsp: &Dsp<'_>, index: c_int, value: Self) -> Result<()> {
+        dsp.set_parameter_bool(index, value)
+    }
+}
+
+/// The index of a parameter, from bindgen's enum of an effect's parameters, whose inner
+/// type depends on the platform.
+fn index<T: TryInto<c_int>>(raw: T) -> c_int
+where
+    T::Error: Debug,
+{
+    raw.try_into()
+        .expect("FMOD's parameter indices should fit in an int")
+}
+
+/// Implement [`Parameter`] for enums FMOD keeps in int parameters.
+macro_rules! enum_parameters {
+    ($($ty:ident),* $(,)?) => {$(
+        impl Parameter for $ty {
+            fn get(dsp: &Dsp<'_>, index: c_int) -> Result<Self> {
+                let invalid = || Error::InvalidParameter {
+                    parameter: stringify!($ty),
+                };
+                let raw = dsp.parameter_int(index)?;
+                Ok(Self(raw.try_into().map_err(|_| invalid())?))
+            }
+
+            fn set(dsp: &Dsp<'_>, index: c_int, value: Self) -> Result<()> {
+                let invalid = || Error::InvalidParameter {
+                    parameter: stringify!($ty),
+                };
+                let raw = value.0.try_into().map_err(|_| invalid())?;
+                dsp.set_parameter_int(index, raw)
+            }
+        }
+    )*};
+}
+
+enum_parameters!(
+    FMOD_DSP_MULTIBAND_EQ_FILTER_TYPE,
+    FMOD_DSP_CHANNELMIX_OUTPUT,
+    FMOD_DSP_PAN_MODE_TYPE,
+    FMOD_DSP_PAN_2D_STEREO_MODE_TYPE,
+    FMOD_DSP_PAN_3D_ROLLOFF_TYPE,
+    FMOD_DSP_PAN_3D_EXTENT_MODE_TYPE,
+    FMOD_SPEAKERMODE,
+);
+
+/// A set of speakers, as the mask [`Pan::enabled_speakers`] holds, where each
+/// `FMOD_SPEAKER` is the bit at its index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpeakerMask(c_int);
+
+impl SpeakerMask {
+    /// No speakers.
+    pub const NONE: Self = Self(0);
+    /// Every speaker, which a panner starts with.
+    pub const ALL: Self = Self((1 << FMOD_SPEAKER::FMOD_SPEAKER_MAX.0) - 1);
+
+    /// The speakers in the mask `bits`.
+    #[must_use]
+    pub const fn from_bits(bits: c_int) -> Self {
+        Self(bits)
+    }
+
+    /// The mask, as FMOD takes it.
+    #[must_use]
+    pub const fn bits(self) -> c_int {
+        self.0
+    }
+
+    /// Whether `speaker` is in the set.
+    #[must_use]
+    pub fn contains(self, speaker: FMOD_SPEAKER) -> bool {
+        Self::bit(speaker).is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    /// The set with `speaker` in it.
+    ///
+    /// # Panics
+    /// Panics if `speaker` isn't one, as `FMOD_SPEAKER_NONE` isn't.
+    #[must_use]
+    pub fn with(self, speaker: FMOD_SPEAKER) -> Self {
+        Self(self.0 | Self::speaker_bit(speaker))
+    }
+
+    /// The set without `speaker` in it.
+    ///
+    /// # Panics
+    /// Panics if `speaker` isn't one, as `FMOD_SPEAKER_NONE` isn't.
+    #[must_use]
+    pub fn without(self, speaker: FMOD_SPEAKER) -> Self {
+        Self(self.0 & !Self::speaker_bit(speaker))
+    }
+
+    fn bit(speaker: FMOD_SPEAKER) -> Option<c_int> {
+        (0..FMOD_SPEAKER::FMOD_SPEAKER_MAX.0)
+            .contains(&speaker.0)
+            .then(|| 1 << speaker.0)
+    }
+
+    fn speaker_bit(speaker: FMOD_SPEAKER) -> c_int {
+        Self::bit(speaker).unwrap_or_else(|| panic!("{speaker:?} isn't a speaker"))
+    }
+}
+
+impl FromIterator<FMOD_SPEAKER> for SpeakerMask {
+    fn from_iter<I: IntoIterator<Item = FMOD_SPEAKER>>(speakers: I) -> Self {
+        speakers.into_iter().fold(Self::NONE, Self::with)
+    }
+}
+
+impl Parameter for SpeakerMask {
+    fn get(dsp: &Dsp<'_>, index: c_int) -> Result<Self> {
+        dsp.parameter_int(index).map(Self)
+    }
+
+    fn set(dsp: &Dsp<'_>, index: c_int, value: Self) -> Result<()> {
+        dsp.set_parameter_int(index, value.0)
+    }
+}
+
+/// Declare effects, each wrapping a [`Dsp`] of one type, with a getter and setter for each
+/// of the listed parameters.
+macro_rules! effects {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident = $ty:ident {$(
+            $(#[$param_meta:meta])*
+            $get:ident / $set:ident: $param:ty = $index:expr,
+        )*}
+    )*) => {$(
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub struct $name<'s>(Dsp<'s>);
+
+        impl<'s> $name<'s> {
+            #[doc = concat!("Create the effect, as `", stringify!($ty), "`.")]
+            ///
+            /// # Errors
+            /// Fails if `FMOD_System_CreateDSPByType` does.
+            pub fn new(system: &'s System) -> Result<Self> {
+                system.create_dsp(FMOD_DSP_TYPE::$ty).map(Self)
+            }
+
+            /// The unit behind the effect.
+            #[must_use]
+            pub fn into_dsp(self) -> Dsp<'s> {
+                self.0
+            }
+
+            $(
+                $(#[$param_meta])*
+                ///
+                /// # Errors
+                /// Fails if getting the parameter from FMOD does.
+                pub fn $get(&self) -> Result<$param> {
+                    Parameter::get(&self.0, index($index.0))
+                }
+
+                #[doc = concat!("Set [`", stringify!($get), "`](Self::", stringify!($get), ").")]
+                ///
+                /// # Errors
+                /// Fails if setting the parameter in FMOD does, as for a value out of its
+                /// range.
+                pub fn $set(&self, value: $param) -> Result<()> {
+                    Parameter::set(&self.0, index($index.0), value)
+                }
+            )*
+        }
+
+        impl<'s> Deref for $name<'s> {
+            type Target = Dsp<'s>;
+
+            fn deref(&self) -> &Dsp<'s> {
+                &self.0
+            }
+        }
+    )*};
+}
+
+effects! {
+    /// An echo, `FMOD_DSP_TYPE_ECHO`.
+    Echo = FMOD_DSP_TYPE_ECHO {
+        /// The delay between echoes, in milliseconds.
+        delay / set_delay: f32 = FMOD_DSP_ECHO::FMOD_DSP_ECHO_DELAY,
+        /// How much of each echo is fed back, as a percentage.
+        feedback / set_feedback: f32 = FMOD_DSP_ECHO::FMOD_DSP_ECHO_FEEDBACK,
+        /// The level of the original signal, in dB.
+        dry_level / set_dry_level: f32 = FMOD_DSP_ECHO::FMOD_DSP_ECHO_DRYLEVEL,
+        /// The level of the echoes, in dB.
+        wet_level / set_wet_level: f32 = FMOD_DSP_ECHO::FMOD_DSP_ECHO_WETLEVEL,
+    }
+
+    /// An I3DL2 reverb, `FMOD_DSP_TYPE_SFXREVERB`.
+    SfxReverb = FMOD_DSP_TYPE_SFXREVERB {
+        /// How long the reverb takes to decay at mid frequencies, in milliseconds.
+        decay_time / set_decay_time: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_DECAYTIME,
+        /// The delay to the first reflection, in milliseconds.
+        early_delay / set_early_delay: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_EARLYDELAY,
+        /// The delay from the first reflection to the late reverb, in milliseconds.
+        late_delay / set_late_delay: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_LATEDELAY,
+        /// The frequency high frequencies are measured from, in Hz.
+        hf_reference / set_hf_reference: f32 =
+            FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_HFREFERENCE,
+        /// How long high frequencies take to decay, as a percentage of the decay time.
+        hf_decay_ratio / set_hf_decay_ratio: f32 =
+            FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_HFDECAYRATIO,
+        /// The echo density of the late reverb, as a percentage.
+        diffusion / set_diffusion: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_DIFFUSION,
+        /// The modal density of the late reverb, as a percentage.
+        density / set_density: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_DENSITY,
+        /// The frequency the low shelf filter cuts at, in Hz.
+        low_shelf_frequency / set_low_shelf_frequency: f32 =
+            FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_LOWSHELFFREQUENCY,
+        /// The gain of the low shelf filter, in dB.
+        low_shelf_gain / set_low_shelf_gain: f32 =
+            FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_LOWSHELFGAIN,
+        /// The frequency the high cut filter cuts at, in Hz.
+        high_cut / set_high_cut: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_HIGHCUT,
+        /// The mix of early reflections to late reverb, as a percentage.
+        early_late_mix / set_early_late_mix: f32 =
+            FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_EARLYLATEMIX,
+        /// The level of the reverb, in dB.
+        wet_level / set_wet_level: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_WETLEVEL,
+        /// The level of the original signal, in dB.
+        dry_level / set_dry_level: f32 = FMOD_DSP_SFXREVERB::FMOD_DSP_SFXREVERB_DRYLEVEL,
+    }
+
+    /// A compressor, `FMOD_DSP_TYPE_COMPRESSOR`.
+    Compressor = FMOD_DSP_TYPE_COMPRESSOR {
+        /// The level compression starts at, in dB.
+        threshold / set_threshold: f32 = FMOD_DSP_COMPRESSOR::FMOD_DSP_COMPRESSOR_THRESHOLD,
+        /// The ratio of input to output above the threshold.
+        ratio / set_ratio: f32 = FMOD_DSP_COMPRESSOR::FMOD_DSP_COMPRESSOR_RATIO,
+        /// How long compression takes to start, in milliseconds.
+        attack / set_attack: f32 = FMOD_DSP_COMPRESSOR::FMOD_DSP_COMPRESSOR_ATTACK,
+        /// How long compression takes to stop, in milliseconds.
+        release / set_release: f32 = FMOD_DSP_COMPRESSOR::FMOD_DSP_COMPRESSOR_RELEASE,
+        /// The gain applied after compression, in dB.
+        gain_makeup / set_gain_makeup: f32 =
+            FMOD_DSP_COMPRESSOR::FMOD_DSP_COMPRESSOR_GAINMAKEUP,
+        /// Whether the channels are compressed together, rather than each on its own.
+        linked / set_linked: bool = FMOD_DSP_COMPRESSOR::FMOD_DSP_COMPRESSOR_LINKED,
+    }
+
+    /// A pitch shifter, `FMOD_DSP_TYPE_PITCHSHIFT`.
+    PitchShift = FMOD_DSP_TYPE_PITCHSHIFT {
+        /// The pitch, as a multiple of the original.
+        pitch / set_pitch: f32 = FMOD_DSP_PITCHSHIFT::FMOD_DSP_PITCHSHIFT_PITCH,
+        /// The size of the FFT window, in samples.
+        fft_size / set_fft_size: f32 = FMOD_DSP_PITCHSHIFT::FMOD_DSP_PITCHSHIFT_FFTSIZE,
+        /// The most channels the shifter takes, or 0 for the mixer's.
+        max_channels / set_max_channels: f32 =
+            FMOD_DSP_PITCHSHIFT::FMOD_DSP_PITCHSHIFT_MAXCHANNELS,
+    }
+
+    /// A channel mixer, `FMOD_DSP_TYPE_CHANNELMIX`. Its per-channel parameters are
+    /// [`gain`](Self::gain) and [`output_channel`](Self::output_channel).
+    ChannelMix = FMOD_DSP_TYPE_CHANNELMIX {
+        /// The speaker layout the channels are mixed to.
+        output_grouping / set_output_grouping: FMOD_DSP_CHANNELMIX_OUTPUT =
+            FMOD_DSP_CHANNELMIX::FMOD_DSP_CHANNELMIX_OUTPUTGROUPING,
+    }
+
+    /// A fader, `FMOD_DSP_TYPE_FADER`.
+    Fader = FMOD_DSP_TYPE_FADER {
+        /// The gain, in dB.
+        gain / set_gain: f32 = FMOD_DSP_FADER::FMOD_DSP_FADER_GAIN,
+    }
+
+    /// A panner, `FMOD_DSP_TYPE_PAN`.
+    Pan = FMOD_DSP_TYPE_PAN {
+        /// Whether the signal is panned in 2D or 3D.
+        mode / set_mode: FMOD_DSP_PAN_MODE_TYPE = FMOD_DSP_PAN::FMOD_DSP_PAN_MODE,
+        /// The position of a stereo signal, from -100 for left to 100 for right.
+        stereo_position / set_stereo_position: f32 =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_2D_STEREO_POSITION,
+        /// The direction of a surround signal, in degrees from the front.
+        direction / set_direction: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_2D_DIRECTION,
+        /// How far a surround signal is spread, in degrees.
+        extent / set_extent: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_2D_EXTENT,
+        /// The rotation of a surround signal, in degrees.
+        rotation / set_rotation: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_2D_ROTATION,
+        /// The level sent to the LFE channel in 2D, in dB.
+        lfe_level / set_lfe_level: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_2D_LFE_LEVEL,
+        /// How a stereo signal is panned in surround.
+        stereo_mode / set_stereo_mode: FMOD_DSP_PAN_2D_STEREO_MODE_TYPE =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_2D_STEREO_MODE,
+        /// How far apart the channels of a stereo signal are, in degrees.
+        stereo_separation / set_stereo_separation: f32 =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_2D_STEREO_SEPARATION,
+        /// The direction of the axis between a stereo signal's channels, in degrees.
+        stereo_axis / set_stereo_axis: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_2D_STEREO_AXIS,
+        /// The speakers that are panned to.
+        enabled_speakers / set_enabled_speakers: SpeakerMask =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_ENABLED_SPEAKERS,
+        /// How the signal rolls off with distance in 3D.
+        rolloff_3d / set_rolloff_3d: FMOD_DSP_PAN_3D_ROLLOFF_TYPE =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_3D_ROLLOFF,
+        /// The distance the signal starts rolling off at.
+        min_distance / set_min_distance: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_3D_MIN_DISTANCE,
+        /// The distance the signal stops rolling off at.
+        max_distance / set_max_distance: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_3D_MAX_DISTANCE,
+        /// How the signal's extent is found in 3D.
+        extent_mode / set_extent_mode: FMOD_DSP_PAN_3D_EXTENT_MODE_TYPE =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_3D_EXTENT_MODE,
+        /// The size of the source, for its extent in 3D.
+        sound_size / set_sound_size: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_3D_SOUND_SIZE,
+        /// The least the signal is spread in 3D, in degrees.
+        min_extent / set_min_extent: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_3D_MIN_EXTENT,
+        /// The blend from 2D to 3D panning, from 0 to 1.
+        pan_blend / set_pan_blend: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_3D_PAN_BLEND,
+        /// The speaker layout panned to in surround.
+        surround_speaker_mode / set_surround_speaker_mode: FMOD_SPEAKERMODE =
+            FMOD_DSP_PAN::FMOD_DSP_PAN_SURROUND_SPEAKER_MODE,
+        /// The blend of a 2D signal into the height speakers, from 0 to 1.
+        height_blend / set_height_blend: f32 = FMOD_DSP_PAN::FMOD_DSP_PAN_2D_HEIGHT_BLEND,
+    }
+
+    /// A 5-band equalizer, `FMOD_DSP_TYPE_MULTIBAND_EQ`. Its parameters are per band.
+    MultibandEq = FMOD_DSP_TYPE_MULTIBAND_EQ {}
+}
+
+impl Pan<'_> {
+    /// Whether a signal without an LFE channel is upmixed to it.
+    ///
+    /// # Errors
+    /// Fails if getting the parameter from FMOD does.
+    pub fn lfe_upmix_enabled(&self) -> Result<bool> {
+        // FMOD keeps it in an int parameter, as 0 or 1.
+        let param = FMOD_DSP_PAN::FMOD_DSP_PAN_LFE_UPMIX_ENABLED;
+        Ok(self.0.parameter_int(index(param.0))? != 0)
+    }
+
+    /// Set [`lfe_upmix_enabled`](Self::lfe_upmix_enabled).
+    ///
+    /// # Errors
+    /// Fails if setting the parameter in FMOD does.
+    pub fn set_lfe_upmix_enabled(&self, enabled: bool) -> Result<()> {
+        let param = FMOD_DSP_PAN::FMOD_DSP_PAN_LFE_UPMIX_ENABLED;
+        self.0.set_parameter_int(index(param.0), enabled.into())
+    }
+}
+
+/// A band of a [`MultibandEq`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EqBand {
+    A,
+    B,
+    C,
+    D,
+    E,
+}
+
+impl EqBand {
+    /// The index of the band's `param`, given as band A's.
+    fn index(self, param: FMOD_DSP_MULTIBAND_EQ) -> c_int {
+        // Each band has a filter, frequency, Q and gain, in that order.
+        let band = match self {
+            Self::A => 0,
+            Self::B => 1,
+            Self::C => 2,
+            Self::D => 3,
+            Self::E => 4,
+        };
+        index(param.0) + 4 * band
+    }
+}
+
+impl MultibandEq<'_> {
+    /// The filter of `band`, where `FMOD_DSP_MULTIBAND_EQ_FILTER_DISABLED` turns it off.
+    ///
+    /// # Errors
+    /// Fails if getting the parameter from FMOD does.
+    pub fn filter(&self, band: EqBand) -> Result<FMOD_DSP_MULTIBAND_EQ_FILTER_TYPE> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_FILTER;
+        Parameter::get(&self.0, band.index(param))
+    }
+
+    /// Set [`filter`](Self::filter).
+    ///
+    /// # Errors
+    /// Fails if setting the parameter in FMOD does.
+    pub fn set_filter(
+        &self,
+        band: EqBand,
+        filter: FMOD_DSP_MULTIBAND_EQ_FILTER_TYPE,
+    ) -> Result<()> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_FILTER;
+        Parameter::set(&self.0, band.index(param), filter)
+    }
+
+    /// The frequency `band` filters at, in Hz.
+    ///
+    /// # Errors
+    /// Fails if getting the parameter from FMOD does.
+    pub fn frequency(&self, band: EqBand) -> Result<f32> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_FREQUENCY;
+        Parameter::get(&self.0, band.index(param))
+    }
+
+    /// Set [`frequency`](Self::frequency).
+    ///
+    /// # Errors
+    /// Fails if setting the parameter in FMOD does, as for a value out of its range.
+    pub fn set_frequency(&self, band: EqBand, frequency: f32) -> Result<()> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_FREQUENCY;
+        Parameter::set(&self.0, band.index(param), frequency)
+    }
+
+    /// The quality factor of `band`, which is its bandwidth for some filters.
+    ///
+    /// # Errors
+    /// Fails if getting the parameter from FMOD does.
+    pub fn q(&self, band: EqBand) -> Result<f32> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_Q;
+        Parameter::get(&self.0, band.index(param))
+    }
+
+    /// Set [`q`](Self::q).
+    ///
+    /// # Errors
+    /// Fails if setting the parameter in FMOD does, as for a value out of its range.
+    pub fn set_q(&self, band: EqBand, q: f32) -> Result<()> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_Q;
+        Parameter::set(&self.0, band.index(param), q)
+    }
+
+    /// The gain of `band`, in dB, for the filters that have one.
+    ///
+    /// # Errors
+    /// Fails if getting the parameter from FMOD does.
+    pub fn gain(&self, band: EqBand) -> Result<f32> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_GAIN;
+        Parameter::get(&self.0, band.index(param))
+    }
+
+    /// Set [`gain`](Self::gain).
+    ///
+    /// # Errors
+    /// Fails if setting the parameter in FMOD does, as for a value out of its range.
+    pub fn set_gain(&self, band: EqBand, gain: f32) -> Result<()> {
+        let param = FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_A_GAIN;
+        Parameter::set(&self.0, band.index(param), gain)
+    }
+}
+
+/// The index of the parameter `channel` places after `first`, if the mixer has that
+/// channel.
+fn channel_index(first: FMOD_DSP_CHANNELMIX, channel: usize) -> Result<c_int> {
+    if channel < CHANNELMIX_CHANNELS {
+        Ok(index(first.0) + index(channel))
+    } else {
+        Err(Error::InvalidParameter {
+            parameter: "channel",
+        })
+    }
+}
+
+impl ChannelMix<'_> {
+    /// The gain of input `channel`, in dB.
+    ///
+    /// # Errors
+    /// Fails if `channel` isn't one of the mixer's 32, or getting the parameter from FMOD
+    /// does.
+    pub fn gain(&self, channel: usize) -> Result<f32> {
+        let first = FMOD_DSP_CHANNELMIX::FMOD_DSP_CHANNELMIX_GAIN_CH0;
+        Parameter::get(&self.0, channel_index(first, channel)?)
+    }
+
+    /// Set [`gain`](Self::gain).
+    ///
+    /// # Errors
+    /// Fails if `channel` isn't one of the mixer's 32, or setting the parameter in FMOD
+    /// does, as for a value out of its range.
+    pub fn set_gain(&self, channel: usize, gain: f32) -> Result<()> {
+        let first = FMOD_DSP_CHANNELMIX::FMOD_DSP_CHANNELMIX_GAIN_CH0;
+        Parameter::set(&self.0, channel_index(first, channel)?, gain)
+    }
+
+    /// The output channel input `channel` is mixed to.
+    ///
+    /// # Errors
+    /// Fails if `channel` isn't one of the mixer's 32, or getting the parameter from FMOD
+    /// does.
+    pub fn output_channel(&self, channel: usize) -> Result<c_int> {
+        let first = FMOD_DSP_CHANNELMIX::FMOD_DSP_CHANNELMIX_OUTPUT_CH0;
+        Parameter::get(&self.0, channel_index(first, channel)?)
+    }
+
+    /// Set [`output_channel`](Self::output_channel).
+    ///
+    /// # Errors
+    /// Fails if `channel` isn't one of the mixer's 32, or setting the parameter in FMOD
+    /// does, as for a value out of its range.
+    pub fn set_output_channel(&self, channel: usize, output: c_int) -> Result<()> {
+        let first = FMOD_DSP_CHANNELMIX::FMOD_DSP_CHANNELMIX_OUTPUT_CH0;
+        Parameter::set(&self.0, channel_index(first, channel)?, output)
+    }
+}
+
+#[cfg(test)]
+// Parameters are stored as they're set, so they compare exactly.
+#[allow(clippy::float_cmp)]
+mod tests {
+    use fmod_sys::{FMOD_DSPCONNECTION_TYPE, FMOD_OUTPUTTYPE};
+
+    use super::*;
+
+    fn system() -> System {
+        System::builder()
+            .output(FMOD_OUTPUTTYPE::FMOD_OUTPUTTYPE_NOSOUND)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn parameters() {
+        let system = system();
+        let echo = Echo::new(&system).unwrap();
+        assert_eq!(echo.dsp_type().unwrap(), FMOD_DSP_TYPE::FMOD_DSP_TYPE_ECHO);
+        echo.set_delay(250.0).unwrap();
+        assert_eq!(echo.delay().unwrap(), 250.0);
+        let raw = index(FMOD_DSP_ECHO::FMOD_DSP_ECHO_DELAY.0);
+        assert_eq!(echo.parameter_float(raw).unwrap(), 250.0);
+
+        let compressor = Compressor::new(&system).unwrap();
+        compressor.set_linked(true).unwrap();
+        assert!(compressor.linked().unwrap());
+
+        let pan = Pan::new(&system).unwrap();
+        let mode = FMOD_DSP_PAN_MODE_TYPE::FMOD_DSP_PAN_MODE_SURROUND;
+        pan.set_mode(mode).unwrap();
+        assert_eq!(pan.mode().unwrap(), mode);
+        pan.set_lfe_upmix_enabled(true).unwrap();
+        assert!(pan.lfe_upmix_enabled().unwrap());
+        let raw = index(FMOD_DSP_PAN::FMOD_DSP_PAN_LFE_UPMIX_ENABLED.0);
+        assert_eq!(pan.parameter_int(raw).unwrap(), 1);
+        let speakers = [
+            FMOD_SPEAKER::FMOD_SPEAKER_FRONT_LEFT,
+            FMOD_SPEAKER::FMOD_SPEAKER_FRONT_RIGHT,
+        ]
+        .into_iter()
+        .collect::<SpeakerMask>();
+        pan.set_enabled_speakers(speakers).unwrap();
+        assert_eq!(pan.enabled_speakers().unwrap().bits(), 0b11);
+        assert!(speakers.contains(FMOD_SPEAKER::FMOD_SPEAKER_FRONT_RIGHT));
+        assert!(!speakers.contains(FMOD_SPEAKER::FMOD_SPEAKER_NONE));
+        assert_eq!(SpeakerMask::ALL.bits(), 0xfff);
+        assert_eq!(
+            SpeakerMask::ALL
+                .without(FMOD_SPEAKER::FMOD_SPEAKER_LOW_FREQUENCY)
+                .bits(),
+            0xff7
+        );
+
+        let eq = MultibandEq::new(&system).unwrap();
+        eq.set_gain(EqBand::C, -6.0).unwrap();
+        assert_eq!(eq.gain(EqBand::C).unwrap(), -6.0);
+        assert_eq!(eq.gain(EqBand::A).unwrap(), 0.0);
+        let raw = index(FMOD_DSP_MULTIBAND_EQ::FMOD_DSP_MULTIBAND_EQ_C_GAIN.0);
+        assert_eq!(eq.parameter_float(raw).unwrap(), -6.0);
+
+        let mix = ChannelMix::new(&system).unwrap();
+        mix.set_gain(31, -3.0).unwrap();
+        assert_eq!(mix.gain(31).unwrap(), -3.0);
+        let raw = index(FMOD_DSP_CHANNELMIX::FMOD_DSP_CHANNELMIX_GAIN_CH31.0);
+        assert_eq!(mix.parameter_float(raw).unwrap(), -3.0);
+
+        // A parameter keeps its type.
+        let fader = Fader::new(&system).unwrap().into_dsp();
+        let raw = index(FMOD_DSP_FADER::FMOD_DSP_FADER_GAIN.0);
+        fader.set_parameter_float(raw, 1.0).unwrap();
+        assert!(fader.parameter_int(raw).is_err());
+    }
+
+    #[test]
+    fn connections() {
+        let system = system();
+        let fader = Fader::new(&system).unwrap();
+        let echo = Echo::new(&system).unwrap();
+        let connection = fader
+            .add_input(
+                &echo,
+                FMOD_DSPCONNECTION_TYPE::FMOD_DSPCONNECTION_TYPE_STANDARD,
+            )
+            .unwrap();
+        assert_eq!(
+            connection.connection_type().unwrap(),
+            FMOD_DSPCONNECTION_TYPE::FMOD_DSPCONNECTION_TYPE_STANDARD
+        );
+        assert_eq!(connection.mix().unwrap(), 1.0);
+        connection.set_mix(0.5).unwrap();
+        assert_eq!(connection.mix().unwrap(), 0.5);
+        connection.set_mix_matrix(&[1.0, 0.0, 0.0, 1.0], 2).unwrap();
+        assert!(connection.set_mix_matrix(&[], 1).is_err());
+        assert_eq!(
+            connection.set_mix_matrix(&[1.0], 0),
+            Err(Error::InvalidParameter {
+                parameter: "in_channels"
+            })
+        );
+        assert_eq!(
+            connection.set_mix_matrix(&[1.0, 0.0, 0.0], 2),
+            Err(Error::InvalidParameter {
+                parameter: "matrix"
+            })
+        );
+        assert!(fader
+            .add_input(
+                &fader,
+                FMOD_DSPCONNECTION_TYPE::FMOD_DSPCONNECTION_TYPE_STANDARD
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn channel_out_of_range() {
+        let system = system();
+        let mix = ChannelMix::new(&system).unwrap();
+        let invalid = Error::InvalidParameter {
+            parameter: "channel",
+        };
+        assert_eq!(mix.gain(32), Err(invalid.clone()));
+        assert_eq!(mix.set_output_channel(32, 0), Err(invalid));
+    }
+
+    // With MSVC, C enums are signed, so bindgen's can hold any int FMOD gives back.
+    #[test]
+    #[cfg(not(target_env = "msvc"))]
+    fn invalid_enum_values() {
+        let system = system();
+        let pan = Pan::new(&system).unwrap();
+        let invalid = Error::InvalidParameter {
+            parameter: "FMOD_DSP_PAN_MODE_TYPE",
+        };
+        let raw = index(FMOD_DSP_PAN::FMOD_DSP_PAN_MODE.0);
+        pan.set_parameter_int(raw, -1).unwrap();
+        assert_eq!(pan.mode(), Err(invalid));
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! The errors of the safe API.
+
+use std::{fmt, path::PathBuf};
+
+use fmod_sys::FMOD_RESULT;
+
+/// A failure of the safe API.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// An FMOD function returned something other than `FMOD_OK`.
+    Fmod {
+        /// The function that failed, such as `FMOD_System_Init`.
+        function: &'static str,
+        /// What it returned.
+        result: FMOD_RESULT,
+    },
+    /// A channel or group was used after its handle stopped being valid, as when a
+    /// channel's sound finished, or its voice was stolen for another.
+    Stale {
+        /// The function it was, or would have been, passed to.
+        function: &'static str,
+        /// What FMOD returned when the handle was found stale: `FMOD_ERR_CHANNEL_STOLEN` if
+        /// the voice was stolen, or `FMOD_ERR_INVALID_HANDLE` otherwise.
+        result: FMOD_RESULT,
+    },
+    /// A name FMOD can't take, because it has a nul byte in it.
+    InvalidName(String),
+    /// A path FMOD can't take, because it isn't Unicode or has a nul byte in it.
+    InvalidPath(PathBuf),
+    /// A value a parameter can't have: an enum value an int DSP parameter can't hold, or
+    /// FMOD gave back, a channel a mixer doesn't have, or a mix matrix without whole rows.
+    InvalidParameter {
+        /// The parameter: an argument, such as `in_channels`, or the enum a DSP parameter
+        /// holds, such as `FMOD_DSP_PAN_MODE_TYPE`.
+        parameter: &'static str,
+    },
+    /// A buffer longer than FMOD can address.
+    BufferTooLarge {
+        /// The length of the buffer, in bytes.
+        len: usize,
+    },
+}
+
+impl Error {
+    /// The `FMOD_RESULT` behind this error, if it came from FMOD. For
+    /// [`Stale`](Self::Stale) errors found without calling FMOD, it's what FMOD returned
+    /// when the handle was first found stale.
+    #[must_use]
+    pub fn result(&self) -> Option<FMOD_RESULT> {
+        match self {
+            Self::Fmod { result, .. } | Self::Stale { result, .. } => Some(*result),
+            Self::InvalidName(_)
+            | Self::InvalidPath(_)
+            | Self::InvalidParameter { .. }
+            | Self::BufferTooLarge { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fmod { function, result } => write!(f, "`{function}` failed with {result:?}"),
+            Self::Stale { function, result } => {
+                write!(f, "the handle passed to `{function}` is stale ({result:?})")
+            }
+            Self::InvalidName(name) => write!(f, "FMOD can't take the name `{name:?}`"),
+            Self::InvalidPath(path) => write!(f, "FMOD can't open `{}`", path.display()),
+            Self::InvalidParameter { parameter } => write!(f, "invalid value for `{parameter}`"),
+            Self::BufferTooLarge { len } => {
+                write!(f, "a buffer of {len} bytes is too large for FMOD")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A `Result` of the safe API.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Turn what `function` returned into a `Result`.
+pub(crate) fn check(function: &'static str, result: FMOD_RESULT) -> Result<()> {
+    if result == FMOD_RESULT::FMOD_OK {
+        Ok(())
+    } else {
+        Err(Error::Fmod { function, result })
+    }
+}
+
+/// Turn what `function` returned for a channel or group into a `Result`, where an invalid
+/// or stolen handle is [`Error::Stale`].
+pub(crate) fn check_handle(function: &'static str, result: FMOD_RESULT) -> Result<()> {
+    match result {
+        FMOD_RESULT::FMOD_ERR_INVALID_HANDLE | FMOD_RESULT::FMOD_ERR_CHANNEL_STOLEN => {
+            Err(Error::Stale { function, result })
+        }
+        result => check(function, result),
+    }
+}
+
+/// Call an FMOD function through `ffi`, turning what it returns into a `Result` that names
+/// it.
+macro_rules! call {
+    ($function:ident($($arg:expr),* $(,)?)) => {
+        $crate::error::check(
+            stringify!($function),
+            $crate::ffi::$function($($arg),*),
+        )
+    };
+}
+
+pub(crate) use call;
@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Bindings for the FMOD Engine SDK, in one crate.
+//!
+//! The Core API is always available as [`fmod_sys`]. The Studio and `FSBank` APIs are
+//! available as `fmod_studio_sys` and `fsbank_sys` with the `studio` and `fsbank`
+//! features. The `mockall`, `trace-calls`, `record-calls`, `link-static`, `pkg-config` and
+//! `docs-only` features are passed on to whichever of the sys crates are enabled.
+//!
+//! On top of them, this crate has a safe API over the Core API, starting from a
+//! [`System`], which is released when it's dropped, and the [`Sound`]s loaded with it.
+//! Failures are returned as an [`Error`].
+
+mod channel;
+mod dsp;
+mod effects;
+mod error;
+mod sound;
+mod system;
+
+pub use channel::{Channel, ChannelControl, ChannelGroup};
+pub use dsp::{Dsp, DspConnection};
+pub use effects::{
+    ChannelMix, Compressor, Echo, EqBand, Fader, MultibandEq, Pan, PitchShift, SfxReverb,
+    SpeakerMask,
+};
+pub use error::{Error, Result};
+pub use fmod_sys;
+pub use sound::{LoopMode, RawFormat, Rolloff, Sound, SoundBuilder};
+pub use system::{DspBufferSize, SoftwareFormat, System, SystemBuilder};
+
+// The safe API is tested against the fake, which behaves like FMOD without it.
+#[cfg(not(test))]
+use fmod_sys::functions as ffi;
+#[cfg(test)]
+use fmod_sys_fake::functions as ffi;
+
+#[cfg(feature = "studio")]
+pub use fmod_studio_sys;
+
+#[cfg(feature = "fsbank")]
+pub use fsbank_sys;
@@ -0,0 +1,415 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! Sounds, loaded from files or memory, whole or streamed.
+
+use std::{
+    ffi::{c_char, c_int, c_uint, CStr, CString},
+    marker::PhantomData,
+    path::Path,
+    ptr::{null_mut, NonNull},
+};
+
+use fmod_sys::{
+    FMOD_2D, FMOD_3D, FMOD_3D_CUSTOMROLLOFF, FMOD_3D_HEADRELATIVE, FMOD_3D_INVERSEROLLOFF,
+    FMOD_3D_INVERSETAPEREDROLLOFF, FMOD_3D_LINEARROLLOFF, FMOD_3D_LINEARSQUAREROLLOFF,
+    FMOD_CREATESOUNDEXINFO, FMOD_CREATESTREAM, FMOD_LOOP_BIDI, FMOD_LOOP_NORMAL, FMOD_LOOP_OFF,
+    FMOD_MODE, FMOD_OPENMEMORY, FMOD_OPENMEMORY_POINT, FMOD_OPENRAW, FMOD_SOUND, FMOD_SOUND_FORMAT,
+    FMOD_TIMEUNIT,
+};
+
+use crate::{
+    error::{call, Result},
+    Error, System,
+};
+
+// FMOD checks `cbsize` against the size of the struct it was built with.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+const EXINFO_SIZE: c_int = size_of::<FMOD_CREATESOUNDEXINFO>() as c_int;
+
+/// How a sound loops when it's played.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LoopMode {
+    /// Play once, as `FMOD_LOOP_OFF`.
+    #[default]
+    Off,
+    /// Loop from the end back to the start, as `FMOD_LOOP_NORMAL`.
+    Normal,
+    /// Loop back and forth, as `FMOD_LOOP_BIDI`.
+    Bidi,
+}
+
+/// How a 3D sound gets quieter with distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rolloff {
+    /// As `FMOD_3D_INVERSEROLLOFF`, FMOD's default, which is how sound behaves in reality.
+    Inverse,
+    /// As `FMOD_3D_LINEARROLLOFF`.
+    Linear,
+    /// As `FMOD_3D_LINEARSQUAREROLLOFF`.
+    LinearSquare,
+    /// As `FMOD_3D_INVERSETAPEREDROLLOFF`, inverse up to the max distance, then linear
+    /// square to silence.
+    InverseTapered,
+    /// As `FMOD_3D_CUSTOMROLLOFF`, following a curve set on the channel.
+    Custom,
+}
+
+/// The format of raw PCM data, which has no header for FMOD to read it from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawFormat {
+    /// The format of each sample.
+    pub format: FMOD_SOUND_FORMAT,
+    /// The number of interleaved channels.
+    pub channels: c_int,
+    /// The sample rate, in Hz.
+    pub frequency: c_int,
+}
+
+/// Settings for loading [`Sound`]s, which can load any number of them.
+///
+/// ``` no_run
+/// use fmod::{LoopMode, SoundBuilder, System};
+///
+/// let system = System::builder().build()?;
+/// let music = SoundBuilder::default()
+///     .stream(true)
+///     .loop_mode(LoopMode::Normal)
+///     .open_file(&system, "music.ogg")?;
+/// # Ok::<(), fmod::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct SoundBuilder {
+    stream: bool,
+    loop_mode: LoopMode,
+    three_d: bool,
+    head_relative: bool,
+    rolloff: Option<Rolloff>,
+    raw: Option<RawFormat>,
+}
+
+impl SoundBuilder {
+    /// Stream sounds as they play, with `FMOD_System_CreateStream`, instead of loading them
+    /// whole. A stream can only be played on one channel at a time.
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    /// Loop sounds this way. They play once by default.
+    pub fn loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
+    /// Position sounds in 3D, as `FMOD_3D`, instead of playing them in 2D.
+    pub fn three_d(mut self, three_d: bool) -> Self {
+        self.three_d = three_d;
+        self
+    }
+
+    /// Position 3D sounds relative to the listener, as `FMOD_3D_HEADRELATIVE`, instead of
+    /// in the world.
+    pub fn head_relative(mut self, head_relative: bool) -> Self {
+        self.head_relative = head_relative;
+        self
+    }
+
+    /// Roll 3D sounds off with distance this way.
+    pub fn rolloff(mut self, rolloff: Rolloff) -> Self {
+        self.rolloff = Some(rolloff);
+        self
+    }
+
+    /// Read sounds as raw PCM data in this format, as `FMOD_OPENRAW`.
+    pub fn raw(mut self, format: RawFormat) -> Self {
+        self.raw = Some(format);
+        self
+    }
+
+    /// Load a sound from a file, or a URL for a stream.
+    ///
+    /// # Errors
+    /// Fails if `path` isn't Unicode or has a nul byte in it, or if FMOD can't load it.
+    pub fn open_file<'s>(&self, system: &'s System, path: impl AsRef<Path>) -> Result<Sound<'s>> {
+        let path = path.as_ref();
+        let name = path
+            .to_str()
+            .and_then(|it| CString::new(it).ok())
+            .ok_or_else(|| Error::InvalidPath(path.to_owned()))?;
+        let mut exinfo = self.exinfo();
+        let exinfo = if self.raw.is_some() {
+            &raw mut exinfo
+        } else {
+            null_mut()
+        };
+        unsafe { self.create(system, name.as_ptr(), self.mode(), exinfo) }
+    }
+
+    /// Load a sound from a file's contents in memory, as `FMOD_OPENMEMORY`. FMOD copies
+    /// `data`, except for a stream, which it reads from `data` in place, so the sound
+    /// borrows `data` for as long as it lives either way.
+    ///
+    /// # Errors
+    /// Fails if `data` is longer than FMOD can address, or if FMOD can't load it.
+    pub fn open_memory<'s>(&self, system: &'s System, data: &'s [u8]) -> Result<Sound<'s>> {
+        unsafe { self.create_from_memory(system, data, FMOD_OPENMEMORY) }
+    }
+
+    /// Load a sound from a file's contents in memory, which FMOD reads in place, as
+    /// `FMOD_OPENMEMORY_POINT`. The sound borrows `data` for as long as it lives.
+    ///
+    /// # Errors
+    /// Fails if `data` is longer than FMOD can address, or if FMOD can't load it.
+    pub fn open_memory_point<'s>(&self, system: &'s System, data: &'s [u8]) -> Result<Sound<'s>> {
+        unsafe { self.create_from_memory(system, data, FMOD_OPENMEMORY_POINT) }
+    }
+
+    /// Load a sound from `data` with `memory_mode`, which `data` has to outlive the sound
+    /// for if it isn't copied.
+    unsafe fn create_from_memory<'s>(
+        &self,
+        system: &'s System,
+        data: &[u8],
+        memory_mode: i32,
+    ) -> Result<Sound<'s>> {
+        let mut exinfo = self.exinfo();
+        exinfo.length =
+            c_uint::try_from(data.len()).map_err(|_| Error::BufferTooLarge { len: data.len() })?;
+        let mode = self.mode() | memory_mode.cast_unsigned();
+        self.create(system, data.as_ptr().cast(), mode, &raw mut exinfo)
+    }
+
+    /// Create a sound with `FMOD_System_CreateSound`, or `FMOD_System_CreateStream` for a
+    /// stream.
+    unsafe fn create<'s>(
+        &self,
+        system: &'s System,
+        name_or_data: *const c_char,
+        mode: FMOD_MODE,
+        exinfo: *mut FMOD_CREATESOUNDEXINFO,
+    ) -> Result<Sound<'s>> {
+        let mut raw = null_mut();
+        let system = system.as_raw();
+        if self.stream {
+            call!(FMOD_System_CreateStream(
+                system,
+                name_or_data,
+                mode,
+                exinfo,
+                &raw mut raw
+            ))?;
+        } else {
+            call!(FMOD_System_CreateSound(
+                system,
+                name_or_data,
+                mode,
+                exinfo,
+                &raw mut raw
+            ))?;
+        }
+        Ok(Sound {
+            raw: NonNull::new(raw).expect("FMOD should create a sound when it succeeds"),
+            _borrow: PhantomData,
+        })
+    }
+
+    /// The mode flags for these settings, without where the sound is read from.
+    fn mode(&self) -> FMOD_MODE {
+        let loop_mode = match self.loop_mode {
+            LoopMode::Off => FMOD_LOOP_OFF,
+            LoopMode::Normal => FMOD_LOOP_NORMAL,
+            LoopMode::Bidi => FMOD_LOOP_BIDI,
+        };
+        let mut mode = loop_mode | if self.three_d { FMOD_3D } else { FMOD_2D };
+        if self.head_relative {
+            mode |= FMOD_3D_HEADRELATIVE;
+        }
+        mode |= match self.rolloff {
+            None => 0,
+            Some(Rolloff::Inverse) => FMOD_3D_INVERSEROLLOFF,
+            Some(Rolloff::Linear) => FMOD_3D_LINEARROLLOFF,
+            Some(Rolloff::LinearSquare) => FMOD_3D_LINEARSQUAREROLLOFF,
+            Some(Rolloff::InverseTapered) => FMOD_3D_INVERSETAPEREDROLLOFF,
+            Some(Rolloff::Custom) => FMOD_3D_CUSTOMROLLOFF,
+        };
+        if self.stream {
+            mode |= FMOD_CREATESTREAM;
+        }
+        if self.raw.is_some() {
+            mode |= FMOD_OPENRAW;
+        }
+        mode.cast_unsigned()
+    }
+
+    /// The extra info for these settings, with its size set, and the format of raw data.
+    fn exinfo(&self) -> FMOD_CREATESOUNDEXINFO {
+        // Zero is the default of every field, and a valid null pointer or `None`.
+        let mut exinfo = unsafe { std::mem::zeroed::<FMOD_CREATESOUNDEXINFO>() };
+        exinfo.cbsize = EXINFO_SIZE;
+        if let Some(raw) = self.raw {
+            exinfo.format = raw.format;
+            exinfo.numchannels = raw.channels;
+            exinfo.defaultfrequency = raw.frequency;
+        }
+        exinfo
+    }
+}
+
+/// A loaded sound, released when it's dropped. It can't outlive the [`System`] that loaded
+/// it, or memory it reads in place.
+#[derive(Debug)]
+pub struct Sound<'s> {
+    raw: NonNull<FMOD_SOUND>,
+    _borrow: PhantomData<&'s System>,
+}
+
+impl Sound<'_> {
+    /// The sound's handle, which stays owned by it.
+    #[must_use]
+    pub fn as_raw(&self) -> *mut FMOD_SOUND {
+        self.raw.as_ptr()
+    }
+
+    /// The sound's name, from its tags or file name.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_Sound_GetName` does.
+    pub fn name(&self) -> Result<String> {
+        let mut name = [0 as c_char; 256];
+        unsafe {
+            call!(FMOD_Sound_GetName(
+                self.as_raw(),
+                name.as_mut_ptr(),
+                name.len().try_into().unwrap_or(c_int::MAX),
+            ))?;
+            Ok(CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned())
+        }
+    }
+
+    /// The length of the sound, in `unit`, such as `FMOD_TIMEUNIT_MS`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_Sound_GetLength` does, such as for a unit the sound can't be measured
+    /// in.
+    pub fn length(&self, unit: FMOD_TIMEUNIT) -> Result<c_uint> {
+        let mut length = 0;
+        unsafe { call!(FMOD_Sound_GetLength(self.as_raw(), &raw mut length, unit))? };
+        Ok(length)
+    }
+
+    /// The sound's mode flags.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_Sound_GetMode` does.
+    pub fn mode(&self) -> Result<FMOD_MODE> {
+        let mut mode = 0;
+        unsafe { call!(FMOD_Sound_GetMode(self.as_raw(), &raw mut mode))? };
+        Ok(mode)
+    }
+
+    /// How many times the sound loops before stopping, where -1 is forever.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_Sound_GetLoopCount` does.
+    pub fn loop_count(&self) -> Result<c_int> {
+        let mut count = 0;
+        unsafe { call!(FMOD_Sound_GetLoopCount(self.as_raw(), &raw mut count))? };
+        Ok(count)
+    }
+
+    /// Set how many times the sound loops before stopping, where -1 is forever. This only
+    /// applies to channels played after it's set.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_Sound_SetLoopCount` does.
+    pub fn set_loop_count(&self, count: c_int) -> Result<()> {
+        unsafe { call!(FMOD_Sound_SetLoopCount(self.as_raw(), count)) }
+    }
+}
+
+impl Drop for Sound<'_> {
+    fn drop(&mut self) {
+        // Releasing stops the channels playing the sound. There's nothing to do if it fails.
+        let _ = unsafe { call!(FMOD_Sound_Release(self.as_raw())) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fmod_sys::{FMOD_RESULT, FMOD_SOUND_FORMAT, FMOD_TIMEUNIT_MS};
+
+    use super::*;
+
+    #[test]
+    fn loads() {
+        let system = System::builder().build().unwrap();
+        let data = vec![0; 64];
+        let builder = SoundBuilder::default()
+            .loop_mode(LoopMode::Bidi)
+            .three_d(true)
+            .rolloff(Rolloff::Linear);
+        let point = builder.open_memory_point(&system, &data).unwrap();
+        let mode = point.mode().unwrap().cast_signed();
+        assert_eq!(
+            mode,
+            FMOD_LOOP_BIDI | FMOD_3D | FMOD_3D_LINEARROLLOFF | FMOD_OPENMEMORY_POINT
+        );
+        assert!(point.length(FMOD_TIMEUNIT_MS.cast_unsigned()).unwrap() > 0);
+
+        let stream = SoundBuilder::default()
+            .stream(true)
+            .raw(RawFormat {
+                format: FMOD_SOUND_FORMAT::FMOD_SOUND_FORMAT_PCM16,
+                channels: 2,
+                frequency: 48_000,
+            })
+            .open_memory(&system, &data)
+            .unwrap();
+        let mode = stream.mode().unwrap().cast_signed();
+        assert_eq!(
+            mode,
+            FMOD_LOOP_OFF | FMOD_2D | FMOD_CREATESTREAM | FMOD_OPENRAW | FMOD_OPENMEMORY
+        );
+        stream.set_loop_count(3).unwrap();
+        assert_eq!(stream.loop_count().unwrap(), 3);
+
+        let raw = stream.as_raw();
+        drop(stream);
+        let mut count = 0;
+        assert_eq!(
+            unsafe { crate::ffi::FMOD_Sound_GetLoopCount(raw, &raw mut count) },
+            FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+        );
+    }
+
+    #[test]
+    fn failures() {
+        let system = System::builder().build().unwrap();
+        assert_eq!(
+            SoundBuilder::default()
+                .open_file(&system, "missing.ogg")
+                .unwrap_err(),
+            Error::Fmod {
+                function: "FMOD_System_CreateSound",
+                result: FMOD_RESULT::FMOD_ERR_FILE_NOTFOUND,
+            }
+        );
+        assert_eq!(
+            SoundBuilder::default()
+                .open_file(&system, "nul\0.ogg")
+                .unwrap_err(),
+            Error::InvalidPath("nul\0.ogg".into())
+        );
+        assert_eq!(
+            SoundBuilder::default()
+                .stream(true)
+                .open_memory(&system, &[])
+                .unwrap_err()
+                .result(),
+            Some(FMOD_RESULT::FMOD_ERR_INVALID_PARAM)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+// SPDX-FileCopyrightText: 2024 Julia DeMille <me@jdemille.com>
+//
+// SPDX-License-Identifier: MPL-2.0
+
+//! The Core API's system, which owns everything else.
+
+use std::{
+    ffi::{c_int, c_uint},
+    ptr::{null_mut, NonNull},
+};
+
+use fmod_sys::{
+    FMOD_INITFLAGS, FMOD_INIT_NORMAL, FMOD_OUTPUTTYPE, FMOD_SPEAKERMODE, FMOD_SYSTEM, FMOD_VERSION,
+};
+
+use crate::error::{call, Result};
+
+/// The format FMOD mixes in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoftwareFormat {
+    /// The sample rate of the mixer, in Hz.
+    pub sample_rate: c_int,
+    /// The speaker setup of the mixer.
+    pub speaker_mode: FMOD_SPEAKERMODE,
+    /// The number of speakers, for `FMOD_SPEAKERMODE_RAW`.
+    pub raw_speakers: c_int,
+}
+
+/// The size of the mixer's buffers, which trades latency for stability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DspBufferSize {
+    /// The length of each buffer, in samples.
+    pub length: c_uint,
+    /// The number of buffers.
+    pub count: c_int,
+}
+
+/// An initialized FMOD system, released when it's dropped.
+///
+/// ``` no_run
+/// use fmod::System;
+///
+/// let system = System::builder().max_channels(64).build()?;
+/// loop {
+///     system.update()?;
+///     # break;
+/// }
+/// # Ok::<(), fmod::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct System {
+    raw: NonNull<FMOD_SYSTEM>,
+}
+
+impl System {
+    /// A builder for a system, with FMOD's defaults and 32 channels.
+    pub fn builder() -> SystemBuilder {
+        SystemBuilder::default()
+    }
+
+    /// The system's handle, which stays owned by it.
+    #[must_use]
+    pub fn as_raw(&self) -> *mut FMOD_SYSTEM {
+        self.raw.as_ptr()
+    }
+
+    /// Update the system. Call this once per frame.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_Update` does.
+    pub fn update(&self) -> Result<()> {
+        unsafe { call!(FMOD_System_Update(self.as_raw())) }
+    }
+
+    /// The version of the FMOD library, as in `FMOD_VERSION`.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_GetVersion` does.
+    pub fn version(&self) -> Result<c_uint> {
+        let mut version = 0;
+        unsafe { call!(FMOD_System_GetVersion(self.as_raw(), &raw mut version))? };
+        Ok(version)
+    }
+
+    /// The output the system mixes to.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_GetOutput` does.
+    pub fn output(&self) -> Result<FMOD_OUTPUTTYPE> {
+        let mut output = FMOD_OUTPUTTYPE::FMOD_OUTPUTTYPE_AUTODETECT;
+        unsafe { call!(FMOD_System_GetOutput(self.as_raw(), &raw mut output))? };
+        Ok(output)
+    }
+
+    /// The format the system mixes in.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_GetSoftwareFormat` does.
+    pub fn software_format(&self) -> Result<SoftwareFormat> {
+        let mut format = SoftwareFormat {
+            sample_rate: 0,
+            speaker_mode: FMOD_SPEAKERMODE::FMOD_SPEAKERMODE_DEFAULT,
+            raw_speakers: 0,
+        };
+        unsafe {
+            call!(FMOD_System_GetSoftwareFormat(
+                self.as_raw(),
+                &raw mut format.sample_rate,
+                &raw mut format.speaker_mode,
+                &raw mut format.raw_speakers,
+            ))?;
+        }
+        Ok(format)
+    }
+
+    /// The size of the mixer's buffers.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_GetDSPBufferSize` does.
+    pub fn dsp_buffer_size(&self) -> Result<DspBufferSize> {
+        let mut size = DspBufferSize {
+            length: 0,
+            count: 0,
+        };
+        unsafe {
+            call!(FMOD_System_GetDSPBufferSize(
+                self.as_raw(),
+                &raw mut size.length,
+                &raw mut size.count,
+            ))?;
+        }
+        Ok(size)
+    }
+
+    /// The number of channels playing, and how many of them are real rather than virtual.
+    ///
+    /// # Errors
+    /// Fails if `FMOD_System_GetChannelsPlaying` does.
+    pub fn channels_playing(&self) -> Result<(c_int, c_int)> {
+        let (mut channels, mut real) = (0, 0);
+        unsafe {
+            call!(FMOD_System_GetChannelsPlaying(
+                self.as_raw(),
+                &raw mut channels,
+                &raw mut real,
+            ))?;
+        }
+        Ok((channels, real))
+    }
+}
+
+impl Drop for System {
+    fn drop(&mut self) {
+        // Releasing closes the system first. There's nothing to do if it fails.
+        let _ = unsafe { call!(FMOD_System_Release(self.as_raw())) };
+    }
+}
+
+/// Settings for a [`System`], which are applied before it's initialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct SystemBuilder {
+    max_channels: c_int,
+    flags: FMOD_INITFLAGS,
+    output: Option<FMOD_OUTPUTTYPE>,
+    software_format: Option<SoftwareFormat>,
+    dsp_buffer_size: Option<DspBufferSize>,
+}
+
+impl Default for SystemBuilder {
+    fn default() -> Self {
+        Self {
+            max_channels: 32,
+            flags: FMOD_INIT_NORMAL.cast_unsigned(),
+            output: None,
+            software_format: None,
+            dsp_buffer_size: None,
+        }
+    }
+}
+
+impl SystemBuilder {
+    /// Play at most this many channels at once, counting virtual ones.
+    pub fn max_channels(mut self, max_channels: c_int) -> Self {
+        self.max_channels = max_channels;
+        self
+    }
+
+    /// Initialize with these `FMOD_INIT_*` flags, instead of `FMOD_INIT_NORMAL`.
+    pub fn flags(mut self, flags: FMOD_INITFLAGS) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Mix to this output, instead of the one FMOD detects.
+    pub fn output(mut self, output: FMOD_OUTPUTTYPE) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Mix in this format, instead of the output's.
+    pub fn software_format(mut self, format: SoftwareFormat) -> Self {
+        self.software_format = Some(format);
+        self
+    }
+
+    /// Mix with buffers of this size, instead of the output's default.
+    pub fn dsp_buffer_size(mut self, size: DspBufferSize) -> Self {
+        self.dsp_buffer_size = Some(size);
+        self
+    }
+
+    /// Create and initialize the system.
+    ///
+    /// # Errors
+    /// Fails if any of the calls to set the system up does, in which case the system is
+    /// released.
+    ///
+    /// # Panics
+    /// Panics if FMOD reports creating the system without giving a handle to it.
+    pub fn build(self) -> Result<System> {
+        let mut raw = null_mut();
+        unsafe {
+            call!(FMOD_System_Create(
+                &raw mut raw,
+                FMOD_VERSION.cast_unsigned()
+            ))?;
+        }
+        let raw = NonNull::new(raw).expect("FMOD should create a system when it succeeds");
+        // From here on, dropping the system releases it if a call fails.
+        let system = System { raw };
+        let raw = system.as_raw();
+        unsafe {
+            if let Some(output) = self.output {
+                call!(FMOD_System_SetOutput(raw, output))?;
+            }
+            if let Some(format) = self.software_format {
+                call!(FMOD_System_SetSoftwareFormat(
+                    raw,
+                    format.sample_rate,
+                    format.speaker_mode,
+                    format.raw_speakers,
+                ))?;
+            }
+            if let Some(size) = self.dsp_buffer_size {
+                call!(FMOD_System_SetDSPBufferSize(raw, size.length, size.count))?;
+            }
+            call!(FMOD_System_Init(
+                raw,
+                self.max_channels,
+                self.flags,
+                null_mut()
+            ))?;
+        }
+        Ok(system)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fmod_sys::FMOD_RESULT;
+
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn builds() {
+        let system = System::builder()
+            .max_channels(64)
+            .output(FMOD_OUTPUTTYPE::FMOD_OUTPUTTYPE_NOSOUND)
+            .software_format(SoftwareFormat {
+                sample_rate: 44_100,
+                speaker_mode: FMOD_SPEAKERMODE::FMOD_SPEAKERMODE_STEREO,
+                raw_speakers: 0,
+            })
+            .dsp_buffer_size(DspBufferSize {
+                length: 512,
+                count: 4,
+            })
+            .build()
+            .unwrap();
+        system.update().unwrap();
+        assert_eq!(system.version().unwrap(), FMOD_VERSION.cast_unsigned());
+        assert_eq!(
+            system.output().unwrap(),
+            FMOD_OUTPUTTYPE::FMOD_OUTPUTTYPE_NOSOUND
+        );
+        assert_eq!(system.software_format().unwrap().sample_rate, 44_100);
+        assert_eq!(system.dsp_buffer_size().unwrap().count, 4);
+        assert_eq!(system.channels_playing().unwrap(), (0, 0));
+
+        let raw = system.as_raw();
+        drop(system);
+        assert_eq!(
+            unsafe { crate::ffi::FMOD_System_Update(raw) },
+            FMOD_RESULT::FMOD_ERR_INVALID_HANDLE
+        );
+    }
+
+    #[test]
+    fn failures() {
+        let error = System::builder()
+            .dsp_buffer_size(DspBufferSize {
+                length: 0,
+                count: 4,
+            })
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            Error::Fmod {
+                function: "FMOD_System_SetDSPBufferSize",
+                result: FMOD_RESULT::FMOD_ERR_INVALID_PARAM,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "`FMOD_System_SetDSPBufferSize` failed with {:?}",
+                FMOD_RESULT::FMOD_ERR_INVALID_PARAM
+            )
+        );
+        assert!(System::builder().max_channels(-1).build().is_err());
+    }
+}